@@ -2,7 +2,10 @@ use image::codecs::jpeg::JpegEncoder;
 use image::{DynamicImage, ImageFormat, ImageReader};
 use std::fs;
 use std::io::Cursor;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Outputs smaller than this are written straight to the destination instead of temp + rename.
+pub const DIRECT_WRITE_THRESHOLD: u64 = 1_024_000;
 
 /// Decode + resize + encode a single JPEG in memory, returning (source_size, compressed_size).
 pub fn sample_compress_in_memory(
@@ -48,13 +51,16 @@ pub fn encode_only(image: &DynamicImage, quality: u8) -> Option<u64> {
 }
 
 /// Compress a single file: decode → resize → encode → write (with optional EXIF preservation).
+/// Returns the output size and the write mode used (`"direct"` or `"atomic"`).
 pub fn compress_one_file(
     source: &Path,
     destination: &Path,
     resize_percent: f32,
     quality: u8,
     preserve_exif: bool,
-) -> Result<u64, String> {
+    verify_output: bool,
+    direct_write_threshold: u64,
+) -> Result<(u64, &'static str), String> {
     let original_bytes =
         fs::read(source).map_err(|e| format!("ファイルの読み込みに失敗しました: {}", e))?;
     let mut image = ImageReader::open(source)
@@ -90,10 +96,41 @@ pub fn compress_one_file(
             .map_err(|e| format!("出力先フォルダの作成に失敗しました: {}", e))?;
     }
 
-    // Use atomic write: write to temp file then rename
-    fs::write(destination, &output_bytes)
-        .map_err(|e| format!("ファイルの書き込みに失敗しました: {}", e))?;
-    Ok(output_bytes.len() as u64)
+    let write_mode = if (output_bytes.len() as u64) < direct_write_threshold {
+        fs::write(destination, &output_bytes)
+            .map_err(|e| format!("ファイルの書き込みに失敗しました: {}", e))?;
+        "direct"
+    } else {
+        // Atomic write: temp file next to the destination, then rename over it.
+        let temp = temp_path_for(destination);
+        fs::write(&temp, &output_bytes)
+            .map_err(|e| format!("ファイルの書き込みに失敗しました: {}", e))?;
+        if let Err(e) = fs::rename(&temp, destination) {
+            let _ = fs::remove_file(&temp);
+            return Err(format!("ファイルの書き込みに失敗しました: {}", e));
+        }
+        "atomic"
+    };
+
+    if verify_output {
+        // Header-only decode to catch truncated or malformed JPEG output.
+        let verified = ImageReader::open(destination)
+            .map_err(|e| e.to_string())
+            .and_then(|reader| reader.into_dimensions().map_err(|e| e.to_string()));
+        if let Err(e) = verified {
+            let _ = fs::remove_file(destination);
+            return Err(format!("出力ファイルの検証に失敗しました: {}", e));
+        }
+    }
+    Ok((output_bytes.len() as u64, write_mode))
+}
+
+fn temp_path_for(destination: &Path) -> PathBuf {
+    let name = destination
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    destination.with_file_name(format!(".{}.cfm-tmp", name))
 }
 
 fn parse_filter(s: &str) -> image::imageops::FilterType {
@@ -1,13 +1,18 @@
 mod codec;
 mod protocol;
 
-use protocol::{CompressBatchItem, CompressFileStatus, Request, ResizeBatchItem, Response};
+use protocol::{
+    CompressBatchItem, CompressFileStatus, Request, ResizeBatchItem, Response, SampleEstimateEntry,
+    SuggestIteration,
+};
 use rayon::prelude::*;
 use std::collections::HashMap;
+use std::fs;
 use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::time::Duration;
 
 fn main() {
     let stdout = Arc::new(Mutex::new(io::stdout()));
@@ -120,6 +125,10 @@ fn handle_request(
             resize_percent,
             quality,
             preserve_exif,
+            verify_output,
+            item_timeout_ms,
+            direct_write_threshold_bytes,
+            max_concurrent_decodes,
         } => {
             handle_compress_batch(
                 &id,
@@ -127,6 +136,10 @@ fn handle_request(
                 resize_percent,
                 quality,
                 preserve_exif,
+                verify_output,
+                item_timeout_ms,
+                direct_write_threshold_bytes.unwrap_or(codec::DIRECT_WRITE_THRESHOLD),
+                max_concurrent_decodes,
                 stdout,
                 cancel_flag,
             );
@@ -182,7 +195,7 @@ fn handle_sample_estimate(
     let total = samples.len();
     let done = AtomicUsize::new(0);
 
-    let results: Vec<(u64, u64)> = samples
+    let results: Vec<(&String, u64, u64)> = samples
         .par_iter()
         .filter_map(|path| {
             if cancel_flag.load(Ordering::Relaxed) {
@@ -197,30 +210,73 @@ fn handle_sample_estimate(
                     id: id.to_string(),
                     current,
                     total,
+                    sample: result.map(|(source_size, compressed_size)| SampleEstimateEntry {
+                        source: (*path).clone(),
+                        source_size,
+                        compressed_size,
+                    }),
                 },
             );
-            result
+            result.map(|(src, comp)| (*path, src, comp))
         })
         .collect();
 
     let (src_total, comp_total) = results
         .iter()
-        .fold((0u64, 0u64), |(s, c), &(src, comp)| (s + src, c + comp));
+        .fold((0u64, 0u64), |(s, c), &(_, src, comp)| (s + src, c + comp));
     let compression_ratio = if src_total == 0 {
         1.0
     } else {
         comp_total as f64 / src_total as f64
     };
+    let ratios: Vec<f64> = results
+        .iter()
+        .filter(|&&(_, src, _)| src > 0)
+        .map(|&(_, src, comp)| comp as f64 / src as f64)
+        .collect();
+    let confidence = ratio_confidence(&ratios);
+
+    send_response(
+        stdout,
+        &Response::SampleEstimateDetail {
+            id: id.to_string(),
+            samples: results
+                .iter()
+                .map(|&(path, source_size, compressed_size)| SampleEstimateEntry {
+                    source: path.clone(),
+                    source_size,
+                    compressed_size,
+                })
+                .collect(),
+        },
+    );
 
     send_response(
         stdout,
         &Response::SampleEstimateResult {
             id: id.to_string(),
             compression_ratio,
+            confidence,
         },
     );
 }
 
+/// `1 - cv` of the per-file ratios, where cv = std_dev / mean (clamped to 1).
+/// Fewer than two samples give no spread to measure, so they report full confidence.
+fn ratio_confidence(ratios: &[f64]) -> f64 {
+    if ratios.len() < 2 {
+        return 1.0;
+    }
+    let n = ratios.len() as f64;
+    let mean = ratios.iter().sum::<f64>() / n;
+    if mean <= 0.0 {
+        return 0.0;
+    }
+    let variance = ratios.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n;
+    let cv = variance.sqrt() / mean;
+    1.0 - cv.min(1.0)
+}
+
 fn handle_suggest_params(
     id: &str,
     files: &[String],
@@ -239,6 +295,7 @@ fn handle_suggest_params(
                 id: id.to_string(),
                 resize_percent: 100.0,
                 quality: quality_seed.clamp(1, 100),
+                iterations: Vec::new(),
             },
         );
         return;
@@ -284,6 +341,7 @@ fn handle_suggest_params(
                 id: id.to_string(),
                 resize_percent: 100.0,
                 quality,
+                iterations: Vec::new(),
             },
         );
         return;
@@ -292,6 +350,7 @@ fn handle_suggest_params(
     // Binary search for best resize_percent using cached decoded images
     let mut low: f32 = 10.0;
     let mut high: f32 = 100.0;
+    let mut iterations = Vec::with_capacity(5);
 
     for iteration in 0..5 {
         if cancel_flag.load(Ordering::Relaxed) {
@@ -300,6 +359,11 @@ fn handle_suggest_params(
         let mid = (low + high) / 2.0;
         let ratio = sample_ratio_from_decoded(&decoded, mid, quality);
         let estimated = (total_source_bytes as f64) * ratio;
+        iterations.push(SuggestIteration {
+            resize_percent: mid,
+            estimated_ratio: ratio,
+            estimated_bytes: estimated.round() as u64,
+        });
 
         send_response(
             stdout,
@@ -307,6 +371,7 @@ fn handle_suggest_params(
                 id: id.to_string(),
                 current: iteration + 1,
                 total: 5,
+                sample: None,
             },
         );
 
@@ -337,6 +402,7 @@ fn handle_suggest_params(
             id: id.to_string(),
             resize_percent: low.round().max(10.0),
             quality: final_quality,
+            iterations,
         },
     );
 }
@@ -373,15 +439,168 @@ fn sample_ratio_from_decoded(
     }
 }
 
+static TIMEOUT_TEMP_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+/// Private output path for a timed compress; only renamed onto the destination in time.
+fn timeout_temp_path_for(destination: &Path) -> PathBuf {
+    let name = destination
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let seq = TIMEOUT_TEMP_SEQ.fetch_add(1, Ordering::Relaxed);
+    destination.with_file_name(format!(
+        ".{}.cfm-timeout-{}-{}",
+        name,
+        std::process::id(),
+        seq
+    ))
+}
+
+/// Run `compress_one_file` on its own thread and give up waiting after `timeout`.
+/// A timed-out thread cannot be killed, so it writes to a temp path that is deleted
+/// once the wait was abandoned; `permit` is held until the thread itself finishes.
+#[allow(clippy::too_many_arguments)]
+fn compress_with_timeout(
+    source: PathBuf,
+    destination: PathBuf,
+    resize_percent: f32,
+    quality: u8,
+    preserve_exif: bool,
+    verify_output: bool,
+    direct_write_threshold: u64,
+    timeout: Option<Duration>,
+    permit: Option<SemaphorePermit>,
+) -> Result<(u64, &'static str), String> {
+    let Some(timeout) = timeout else {
+        let result = codec::compress_one_file(
+            &source,
+            &destination,
+            resize_percent,
+            quality,
+            preserve_exif,
+            verify_output,
+            direct_write_threshold,
+        );
+        drop(permit);
+        return result;
+    };
+    let temp = timeout_temp_path_for(&destination);
+    let thread_temp = temp.clone();
+    // Set under the lock by the waiter when it gives up; the thread checks it under the
+    // same lock before handing over its result.
+    let abandoned = Arc::new(Mutex::new(false));
+    let thread_abandoned = Arc::clone(&abandoned);
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let result = codec::compress_one_file(
+            &source,
+            &thread_temp,
+            resize_percent,
+            quality,
+            preserve_exif,
+            verify_output,
+            direct_write_threshold,
+        );
+        drop(permit);
+        let abandoned = thread_abandoned.lock().unwrap_or_else(|e| e.into_inner());
+        if *abandoned {
+            let _ = fs::remove_file(&thread_temp);
+        } else {
+            let _ = tx.send(result);
+        }
+    });
+    let result = match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            let mut abandoned = abandoned.lock().unwrap_or_else(|e| e.into_inner());
+            match rx.try_recv() {
+                Ok(result) => result,
+                Err(_) => {
+                    *abandoned = true;
+                    return Err("タイムアウト".to_string());
+                }
+            }
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            let _ = fs::remove_file(&temp);
+            return Err("圧縮処理が異常終了しました".to_string());
+        }
+    };
+    match result {
+        Ok(done) => {
+            if let Err(e) = fs::rename(&temp, &destination) {
+                let _ = fs::remove_file(&temp);
+                return Err(format!("ファイルの書き込みに失敗しました: {}", e));
+            }
+            Ok(done)
+        }
+        Err(error) => {
+            let _ = fs::remove_file(&temp);
+            Err(error)
+        }
+    }
+}
+
+/// Counting semaphore bounding how many images are decoded at once.
+struct Semaphore {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+struct SemaphorePermit {
+    semaphore: Arc<Semaphore>,
+}
+
+impl Semaphore {
+    fn new(permits: usize) -> Self {
+        Self {
+            available: Mutex::new(permits),
+            released: Condvar::new(),
+        }
+    }
+
+    fn acquire(self: &Arc<Self>) -> SemaphorePermit {
+        let mut available = self.available.lock().unwrap_or_else(|e| e.into_inner());
+        while *available == 0 {
+            available = self
+                .released
+                .wait(available)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *available -= 1;
+        SemaphorePermit {
+            semaphore: Arc::clone(self),
+        }
+    }
+}
+
+impl Drop for SemaphorePermit {
+    fn drop(&mut self) {
+        let mut available = self
+            .semaphore
+            .available
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *available += 1;
+        self.semaphore.released.notify_one();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn handle_compress_batch(
     id: &str,
     items: &[CompressBatchItem],
     resize_percent: f32,
     quality: u8,
     preserve_exif: bool,
+    verify_output: bool,
+    item_timeout_ms: Option<u64>,
+    direct_write_threshold: u64,
+    max_concurrent_decodes: Option<usize>,
     stdout: &Arc<Mutex<io::Stdout>>,
     cancel_flag: &Arc<AtomicBool>,
 ) {
+    let semaphore = max_concurrent_decodes.map(|permits| Arc::new(Semaphore::new(permits.max(1))));
     let succeeded = AtomicUsize::new(0);
     let failed = AtomicUsize::new(0);
     let skipped = AtomicUsize::new(0);
@@ -405,6 +624,7 @@ fn handle_compress_batch(
                     } else {
                         Some("スキップ".to_string())
                     },
+                    write_mode: None,
                 },
             );
             return;
@@ -413,9 +633,23 @@ fn handle_compress_batch(
         let source = PathBuf::from(&item.source);
         let destination = PathBuf::from(&item.destination);
 
-        match codec::compress_one_file(&source, &destination, resize_percent, quality, preserve_exif)
-        {
-            Ok(size) => {
+        let timeout = item
+            .timeout_ms
+            .or(item_timeout_ms)
+            .map(Duration::from_millis);
+        let permit = semaphore.as_ref().map(|semaphore| semaphore.acquire());
+        match compress_with_timeout(
+            source,
+            destination,
+            resize_percent,
+            quality,
+            preserve_exif,
+            verify_output,
+            direct_write_threshold,
+            timeout,
+            permit,
+        ) {
+            Ok((size, write_mode)) => {
                 succeeded.fetch_add(1, Ordering::Relaxed);
                 send_response(
                     stdout_ref,
@@ -425,7 +659,8 @@ fn handle_compress_batch(
                         destination: item.destination.clone(),
                         status: CompressFileStatus::Succeeded,
                         output_size: Some(size),
-                        reason: None,
+                        reason: verify_output.then(|| "出力を検証しました (verified)".to_string()),
+                        write_mode: Some(write_mode.to_string()),
                     },
                 );
             }
@@ -440,6 +675,7 @@ fn handle_compress_batch(
                         status: CompressFileStatus::Failed,
                         output_size: None,
                         reason: Some(msg),
+                        write_mode: None,
                     },
                 );
             }
@@ -26,6 +26,17 @@ pub enum Request {
         resize_percent: f32,
         quality: u8,
         preserve_exif: bool,
+        #[serde(default)]
+        verify_output: bool,
+        /// Default per-file timeout; items may override it with `timeout_ms`.
+        #[serde(default)]
+        item_timeout_ms: Option<u64>,
+        /// Overrides `codec::DIRECT_WRITE_THRESHOLD`.
+        #[serde(default)]
+        direct_write_threshold_bytes: Option<u64>,
+        /// Upper bound on files decoded at the same time; `None` means one per worker thread.
+        #[serde(default)]
+        max_concurrent_decodes: Option<usize>,
     },
     ResizeBatch {
         id: String,
@@ -51,6 +62,8 @@ pub struct CompressBatchItem {
     pub source: String,
     pub destination: String,
     pub skip: bool,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -82,15 +95,24 @@ pub enum Response {
         id: String,
         current: usize,
         total: usize,
+        /// Sizes of the sample that just finished, so the host can show a running ratio.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sample: Option<SampleEstimateEntry>,
+    },
+    SampleEstimateDetail {
+        id: String,
+        samples: Vec<SampleEstimateEntry>,
     },
     SampleEstimateResult {
         id: String,
         compression_ratio: f64,
+        confidence: f64,
     },
     SuggestParamsResult {
         id: String,
         resize_percent: f32,
         quality: u8,
+        iterations: Vec<SuggestIteration>,
     },
     CompressFileDone {
         id: String,
@@ -99,6 +121,7 @@ pub enum Response {
         status: CompressFileStatus,
         output_size: Option<u64>,
         reason: Option<String>,
+        write_mode: Option<String>,
     },
     CompressBatchDone {
         id: String,
@@ -126,6 +149,20 @@ pub enum Response {
     },
 }
 
+#[derive(Debug, Serialize)]
+pub struct SuggestIteration {
+    pub resize_percent: f32,
+    pub estimated_ratio: f64,
+    pub estimated_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SampleEstimateEntry {
+    pub source: String,
+    pub source_size: u64,
+    pub compressed_size: u64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum CompressFileStatus {
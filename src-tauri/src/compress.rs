@@ -1,25 +1,49 @@
 use crate::error::AppError;
-use crate::file_collect::{collect_targets_with_extensions, JPEG_ALLOWED_EXTENSIONS};
+use crate::file_collect::{
+    collect_targets_with_extensions, count_by_extension, JPEG_ALLOWED_EXTENSIONS,
+};
+use crate::fs_atomic::dry_run_check;
 use crate::model::{
-    CollisionPolicy, CompressCollectInfoResponse, CompressEstimateResponse,
+    AspectRatioFilter, CollisionPolicy, CompressCollectInfoResponse, CompressEstimateResponse,
     CompressExecuteDetail, CompressExecuteResponse, CompressPreviewItem, CompressPreviewRequest,
     CompressPreviewResponse, EstimateProgressEvent, ExecuteStatus, OperationProgressEvent,
-    PreviewStatus,
+    OperationSummary, PerFileEstimate, PreviewStatus, SuggestIteration,
+};
+use crate::path_norm::{
+    relative_or_portable_absolute, to_windows_extended, validate_output_suffix,
 };
-use crate::path_norm::relative_or_portable_absolute;
+use crate::progress_eta::EtaTracker;
+use crate::rename::sanitize_file_name;
+use crate::reorient::read_orientation;
+use crate::settings;
 use crate::worker_bridge::{self, BatchProgress, CompressBatchItemMsg};
 use chrono::Local;
-use std::collections::HashSet;
+use image::codecs::jpeg::JpegEncoder;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 
+const ESTIMATE_SAMPLES: usize = 10;
+const LOW_CONFIDENCE_THRESHOLD: f64 = 0.7;
+/// Coefficient of variation of source sizes above which sampling is stratified.
+const STRATIFY_SIZE_CV: f64 = 0.5;
+const STRATIFIED_MAX_SAMPLES: usize = 50;
+/// Used when `AppSettings.compress_max_concurrent_decodes` is unset.
+const DEFAULT_MAX_CONCURRENT_DECODES: usize = 4;
+/// A source that shrinks less than this when re-encoded at q95 is treated as already compressed.
+const ALREADY_COMPRESSED_RATIO: f64 = 1.05;
+
 #[derive(Debug, Clone)]
 struct PlannedCompress {
     source: PathBuf,
     destination: PathBuf,
     source_size: u64,
     estimated_size: u64,
+    source_dimensions: Option<(u32, u32)>,
+    output_dimensions: Option<(u32, u32)>,
+    per_file_target_kb: Option<u64>,
     status: PreviewStatus,
     reason: Option<String>,
 }
@@ -28,16 +52,22 @@ pub fn collect_info(
     input_paths: &[String],
     include_subfolders: bool,
 ) -> Result<CompressCollectInfoResponse, AppError> {
-    let collect = collect_targets_with_extensions(input_paths, include_subfolders, JPEG_ALLOWED_EXTENSIONS)
-        .map_err(AppError::InvalidRequest)?;
+    let collect = collect_targets_with_extensions(
+        input_paths,
+        include_subfolders,
+        JPEG_ALLOWED_EXTENSIONS,
+        None,
+    )
+    .map_err(AppError::InvalidRequest)?;
     let total_size: u64 = collect
         .files
         .iter()
-        .map(|path| fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+        .map(|path| fs::metadata(to_windows_extended(path)).map(|m| m.len()).unwrap_or(0))
         .sum();
     Ok(CompressCollectInfoResponse {
         file_count: collect.files.len(),
         total_size,
+        by_extension: count_by_extension(&collect.files),
     })
 }
 
@@ -50,19 +80,28 @@ pub fn estimate(
     is_cancelled: impl Fn() -> bool + Sync,
     on_progress: impl Fn(EstimateProgressEvent) + Sync,
 ) -> Result<CompressEstimateResponse, AppError> {
-    let collect =
-        collect_targets_with_extensions(input_paths, include_subfolders, JPEG_ALLOWED_EXTENSIONS)
-            .map_err(AppError::InvalidRequest)?;
-    let total_source_size: u64 = collect
+    let collect = collect_targets_with_extensions(
+        input_paths,
+        include_subfolders,
+        JPEG_ALLOWED_EXTENSIONS,
+        None,
+    )
+    .map_err(AppError::InvalidRequest)?;
+    let source_sizes: Vec<u64> = collect
         .files
         .iter()
-        .map(|path| fs::metadata(path).map(|m| m.len()).unwrap_or(0))
-        .sum();
+        .map(|path| fs::metadata(to_windows_extended(path)).map(|m| m.len()).unwrap_or(0))
+        .collect();
+    let total_source_size: u64 = source_sizes.iter().sum();
     if collect.files.is_empty() || total_source_size == 0 {
         return Ok(CompressEstimateResponse {
             file_count: collect.files.len(),
             total_source_size,
             estimated_total_size: 0,
+            per_file_estimates: None,
+            estimate_confidence: None,
+            samples_used: 0,
+            sampling_strategy: "uniform".to_string(),
         });
     }
 
@@ -72,29 +111,120 @@ pub fn estimate(
         .map(|p| p.to_string_lossy().to_string())
         .collect();
 
-    let ratio = worker_bridge::sample_estimate(
-        app,
-        file_strings,
-        resize_percent.clamp(1.0, 100.0),
-        quality.clamp(1, 100),
-        10,
-        &is_cancelled,
-        |current, total| {
-            on_progress(EstimateProgressEvent { current, total });
-        },
-    )
-    .map_err(|e| AppError::Io(e))?;
+    let run_sample = |files: Vec<String>, max_samples: usize| {
+        worker_bridge::sample_estimate(
+            app,
+            files,
+            resize_percent.clamp(1.0, 100.0),
+            quality.clamp(1, 100),
+            max_samples,
+            &is_cancelled,
+            |current, total, partial_ratio| {
+                on_progress(EstimateProgressEvent {
+                    current,
+                    total,
+                    partial_ratio,
+                });
+            },
+        )
+    };
+    let stratified = size_coefficient_of_variation(&source_sizes) > STRATIFY_SIZE_CV;
+    let sampled = if stratified {
+        // Mixed phone snaps and studio shots: make sure every size band is represented.
+        let max_samples = STRATIFIED_MAX_SAMPLES.min(file_strings.len());
+        let selected: Vec<String> = stratified_sample(&source_sizes, max_samples)
+            .into_iter()
+            .map(|index| file_strings[index].clone())
+            .collect();
+        let count = selected.len();
+        run_sample(selected, count)?
+    } else {
+        let mut sampled = run_sample(file_strings.clone(), ESTIMATE_SAMPLES)?;
+        // High variance across samples: retry once with a denser sample if there are files to spare.
+        if sampled.confidence < LOW_CONFIDENCE_THRESHOLD
+            && file_strings.len() > ESTIMATE_SAMPLES
+            && !is_cancelled()
+        {
+            sampled = run_sample(file_strings.clone(), ESTIMATE_SAMPLES * 2)?;
+        }
+        sampled
+    };
 
-    let estimated_total_size = ((total_source_size as f64) * ratio).round() as u64;
+    let estimated_total_size =
+        ((total_source_size as f64) * sampled.compression_ratio).round() as u64;
+    let sampled_sizes: HashMap<&str, (u64, u64)> = sampled
+        .samples
+        .iter()
+        .map(|entry| {
+            (
+                entry.source.as_str(),
+                (entry.source_size, entry.compressed_size),
+            )
+        })
+        .collect();
+    let per_file_estimates = file_strings
+        .iter()
+        .zip(source_sizes)
+        .map(|(path, source_size)| match sampled_sizes.get(path.as_str()) {
+            Some(&(sampled_source, compressed)) => PerFileEstimate {
+                path: path.clone(),
+                source_size: sampled_source,
+                estimated_size: Some(compressed),
+            },
+            None => PerFileEstimate {
+                path: path.clone(),
+                source_size,
+                estimated_size: None,
+            },
+        })
+        .collect();
     Ok(CompressEstimateResponse {
         file_count: collect.files.len(),
         total_source_size,
         estimated_total_size,
+        per_file_estimates: Some(per_file_estimates),
+        estimate_confidence: Some(sampled.confidence),
+        samples_used: sampled.samples.len(),
+        sampling_strategy: if stratified { "stratified" } else { "uniform" }.to_string(),
     })
 }
 
+fn size_coefficient_of_variation(sizes: &[u64]) -> f64 {
+    if sizes.is_empty() {
+        return 0.0;
+    }
+    let count = sizes.len() as f64;
+    let mean = sizes.iter().map(|&size| size as f64).sum::<f64>() / count;
+    if mean == 0.0 {
+        return 0.0;
+    }
+    let variance = sizes
+        .iter()
+        .map(|&size| (size as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    variance.sqrt() / mean
+}
+
+/// Indices picked evenly from each size quartile, proportional to the quartile's share.
+fn stratified_sample(sizes: &[u64], max_samples: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&index| sizes[index]);
+    let quartile_len = order.len().div_ceil(4).max(1);
+    let mut selected = Vec::with_capacity(max_samples);
+    for quartile in order.chunks(quartile_len) {
+        let take = ((max_samples * quartile.len()) as f64 / order.len() as f64)
+            .round()
+            .clamp(1.0, quartile.len() as f64) as usize;
+        let step = quartile.len() as f64 / take as f64;
+        selected.extend((0..take).map(|i| quartile[(i as f64 * step) as usize]));
+    }
+    selected.sort_unstable();
+    selected
+}
+
 pub fn preview(request: &CompressPreviewRequest, app: &AppHandle) -> Result<CompressPreviewResponse, AppError> {
-    let state = build_plan(request, app)?;
+    let state = build_plan(request, app, &|| false)?;
     Ok(preview_response_from_state(&state))
 }
 
@@ -108,8 +238,13 @@ where
     FCancel: Fn() -> bool,
     FProgress: FnMut(OperationProgressEvent),
 {
-    let state = build_plan(request, app)?;
-    fs::create_dir_all(&state.output_dir)?;
+    let state = build_plan(request, app, &is_cancelled)?;
+    if request.dry_run.unwrap_or(false) {
+        return Ok(execute_dry_run(&state, is_cancelled, report_progress));
+    }
+    if !state.keep_original {
+        fs::create_dir_all(&state.output_dir)?;
+    }
 
     let total = state.plan.len();
 
@@ -121,6 +256,7 @@ where
             source: item.source.to_string_lossy().to_string(),
             destination: item.destination.to_string_lossy().to_string(),
             skip: matches!(item.status, PreviewStatus::Skipped),
+            timeout_ms: request.item_timeout_ms,
         })
         .collect();
 
@@ -130,13 +266,23 @@ where
     let mut skipped = 0usize;
     let mut processed = 0usize;
     let mut canceled = false;
+    let mut eta = EtaTracker::new();
+    let total_bytes = state.total_bytes;
+    let mut bytes_processed = 0u64;
 
+    let max_concurrent_decodes = settings::load_settings(app)
+        .ok()
+        .and_then(|settings| settings.compress_max_concurrent_decodes)
+        .unwrap_or(DEFAULT_MAX_CONCURRENT_DECODES);
     let result = worker_bridge::compress_batch(
         app,
         items,
         state.effective_resize_percent,
         state.effective_quality,
         request.preserve_exif,
+        request.verify_output.unwrap_or(false),
+        request.use_direct_write_threshold_bytes,
+        Some(max_concurrent_decodes),
         || {
             if is_cancelled() {
                 true
@@ -146,6 +292,7 @@ where
         },
         |progress: BatchProgress| {
             processed += 1;
+            bytes_processed += progress.output_size.unwrap_or(0);
             let status = match progress.status.as_str() {
                 "succeeded" => {
                     succeeded += 1;
@@ -171,6 +318,7 @@ where
                 status,
                 output_size: progress.output_size,
                 reason: progress.reason,
+                write_mode: progress.write_mode.unwrap_or_default(),
             });
 
             report_progress(OperationProgressEvent {
@@ -183,10 +331,12 @@ where
                 current_path: Some(progress.source),
                 done: false,
                 canceled,
+                eta_seconds: eta.record(total.saturating_sub(processed)),
+                bytes_processed,
+                total_bytes,
             });
         },
-    )
-    .map_err(|e| AppError::Io(e))?;
+    )?;
 
     // Use the batch result totals (more accurate for race conditions)
     succeeded = result.succeeded;
@@ -203,32 +353,193 @@ where
         current_path: None,
         done: true,
         canceled,
+        eta_seconds: None,
+        bytes_processed,
+        total_bytes,
     });
 
     Ok(CompressExecuteResponse {
-        output_dir: state.output_dir.to_string_lossy().to_string(),
+        output_dir: output_dir_label(&state),
         effective_resize_percent: state.effective_resize_percent,
         effective_quality: state.effective_quality,
+        summary: OperationSummary::new(
+            "compress",
+            succeeded + failed + skipped,
+            succeeded,
+            failed,
+            skipped,
+            None,
+            canceled,
+        )
+        .with_warnings(state.warnings),
+        details,
+    })
+}
+
+/// Drops input paths that no longer exist so a replayed batch still runs; returns them.
+pub fn take_missing_inputs(request: &mut CompressPreviewRequest) -> Vec<String> {
+    let (present, missing) = request
+        .input_paths
+        .drain(..)
+        .partition(|path| Path::new(path.trim()).exists());
+    request.input_paths = present;
+    missing
+}
+
+/// Reports inputs removed by `take_missing_inputs` as skipped.
+pub fn append_missing_inputs(response: &mut CompressExecuteResponse, missing: &[String]) {
+    for path in missing {
+        response.details.push(CompressExecuteDetail {
+            source_path: path.clone(),
+            destination_path: String::new(),
+            status: ExecuteStatus::Skipped,
+            output_size: None,
+            reason: Some("入力ファイルが見つかりません".to_string()),
+            write_mode: String::new(),
+        });
+    }
+    response.summary.processed += missing.len();
+    response.summary.skipped += missing.len();
+}
+
+/// Response for a replay whose inputs are all gone: nothing runs, every input is skipped.
+pub fn missing_inputs_response(
+    request: &CompressPreviewRequest,
+    missing: &[String],
+) -> CompressExecuteResponse {
+    let mut response = CompressExecuteResponse {
+        output_dir: request.output_dir.clone().unwrap_or_default(),
+        effective_resize_percent: request.resize_percent.clamp(1.0, 100.0),
+        effective_quality: request.quality.clamp(1, 100),
+        summary: OperationSummary::new("compress", 0, 0, 0, 0, None, false),
+        details: Vec::with_capacity(missing.len()),
+    };
+    append_missing_inputs(&mut response, missing);
+    response
+}
+
+/// Walks the plan like `execute` but never hands anything to the worker.
+fn execute_dry_run<FCancel, FProgress>(
+    state: &CompressPlanState,
+    is_cancelled: FCancel,
+    mut report_progress: FProgress,
+) -> CompressExecuteResponse
+where
+    FCancel: Fn() -> bool,
+    FProgress: FnMut(OperationProgressEvent),
+{
+    let total = state.plan.len();
+    let mut details = Vec::with_capacity(total);
+    let mut succeeded = 0usize;
+    let mut failed = 0usize;
+    let mut skipped = 0usize;
+    let mut canceled = false;
+    let mut eta = EtaTracker::new();
+    let total_bytes = state.total_bytes;
+    let mut bytes_processed = 0u64;
+
+    for item in &state.plan {
+        if !canceled && is_cancelled() {
+            canceled = true;
+        }
+        let (status, reason) = if canceled {
+            skipped += 1;
+            (ExecuteStatus::Skipped, Some("キャンセルされました".to_string()))
+        } else if matches!(item.status, PreviewStatus::Skipped) {
+            skipped += 1;
+            (ExecuteStatus::Skipped, item.reason.clone())
+        } else {
+            match dry_run_check(&item.source) {
+                Ok(note) => {
+                    succeeded += 1;
+                    bytes_processed += item.source_size;
+                    (ExecuteStatus::Succeeded, note)
+                }
+                Err(error) => {
+                    failed += 1;
+                    (ExecuteStatus::Failed, Some(error))
+                }
+            }
+        };
+        details.push(CompressExecuteDetail {
+            source_path: item.source.to_string_lossy().to_string(),
+            destination_path: item.destination.to_string_lossy().to_string(),
+            status,
+            output_size: None,
+            reason,
+            write_mode: String::new(),
+        });
+        report_progress(OperationProgressEvent {
+            operation: "compress".to_string(),
+            processed: succeeded + failed + skipped,
+            total,
+            succeeded,
+            failed,
+            skipped,
+            current_path: Some(item.source.to_string_lossy().to_string()),
+            done: false,
+            canceled,
+            eta_seconds: eta.record(total.saturating_sub(succeeded + failed + skipped)),
+            bytes_processed,
+            total_bytes,
+        });
+    }
+
+    report_progress(OperationProgressEvent {
+        operation: "compress".to_string(),
         processed: succeeded + failed + skipped,
+        total,
         succeeded,
         failed,
         skipped,
+        current_path: None,
+        done: true,
+        canceled,
+        eta_seconds: None,
+        bytes_processed,
+        total_bytes,
+    });
+
+    CompressExecuteResponse {
+        output_dir: output_dir_label(state),
+        effective_resize_percent: state.effective_resize_percent,
+        effective_quality: state.effective_quality,
+        summary: OperationSummary::new(
+            "compress",
+            succeeded + failed + skipped,
+            succeeded,
+            failed,
+            skipped,
+            None,
+            canceled,
+        )
+        .with_warnings(state.warnings.clone()),
         details,
-    })
+    }
 }
 
 #[derive(Debug)]
 struct CompressPlanState {
     output_dir: PathBuf,
+    keep_original: bool,
     effective_resize_percent: f32,
     effective_quality: u8,
     target_size_kb: Option<u64>,
     tolerance_percent: f32,
     plan: Vec<PlannedCompress>,
-    warnings: usize,
+    tolerance_warnings: usize,
+    filtered_by_size: usize,
+    filtered_by_aspect: usize,
+    total_bytes: u64,
+    suggest_iterations: Option<Vec<SuggestIteration>>,
+    warnings: Vec<String>,
 }
 
-fn build_plan(request: &CompressPreviewRequest, app: &AppHandle) -> Result<CompressPlanState, AppError> {
+fn build_plan(
+    request: &CompressPreviewRequest,
+    app: &AppHandle,
+    is_cancelled: &dyn Fn() -> bool,
+) -> Result<CompressPlanState, AppError> {
     let resize_percent = request.resize_percent.clamp(1.0, 100.0);
     let quality = request.quality.clamp(1, 100);
     let tolerance_percent = request.tolerance_percent.unwrap_or(10.0).max(0.0);
@@ -237,6 +548,7 @@ fn build_plan(request: &CompressPreviewRequest, app: &AppHandle) -> Result<Compr
         &request.input_paths,
         request.include_subfolders,
         JPEG_ALLOWED_EXTENSIONS,
+        request.max_files,
     )
     .map_err(AppError::InvalidRequest)?;
     if collect.files.is_empty() {
@@ -251,7 +563,9 @@ fn build_plan(request: &CompressPreviewRequest, app: &AppHandle) -> Result<Compr
         return Err(AppError::InvalidRequest(msg));
     }
 
-    if request.output_dir.as_deref().is_none()
+    let keep_original = request.keep_original.unwrap_or(false);
+    if !keep_original
+        && request.output_dir.as_deref().is_none()
         && !collect.single_input_root
         && collect.files.len() > 1
     {
@@ -260,67 +574,197 @@ fn build_plan(request: &CompressPreviewRequest, app: &AppHandle) -> Result<Compr
         ));
     }
 
-    let output_dir = resolve_output_dir(
-        collect.input_root.as_deref(),
-        request.output_dir.as_deref(),
-        "_compressed_",
-    )?;
+    if let Some(suffix) = request.output_suffix.as_deref() {
+        validate_output_suffix(suffix).map_err(AppError::InvalidRequest)?;
+    }
+    let output_dir = if keep_original {
+        collect.input_root.clone().unwrap_or_default()
+    } else {
+        resolve_output_dir(
+            collect.input_root.as_deref(),
+            request.output_dir.as_deref(),
+            request.output_suffix.as_deref().unwrap_or("_compressed_"),
+        )?
+    };
+    let destination_for = |source: &Path| {
+        if keep_original {
+            keep_original_destination(source)
+        } else {
+            output_dir.join(relative_or_portable_absolute(
+                source,
+                collect.input_root.as_deref(),
+            ))
+        }
+    };
 
-    let (effective_resize_percent, effective_quality) = match request.target_size_kb {
+    let total_source: u64 = if request.target_size_kb.is_some() {
+        collect
+            .files
+            .iter()
+            .map(|path| fs::metadata(to_windows_extended(path)).map(|m| m.len()).unwrap_or(0))
+            .sum()
+    } else {
+        0
+    };
+    let (effective_resize_percent, effective_quality, suggest_iterations) = match request
+        .target_size_kb
+    {
         Some(total_target_kb) => {
-            let total_source: u64 = collect
-                .files
-                .iter()
-                .map(|path| fs::metadata(path).map(|m| m.len()).unwrap_or(0))
-                .sum();
             let file_strings: Vec<String> = collect
                 .files
                 .iter()
                 .map(|p| p.to_string_lossy().to_string())
                 .collect();
             let target_bytes = (total_target_kb as u64) * 1024;
-            worker_bridge::suggest_params(
+            match worker_bridge::suggest_params(
                 app,
                 file_strings,
                 total_source,
                 target_bytes,
                 quality,
                 5,
-            )
-            .unwrap_or((resize_percent, quality))
+                is_cancelled,
+            ) {
+                Ok(params) => (
+                    params.resize_percent,
+                    params.quality,
+                    Some(
+                        params
+                            .iterations
+                            .into_iter()
+                            .map(|step| SuggestIteration {
+                                resize_percent: step.resize_percent,
+                                estimated_ratio: step.estimated_ratio,
+                                estimated_bytes: step.estimated_bytes,
+                            })
+                            .collect(),
+                    ),
+                ),
+                Err(_) if is_cancelled() => return Err(AppError::Cancelled),
+                Err(_) => (resize_percent, quality, None),
+            }
         }
-        None => (resize_percent, quality),
+        None => (resize_percent, quality, None),
     };
 
     let mut plan = Vec::with_capacity(collect.files.len());
-    let mut warnings = 0usize;
+    let mut tolerance_warnings = 0usize;
+    let mut filtered_by_size = 0usize;
+    let mut filtered_by_aspect = 0usize;
     let mut used_destinations: HashSet<String> = HashSet::new();
+    // Outputs land beside the sources, so no output may replace another input.
+    let source_keys: HashSet<String> = if keep_original {
+        collect
+            .files
+            .iter()
+            .map(|source| source.to_string_lossy().to_ascii_lowercase())
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    used_destinations.extend(source_keys.iter().cloned());
+    let min_source_bytes = request.min_source_size_kb.map(|kb| kb.saturating_mul(1024));
+    let already_compressed: HashSet<PathBuf> = if request.skip_if_compressed.unwrap_or(false) {
+        collect
+            .files
+            .par_iter()
+            .filter(|source| is_likely_already_compressed(source))
+            .cloned()
+            .collect()
+    } else {
+        HashSet::new()
+    };
 
     for source in &collect.files {
-        let source_size = fs::metadata(source).map(|m| m.len()).unwrap_or(0);
+        let source_size = fs::metadata(to_windows_extended(source)).map(|m| m.len()).unwrap_or(0);
+        let per_file_target_kb = request
+            .target_size_kb
+            .map(|total_kb| weighted_target_bytes(total_kb, source_size, total_source) / 1024);
+        if min_source_bytes.is_some_and(|min| source_size < min) {
+            filtered_by_size += 1;
+            plan.push(PlannedCompress {
+                source: source.clone(),
+                destination: destination_for(source),
+                source_size,
+                estimated_size: source_size,
+                source_dimensions: None,
+                output_dimensions: None,
+                per_file_target_kb,
+                status: PreviewStatus::Skipped,
+                reason: Some("ソースファイルが小さすぎます".to_string()),
+            });
+            continue;
+        }
+        if already_compressed.contains(source) {
+            plan.push(PlannedCompress {
+                source: source.clone(),
+                destination: destination_for(source),
+                source_size,
+                estimated_size: source_size,
+                source_dimensions: None,
+                output_dimensions: None,
+                per_file_target_kb,
+                status: PreviewStatus::Skipped,
+                reason: Some("すでに圧縮済みと判断されました".to_string()),
+            });
+            continue;
+        }
         let estimated_size =
             estimate_size(source_size, effective_resize_percent, effective_quality);
-        let relative = relative_or_portable_absolute(source, collect.input_root.as_deref());
-        let base_destination = output_dir.join(relative);
+        let source_dimensions = imagesize::size(to_windows_extended(source))
+            .ok()
+            .map(|size| (size.width as u32, size.height as u32));
+        if !matches_aspect_ratio(
+            request.aspect_ratio_filter.as_ref(),
+            displayed_dimensions(
+                source,
+                request.aspect_ratio_filter.as_ref(),
+                source_dimensions,
+            ),
+        ) {
+            filtered_by_aspect += 1;
+            plan.push(PlannedCompress {
+                source: source.clone(),
+                destination: destination_for(source),
+                source_size,
+                estimated_size: source_size,
+                source_dimensions,
+                output_dimensions: None,
+                per_file_target_kb,
+                status: PreviewStatus::Skipped,
+                reason: Some("アスペクト比フィルターに不一致".to_string()),
+            });
+            continue;
+        }
+        let output_dimensions = source_dimensions
+            .map(|(width, height)| resized_dimensions(width, height, effective_resize_percent));
+        let base_destination = destination_for(source);
 
         let (status, reason, destination) = resolve_destination_for_policy(
             &base_destination,
+            source,
             &mut used_destinations,
             request.conflict_policy.clone(),
         );
+        let (status, reason) =
+            if source_keys.contains(&destination.to_string_lossy().to_ascii_lowercase()) {
+                (
+                    PreviewStatus::Skipped,
+                    Some("出力先が他の入力ファイルと重複するためスキップしました".to_string()),
+                )
+            } else {
+                (status, reason)
+            };
 
-        let per_file_target_kb = request.target_size_kb.map(|total_kb| {
-            let count = collect.files.len() as u64;
-            if count == 0 { total_kb } else { total_kb / count }
-        });
         let warning_reason = tolerance_warning(
             source_size,
+            total_source,
             estimated_size,
-            per_file_target_kb,
+            request.target_size_kb,
             tolerance_percent,
         );
         if warning_reason.is_some() {
-            warnings += 1;
+            tolerance_warnings += 1;
         }
         let reason = match (reason, warning_reason) {
             (Some(a), Some(b)) => Some(format!("{}; {}", a, b)),
@@ -334,22 +778,81 @@ fn build_plan(request: &CompressPreviewRequest, app: &AppHandle) -> Result<Compr
             destination,
             source_size,
             estimated_size,
+            source_dimensions,
+            output_dimensions,
+            per_file_target_kb,
             status,
             reason,
         });
     }
 
+    let total_bytes = plan
+        .iter()
+        .filter(|item| matches!(item.status, PreviewStatus::Ready))
+        .map(|item| item.source_size)
+        .sum();
     Ok(CompressPlanState {
         output_dir,
+        keep_original,
         effective_resize_percent,
         effective_quality,
         target_size_kb: request.target_size_kb,
         tolerance_percent,
         plan,
-        warnings,
+        tolerance_warnings,
+        filtered_by_size,
+        filtered_by_aspect,
+        total_bytes,
+        suggest_iterations,
+        warnings: collect.warnings(),
     })
 }
 
+/// Stored dimensions as shown to the viewer: EXIF Orientation 5–8 swaps width and height.
+/// EXIF is only read when a filter actually needs it.
+fn displayed_dimensions(
+    source: &Path,
+    filter: Option<&AspectRatioFilter>,
+    dimensions: Option<(u32, u32)>,
+) -> Option<(u32, u32)> {
+    let (width, height) = dimensions?;
+    if matches!(filter, None | Some(AspectRatioFilter::All)) {
+        return Some((width, height));
+    }
+    match read_orientation(source) {
+        Some(5..=8) => Some((height, width)),
+        _ => Some((width, height)),
+    }
+}
+
+/// Unknown dimensions always match so unreadable headers are not filtered out.
+fn matches_aspect_ratio(
+    filter: Option<&AspectRatioFilter>,
+    dimensions: Option<(u32, u32)>,
+) -> bool {
+    let Some((width, height)) = dimensions else {
+        return true;
+    };
+    match filter {
+        None | Some(AspectRatioFilter::All) => true,
+        Some(AspectRatioFilter::Portrait) => height > width,
+        Some(AspectRatioFilter::Landscape) => width > height,
+        Some(AspectRatioFilter::Square) => width == height,
+    }
+}
+
+/// Mirrors the worker's resize math in `codec::decode_and_resize`.
+fn resized_dimensions(width: u32, height: u32, resize_percent: f32) -> (u32, u32) {
+    let ratio = (resize_percent / 100.0).clamp(0.01, 1.0);
+    if ratio >= 0.999 {
+        return (width, height);
+    }
+    (
+        ((width as f32) * ratio).round().max(1.0) as u32,
+        ((height as f32) * ratio).round().max(1.0) as u32,
+    )
+}
+
 fn preview_response_from_state(state: &CompressPlanState) -> CompressPreviewResponse {
     let mut ready = 0usize;
     let mut skipped = 0usize;
@@ -366,6 +869,11 @@ fn preview_response_from_state(state: &CompressPlanState) -> CompressPreviewResp
                 destination_path: item.destination.to_string_lossy().to_string(),
                 source_size: item.source_size,
                 estimated_size: item.estimated_size,
+                source_width: item.source_dimensions.map(|(width, _)| width),
+                source_height: item.source_dimensions.map(|(_, height)| height),
+                output_width: item.output_dimensions.map(|(width, _)| width),
+                output_height: item.output_dimensions.map(|(_, height)| height),
+                per_file_target_kb: item.per_file_target_kb,
                 status: item.status.clone(),
                 reason: item.reason.clone(),
             }
@@ -373,7 +881,7 @@ fn preview_response_from_state(state: &CompressPlanState) -> CompressPreviewResp
         .collect();
 
     CompressPreviewResponse {
-        output_dir: state.output_dir.to_string_lossy().to_string(),
+        output_dir: output_dir_label(state),
         effective_resize_percent: state.effective_resize_percent,
         effective_quality: state.effective_quality,
         target_size_kb: state.target_size_kb,
@@ -382,11 +890,43 @@ fn preview_response_from_state(state: &CompressPlanState) -> CompressPreviewResp
         total: ready + skipped,
         ready,
         skipped,
-        warnings: state.warnings,
+        tolerance_warnings: state.tolerance_warnings,
+        filtered_by_size: state.filtered_by_size,
+        filtered_by_aspect: state.filtered_by_aspect,
+        suggest_iterations: state.suggest_iterations.clone(),
+        warnings: state.warnings.clone(),
+    }
+}
+
+/// Shown instead of a folder when outputs are written beside their sources.
+const SAME_AS_SOURCE_OUTPUT_DIR: &str = "(same as source)";
+
+fn output_dir_label(state: &CompressPlanState) -> String {
+    if state.keep_original {
+        SAME_AS_SOURCE_OUTPUT_DIR.to_string()
+    } else {
+        state.output_dir.to_string_lossy().to_string()
     }
 }
 
-fn resolve_output_dir(
+/// `photo.jpg` → `photo_compressed.jpg` in the source's own folder.
+fn keep_original_destination(source: &Path) -> PathBuf {
+    let stem = source
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("file");
+    let file_name = match source.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => format!("{}_compressed.{}", stem, ext),
+        None => format!("{}_compressed", stem),
+    };
+    let file_name = sanitize_file_name(&file_name, None);
+    source.parent().map_or_else(
+        || PathBuf::from(&file_name),
+        |parent| parent.join(&file_name),
+    )
+}
+
+pub(crate) fn resolve_output_dir(
     input_root: Option<&Path>,
     output_dir: Option<&str>,
     suffix_tag: &str,
@@ -419,6 +959,7 @@ fn resolve_output_dir(
 
 fn resolve_destination_for_policy(
     base_destination: &Path,
+    source: &Path,
     used_destinations: &mut HashSet<String>,
     policy: CollisionPolicy,
 ) -> (PreviewStatus, Option<String>, PathBuf) {
@@ -462,9 +1003,55 @@ fn resolve_destination_for_policy(
                 destination,
             )
         }
+        CollisionPolicy::PrefixParent => {
+            let prefixed = if collision {
+                prefix_parent_destination(base_destination, source)
+            } else {
+                None
+            };
+            match prefixed {
+                Some(prefixed) => {
+                    let destination = uniquify_destination(&prefixed, used_destinations);
+                    let reason = if destination == prefixed {
+                        "競合のため親フォルダ名を付与しました"
+                    } else {
+                        "競合のため親フォルダ名と連番サフィックスを付与しました"
+                    };
+                    (PreviewStatus::Ready, Some(reason.to_string()), destination)
+                }
+                None => {
+                    let destination = uniquify_destination(base_destination, used_destinations);
+                    (
+                        PreviewStatus::Ready,
+                        (destination != base_destination)
+                            .then(|| "競合のため連番サフィックスを付与しました".to_string()),
+                        destination,
+                    )
+                }
+            }
+        }
     }
 }
 
+/// Re-encodes at quality 95; a file that barely shrinks was most likely saved at q75+ already.
+fn is_likely_already_compressed(source: &Path) -> bool {
+    let Ok(original_size) = fs::metadata(to_windows_extended(source)).map(|m| m.len()) else {
+        return false;
+    };
+    let Ok(image) = image::open(to_windows_extended(source)) else {
+        return false;
+    };
+    let mut encoded = Vec::new();
+    if JpegEncoder::new_with_quality(&mut encoded, 95)
+        .encode_image(&image)
+        .is_err()
+        || encoded.is_empty()
+    {
+        return false;
+    }
+    (original_size as f64) / (encoded.len() as f64) < ALREADY_COMPRESSED_RATIO
+}
+
 fn estimate_size(source_size: u64, resize_percent: f32, quality: u8) -> u64 {
     let resize_ratio = (resize_percent / 100.0).clamp(0.01, 1.0) as f64;
     let quality_ratio = (quality as f64 / 100.0).clamp(0.01, 1.0);
@@ -472,14 +1059,23 @@ fn estimate_size(source_size: u64, resize_percent: f32, quality: u8) -> u64 {
     ((source_size as f64) * resize_ratio * resize_ratio * quality_factor).round() as u64
 }
 
+/// Share of the batch target proportional to the file's share of the source bytes.
+fn weighted_target_bytes(total_target_kb: u64, source_size: u64, total_source_size: u64) -> u64 {
+    let total_target_bytes = total_target_kb.saturating_mul(1024);
+    if total_source_size == 0 {
+        return total_target_bytes;
+    }
+    ((total_target_bytes as u128) * (source_size as u128) / (total_source_size as u128)) as u64
+}
+
 fn tolerance_warning(
     source_size: u64,
+    total_source_size: u64,
     estimated_size: u64,
     target_size_kb: Option<u64>,
     tolerance_percent: f32,
 ) -> Option<String> {
-    let target = target_size_kb?;
-    let target_bytes = target.saturating_mul(1024);
+    let target_bytes = weighted_target_bytes(target_size_kb?, source_size, total_source_size);
     if target_bytes == 0 {
         return None;
     }
@@ -521,6 +1117,13 @@ fn uniquify_directory(base: PathBuf) -> PathBuf {
     }
 }
 
+/// `<parent>_<name>` next to `base`, where `<parent>` is the source's immediate folder name.
+fn prefix_parent_destination(base: &Path, source: &Path) -> Option<PathBuf> {
+    let parent_name = source.parent()?.file_name()?.to_str()?;
+    let file_name = base.file_name()?.to_str()?;
+    Some(base.with_file_name(format!("{}_{}", parent_name, file_name)))
+}
+
 fn uniquify_destination(base: &Path, used_destinations: &mut HashSet<String>) -> PathBuf {
     let mut candidate = base.to_path_buf();
     let mut suffix = 1usize;
@@ -1,26 +1,30 @@
 use crate::error::AppError;
-use crate::file_collect::collect_targets_with_extensions;
-use crate::fs_atomic::atomic_move_replace;
+use crate::file_collect::{
+    collect_targets_with_depth, count_by_extension, depth_limit, normalize_extension_alias,
+};
+use crate::fs_atomic::{atomic_move_replace, dry_run_check, remove_emptied_dirs};
 use crate::model::{
     CollisionPolicy, DeleteExecuteDetail, DeleteExecuteResponse, DeleteMode, DeletePreviewItem,
     DeletePreviewRequest, DeletePreviewResponse, ExecuteStatus, OperationProgressEvent,
-    PreviewStatus,
+    OperationSummary, PreviewStatus,
 };
-use crate::path_norm::relative_or_portable_absolute;
+use crate::path_norm::{relative_or_portable_absolute, safe_canonicalize, to_windows_extended};
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use glob::Pattern;
 use std::collections::{HashSet, VecDeque};
 use std::fs;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
-struct PlannedDelete {
-    source: PathBuf,
-    destination: Option<PathBuf>,
-    status: PreviewStatus,
-    reason: Option<String>,
+pub struct PlannedDelete {
+    pub source: PathBuf,
+    pub destination: Option<PathBuf>,
+    pub status: PreviewStatus,
+    pub reason: Option<String>,
 }
 
 pub fn preview(request: &DeletePreviewRequest) -> Result<DeletePreviewResponse, AppError> {
-    let (plan, mode) = build_plan(request)?;
+    let (plan, mode, warnings) = build_plan(request)?;
     let mut ready = 0usize;
     let mut skipped = 0usize;
 
@@ -44,28 +48,82 @@ pub fn preview(request: &DeletePreviewRequest) -> Result<DeletePreviewResponse,
         })
         .collect();
 
+    let sources: Vec<PathBuf> = plan.iter().map(|item| item.source.clone()).collect();
     Ok(DeletePreviewResponse {
         items,
         total: ready + skipped,
         ready,
         skipped,
+        source_by_extension: count_by_extension(&sources),
+        warnings,
     })
 }
 
 pub fn execute<FCancel, FProgress>(
     request: &DeletePreviewRequest,
     is_cancelled: FCancel,
+    report_progress: FProgress,
+) -> Result<DeleteExecuteResponse, AppError>
+where
+    FCancel: Fn() -> bool,
+    FProgress: FnMut(OperationProgressEvent),
+{
+    let (plan, mode, warnings) = build_plan(request)?;
+    if is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
+    let dry_run = request.dry_run.unwrap_or(false);
+    let mut response = execute_plan(
+        plan,
+        &mode,
+        "delete",
+        dry_run,
+        is_cancelled,
+        report_progress,
+    )?;
+    response.summary.warnings = warnings;
+    if request.remove_empty_dirs.unwrap_or(false) && !dry_run {
+        remove_emptied_source_dirs(&request.input_paths, &response.details);
+    }
+    Ok(response)
+}
+
+/// Removes parent folders of deleted files that became empty, stopping at the input folders.
+fn remove_emptied_source_dirs(input_paths: &[String], details: &[DeleteExecuteDetail]) {
+    let roots: Vec<PathBuf> = input_paths
+        .iter()
+        .filter_map(|path| safe_canonicalize(Path::new(path.trim())).ok())
+        .filter(|path| path.is_dir())
+        .collect();
+    let parents = details
+        .iter()
+        .filter(|detail| matches!(detail.status, ExecuteStatus::Succeeded))
+        .filter_map(|detail| {
+            Path::new(&detail.source_path)
+                .parent()
+                .map(Path::to_path_buf)
+        });
+    remove_emptied_dirs(parents, &roots);
+}
+
+/// Runs an already-built delete plan. Shared with other modules that end in a delete/retreat step.
+pub fn execute_plan<FCancel, FProgress>(
+    plan: Vec<PlannedDelete>,
+    mode: &DeleteMode,
+    operation: &str,
+    dry_run: bool,
+    is_cancelled: FCancel,
     mut report_progress: FProgress,
 ) -> Result<DeleteExecuteResponse, AppError>
 where
     FCancel: Fn() -> bool,
     FProgress: FnMut(OperationProgressEvent),
 {
-    let (plan, mode) = build_plan(request)?;
     let mut details = Vec::with_capacity(plan.len());
     let mut succeeded = 0usize;
     let mut failed = 0usize;
     let mut skipped = 0usize;
+    let mut copy_fallback_count = 0usize;
     let total = plan.len();
     let mut processed = 0usize;
     let mut canceled = false;
@@ -80,7 +138,7 @@ where
             processed += 1;
             details.push(DeleteExecuteDetail {
                 source_path: item.source.to_string_lossy().to_string(),
-                action: delete_mode_label(&mode),
+                action: delete_mode_label(mode),
                 destination_path: item
                     .destination
                     .as_ref()
@@ -93,7 +151,7 @@ where
                 },
             });
             report_progress(OperationProgressEvent {
-                operation: "delete".to_string(),
+                operation: operation.to_string(),
                 processed,
                 total,
                 succeeded,
@@ -102,31 +160,43 @@ where
                 current_path: Some(item.source.to_string_lossy().to_string()),
                 done: false,
                 canceled,
+                eta_seconds: None,
+                bytes_processed: 0,
+                total_bytes: 0,
             });
             continue;
         }
 
-        let result = match mode {
-            DeleteMode::Direct => fs::remove_file(&item.source)
-                .map(|_| None)
-                .map_err(|e| format!("ファイルの削除に失敗しました: {}", e)),
-            DeleteMode::Trash => trash::delete(&item.source)
-                .map(|_| None)
-                .map_err(|e| format!("ゴミ箱への移動に失敗しました: {}", e)),
-            DeleteMode::Retreat => {
-                let Some(destination) = item.destination.as_ref() else {
-                    return Err(AppError::InvalidRequest(
-                        "退避先が指定されていません".to_string(),
-                    ));
-                };
-                if let Some(parent) = destination.parent() {
-                    if let Err(error) = fs::create_dir_all(parent) {
-                        Err(format!("出力先フォルダの作成に失敗しました: {}", error))
-                    } else {
-                        atomic_move_replace(&item.source, destination)
-                    }
-                } else {
-                    atomic_move_replace(&item.source, destination)
+        let result = if dry_run {
+            dry_run_check(&item.source)
+        } else {
+            match mode {
+                DeleteMode::Direct => fs::remove_file(&item.source)
+                    .map(|_| None)
+                    .map_err(|e| format!("ファイルの削除に失敗しました: {}", e)),
+                DeleteMode::Trash => trash::delete(&item.source)
+                    .map(|_| None)
+                    .map_err(|e| format!("ゴミ箱への移動に失敗しました: {}", e)),
+                DeleteMode::Retreat => {
+                    let Some(destination) = item.destination.as_ref() else {
+                        return Err(AppError::InvalidRequest(
+                            "退避先が指定されていません".to_string(),
+                        ));
+                    };
+                    let prepared = match destination.parent() {
+                        Some(parent) => fs::create_dir_all(parent).map_err(|error| {
+                            format!("出力先フォルダの作成に失敗しました: {}", error)
+                        }),
+                        None => Ok(()),
+                    };
+                    prepared
+                        .and_then(|()| atomic_move_replace(&item.source, destination))
+                        .map(|note| {
+                            if note.is_copy_fallback() {
+                                copy_fallback_count += 1;
+                            }
+                            note.into_reason()
+                        })
                 }
             }
         };
@@ -137,7 +207,7 @@ where
                 processed += 1;
                 details.push(DeleteExecuteDetail {
                     source_path: item.source.to_string_lossy().to_string(),
-                    action: delete_mode_label(&mode),
+                    action: delete_mode_label(mode),
                     destination_path: item
                         .destination
                         .as_ref()
@@ -151,7 +221,7 @@ where
                 processed += 1;
                 details.push(DeleteExecuteDetail {
                     source_path: item.source.to_string_lossy().to_string(),
-                    action: delete_mode_label(&mode),
+                    action: delete_mode_label(mode),
                     destination_path: item
                         .destination
                         .as_ref()
@@ -163,7 +233,7 @@ where
         }
 
         report_progress(OperationProgressEvent {
-            operation: "delete".to_string(),
+            operation: operation.to_string(),
             processed,
             total,
             succeeded,
@@ -172,11 +242,14 @@ where
             current_path: Some(item.source.to_string_lossy().to_string()),
             done: false,
             canceled,
+            eta_seconds: None,
+            bytes_processed: 0,
+            total_bytes: 0,
         });
     }
 
     report_progress(OperationProgressEvent {
-        operation: "delete".to_string(),
+        operation: operation.to_string(),
         processed: total,
         total,
         succeeded,
@@ -185,35 +258,137 @@ where
         current_path: None,
         done: true,
         canceled,
+        eta_seconds: None,
+        bytes_processed: 0,
+        total_bytes: 0,
     });
 
     Ok(DeleteExecuteResponse {
-        processed: succeeded + failed + skipped,
-        succeeded,
-        failed,
-        skipped,
+        summary: OperationSummary::new(
+            operation,
+            succeeded + failed + skipped,
+            succeeded,
+            failed,
+            skipped,
+            None,
+            canceled,
+        ),
+        copy_fallback_count,
         details,
     })
 }
 
 fn build_plan(
     request: &DeletePreviewRequest,
-) -> Result<(Vec<PlannedDelete>, DeleteMode), AppError> {
-    let normalized_extensions = normalize_extensions(&request.extensions)?;
+) -> Result<(Vec<PlannedDelete>, DeleteMode, Vec<String>), AppError> {
+    if request.remove_empty_dirs.unwrap_or(false)
+        && request.preserve_directory_structure.unwrap_or(false)
+    {
+        return Err(AppError::InvalidRequest(
+            "相反する設定が指定されました".to_string(),
+        ));
+    }
+    let glob_patterns = compile_glob_patterns(request.glob_patterns.as_deref().unwrap_or(&[]))?;
+    let modified_before = parse_modified_bound(request.modified_before.as_deref())?;
+    let modified_after = parse_modified_bound(request.modified_after.as_deref())?;
+    if let (Some(before), Some(after)) = (modified_before, modified_after) {
+        if before < after {
+            return Err(AppError::InvalidRequest(
+                "変更日時の範囲指定が不正です（終了日時が開始日時より前です）".to_string(),
+            ));
+        }
+    }
+    // Glob patterns alone are enough; otherwise at least one extension is required.
+    let normalized_extensions = if glob_patterns.is_empty()
+        || request.extensions.iter().any(|ext| !ext.trim().is_empty())
+    {
+        normalize_extensions(&request.extensions)?
+    } else {
+        Vec::new()
+    };
     let refs: Vec<&str> = normalized_extensions.iter().map(String::as_str).collect();
-    let collect = collect_targets_with_extensions(
+    let mut collect = collect_targets_with_depth(
         &request.input_paths,
-        request.include_subfolders,
+        depth_limit(request.include_subfolders, request.max_depth),
         refs.as_slice(),
+        request.max_files,
     )
     .map_err(AppError::InvalidRequest)?;
+    collect
+        .ensure_accessible(request.fail_on_permission_denied.unwrap_or(false))
+        .map_err(AppError::InvalidRequest)?;
+    if !glob_patterns.is_empty() {
+        collect.files.retain(|file| {
+            file.file_name().is_some_and(|name| {
+                glob_patterns
+                    .iter()
+                    .any(|pattern| pattern.matches_path(Path::new(name)))
+            })
+        });
+    }
     if collect.files.is_empty() {
-        return Ok((Vec::new(), request.mode.clone()));
+        return Ok((Vec::new(), request.mode.clone(), collect.warnings()));
     }
 
-    let retreat_dir = match request.mode {
+    // Out-of-range files stay out of planning so they don't reserve retreat destinations,
+    // but keep their sorted position in the plan.
+    let in_range_flags: Vec<bool> = collect
+        .files
+        .iter()
+        .map(|file| modified_in_range(file, modified_before, modified_after))
+        .collect();
+    let in_range: Vec<PathBuf> = collect
+        .files
+        .iter()
+        .zip(&in_range_flags)
+        .filter(|(_, in_range)| **in_range)
+        .map(|(file, _)| file.clone())
+        .collect();
+
+    let conflict_policy = request
+        .conflict_policy
+        .clone()
+        .unwrap_or(CollisionPolicy::Sequence);
+    let mut planned = plan_deletions(
+        &in_range,
+        collect.input_root.as_deref(),
+        &request.mode,
+        request.retreat_dir.as_deref(),
+        &conflict_policy,
+    )?
+    .into_iter();
+    let plan = collect
+        .files
+        .iter()
+        .zip(in_range_flags)
+        .filter_map(|(source, in_range)| {
+            if in_range {
+                planned.next()
+            } else {
+                Some(PlannedDelete {
+                    source: source.clone(),
+                    destination: None,
+                    status: PreviewStatus::Skipped,
+                    reason: Some("変更日時が範囲外です".to_string()),
+                })
+            }
+        })
+        .collect();
+
+    Ok((plan, request.mode.clone(), collect.warnings()))
+}
+
+/// Plans a delete/trash/retreat for each source. Retreat destinations mirror the layout under `input_root`.
+pub fn plan_deletions(
+    sources: &[PathBuf],
+    input_root: Option<&Path>,
+    mode: &DeleteMode,
+    retreat_dir: Option<&str>,
+    conflict_policy: &CollisionPolicy,
+) -> Result<Vec<PlannedDelete>, AppError> {
+    let retreat_dir = match mode {
         DeleteMode::Retreat => {
-            let Some(dir) = request.retreat_dir.as_ref() else {
+            let Some(dir) = retreat_dir else {
                 return Err(AppError::InvalidRequest(
                     "退避モードでは退避先フォルダの指定が必要です".to_string(),
                 ));
@@ -228,29 +403,24 @@ fn build_plan(
         }
         _ => None,
     };
-    let conflict_policy = request
-        .conflict_policy
-        .clone()
-        .unwrap_or(CollisionPolicy::Sequence);
 
     let mut used_destinations: HashSet<String> = HashSet::new();
-    let mut plan = Vec::with_capacity(collect.files.len());
-    for source in &collect.files {
-        let (status, reason, destination) = match request.mode {
+    let mut plan = Vec::with_capacity(sources.len());
+    for source in sources {
+        let (status, reason, destination) = match mode {
             DeleteMode::Direct | DeleteMode::Trash => (PreviewStatus::Ready, None, None),
             DeleteMode::Retreat => {
                 let retreat_root = retreat_dir
                     .as_ref()
                     .ok_or_else(|| AppError::InvalidRequest("退避先ルートが未設定です".to_string()))?;
-                let relative = relative_or_portable_absolute(source, collect.input_root.as_deref());
+                let relative = relative_or_portable_absolute(source, input_root);
                 let base_destination = retreat_root.join(relative);
-                let (status, reason, destination) = resolve_retreat_destination_for_policy(
+                resolve_retreat_destination_for_policy(
                     &base_destination,
                     source,
                     &mut used_destinations,
-                    &conflict_policy,
-                );
-                (status, reason, destination)
+                    conflict_policy,
+                )
             }
         };
 
@@ -262,7 +432,53 @@ fn build_plan(
         });
     }
 
-    Ok((plan, request.mode.clone()))
+    Ok(plan)
+}
+
+/// RFC 3339, or a bare `YYYY-MM-DD` taken as local midnight.
+fn parse_modified_bound(value: Option<&str>) -> Result<Option<DateTime<Local>>, AppError> {
+    let Some(raw) = value.map(str::trim).filter(|raw| !raw.is_empty()) else {
+        return Ok(None);
+    };
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(Some(parsed.with_timezone(&Local)));
+    }
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        .map(Some)
+        .ok_or_else(|| AppError::InvalidRequest(format!("日時の形式が正しくありません: {}", raw)))
+}
+
+/// Files whose modification time cannot be read are treated as out of range.
+fn modified_in_range(
+    file: &Path,
+    before: Option<DateTime<Local>>,
+    after: Option<DateTime<Local>>,
+) -> bool {
+    if before.is_none() && after.is_none() {
+        return true;
+    }
+    let Ok(modified) = fs::metadata(to_windows_extended(file)).and_then(|meta| meta.modified())
+    else {
+        return false;
+    };
+    let modified = DateTime::<Local>::from(modified);
+    before.is_none_or(|before| modified < before) && after.is_none_or(|after| modified >= after)
+}
+
+fn compile_glob_patterns(values: &[String]) -> Result<Vec<Pattern>, AppError> {
+    values
+        .iter()
+        .map(|raw| raw.trim())
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            Pattern::new(value).map_err(|e| {
+                AppError::InvalidRequest(format!("無効なglobパターンです: `{}` ({})", value, e))
+            })
+        })
+        .collect()
 }
 
 fn normalize_extensions(values: &[String]) -> Result<Vec<String>, AppError> {
@@ -286,6 +502,7 @@ fn normalize_extensions(values: &[String]) -> Result<Vec<String>, AppError> {
                 raw
             )));
         }
+        let normalized = normalize_extension_alias(&normalized).to_string();
         if unique.insert(normalized.clone()) {
             queue.push_back(normalized);
         }
@@ -351,9 +568,43 @@ fn resolve_retreat_destination_for_policy(
                 Some(unique),
             )
         }
+        CollisionPolicy::PrefixParent => {
+            let prefixed = if collision {
+                prefix_parent_destination(base, source)
+            } else {
+                None
+            };
+            match prefixed {
+                Some(prefixed) => {
+                    let unique = uniquify_destination(&prefixed, source, used_keys);
+                    let reason = if unique == prefixed {
+                        "競合のため親フォルダ名を付与しました"
+                    } else {
+                        "競合のため親フォルダ名と連番サフィックスを付与しました"
+                    };
+                    (PreviewStatus::Ready, Some(reason.to_string()), Some(unique))
+                }
+                None => {
+                    let unique = uniquify_destination(base, source, used_keys);
+                    (
+                        PreviewStatus::Ready,
+                        (unique != base)
+                            .then(|| "競合のため連番サフィックスを付与しました".to_string()),
+                        Some(unique),
+                    )
+                }
+            }
+        }
     }
 }
 
+/// `<parent>_<name>` next to `base`, where `<parent>` is the source's immediate folder name.
+fn prefix_parent_destination(base: &Path, source: &Path) -> Option<PathBuf> {
+    let parent_name = source.parent()?.file_name()?.to_str()?;
+    let file_name = base.file_name()?.to_str()?;
+    Some(base.with_file_name(format!("{}_{}", parent_name, file_name)))
+}
+
 fn uniquify_destination(base: &Path, source: &Path, used_keys: &mut HashSet<String>) -> PathBuf {
     let mut candidate = base.to_path_buf();
     let mut suffix = 1usize;
@@ -0,0 +1,209 @@
+use crate::delete::{execute_plan, plan_deletions};
+use crate::error::AppError;
+use crate::file_collect::collect_all_targets;
+use crate::model::{
+    CollisionPolicy, DeleteExecuteResponse, DeleteMode, DuplicateAction, DuplicateDetectRequest,
+    DuplicateDetectResponse, DuplicateGroup, HashAlgorithm, OperationProgressEvent, PreviewStatus,
+};
+use crate::path_norm::to_windows_extended;
+use rayon::prelude::*;
+use sha2::Digest;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+struct DuplicateScan {
+    input_root: Option<PathBuf>,
+    total_files: usize,
+    unreadable: usize,
+    /// Groups of identical files; each group is in collect order, so the first entry is the one kept.
+    groups: Vec<(String, u64, Vec<PathBuf>)>,
+    warnings: Vec<String>,
+}
+
+pub fn preview(request: &DuplicateDetectRequest) -> Result<DuplicateDetectResponse, AppError> {
+    let scan = scan_duplicates(request)?;
+    let mut duplicate_files = 0usize;
+    let mut wasted_total = 0u64;
+    let duplicate_groups = scan
+        .groups
+        .iter()
+        .map(|(hash, size, files)| {
+            let extra = (files.len() - 1) as u64;
+            duplicate_files += files.len() - 1;
+            wasted_total += size * extra;
+            DuplicateGroup {
+                hash: hash.clone(),
+                file_size: *size,
+                files: files
+                    .iter()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect(),
+                wasted_bytes: size * extra,
+            }
+        })
+        .collect();
+
+    Ok(DuplicateDetectResponse {
+        duplicate_groups,
+        total_files: scan.total_files,
+        duplicate_files,
+        wasted_bytes: wasted_total,
+        unreadable: scan.unreadable,
+        warnings: scan.warnings,
+    })
+}
+
+pub fn execute<FCancel, FProgress>(
+    request: &DuplicateDetectRequest,
+    is_cancelled: FCancel,
+    report_progress: FProgress,
+) -> Result<DeleteExecuteResponse, AppError>
+where
+    FCancel: Fn() -> bool,
+    FProgress: FnMut(OperationProgressEvent),
+{
+    let scan = scan_duplicates(request)?;
+    if is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
+
+    let duplicates: Vec<PathBuf> = scan
+        .groups
+        .iter()
+        .flat_map(|(_, _, files)| files.iter().skip(1).cloned())
+        .collect();
+    let mode = match request.action {
+        DuplicateAction::DeleteKeepFirst | DuplicateAction::ReportOnly => DeleteMode::Direct,
+        DuplicateAction::Retreat => DeleteMode::Retreat,
+    };
+    let conflict_policy = request
+        .conflict_policy
+        .clone()
+        .unwrap_or(CollisionPolicy::Sequence);
+    let mut plan = plan_deletions(
+        &duplicates,
+        scan.input_root.as_deref(),
+        &mode,
+        request.retreat_dir.as_deref(),
+        &conflict_policy,
+    )?;
+    if matches!(request.action, DuplicateAction::ReportOnly) {
+        for item in &mut plan {
+            item.status = PreviewStatus::Skipped;
+            item.reason = Some("レポートのみのため変更しません".to_string());
+        }
+    }
+
+    execute_plan(
+        plan,
+        &mode,
+        "duplicateDetect",
+        request.dry_run.unwrap_or(false),
+        is_cancelled,
+        report_progress,
+    )
+}
+
+fn scan_duplicates(request: &DuplicateDetectRequest) -> Result<DuplicateScan, AppError> {
+    let collect = collect_all_targets(
+        &request.input_paths,
+        request.include_subfolders,
+        request.max_files,
+    )
+    .map_err(AppError::InvalidRequest)?;
+    if collect.files.is_empty() {
+        return Err(AppError::InvalidRequest(
+            "対象ファイルが見つかりません。".to_string(),
+        ));
+    }
+
+    // Only files sharing a size can be identical, so hash just those.
+    let sizes: Vec<Option<u64>> = collect
+        .files
+        .par_iter()
+        .map(|path| {
+            fs::metadata(to_windows_extended(path))
+                .ok()
+                .map(|m| m.len())
+        })
+        .collect();
+    let mut size_counts: HashMap<u64, usize> = HashMap::new();
+    for size in sizes.iter().flatten() {
+        *size_counts.entry(*size).or_insert(0) += 1;
+    }
+
+    let hashes: Vec<Option<String>> = collect
+        .files
+        .par_iter()
+        .zip(sizes.par_iter())
+        .map(|(path, size)| match size {
+            Some(size) if size_counts.get(size).copied().unwrap_or(0) > 1 => {
+                hash_file(path, &request.hash_algorithm).ok()
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut unreadable = sizes.iter().filter(|size| size.is_none()).count();
+    let mut group_index: HashMap<(u64, String), usize> = HashMap::new();
+    let mut groups: Vec<(String, u64, Vec<PathBuf>)> = Vec::new();
+    for ((path, size), hash) in collect.files.iter().zip(&sizes).zip(hashes) {
+        let Some(size) = *size else {
+            continue;
+        };
+        let Some(hash) = hash else {
+            if size_counts.get(&size).copied().unwrap_or(0) > 1 {
+                unreadable += 1;
+            }
+            continue;
+        };
+        match group_index.get(&(size, hash.clone())) {
+            Some(&index) => groups[index].2.push(path.clone()),
+            None => {
+                group_index.insert((size, hash.clone()), groups.len());
+                groups.push((hash, size, vec![path.clone()]));
+            }
+        }
+    }
+    groups.retain(|(_, _, files)| files.len() > 1);
+
+    Ok(DuplicateScan {
+        warnings: collect.warnings(),
+        input_root: collect.input_root,
+        total_files: collect.files.len(),
+        unreadable,
+        groups,
+    })
+}
+
+pub fn hash_file(path: &Path, algorithm: &HashAlgorithm) -> Result<String, String> {
+    match algorithm {
+        HashAlgorithm::Sha256 => hash_with::<sha2::Sha256>(path),
+        HashAlgorithm::Md5 => hash_with::<md5::Md5>(path),
+    }
+}
+
+fn hash_with<D: Digest>(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(to_windows_extended(path))
+        .map_err(|e| format!("ファイルを開けません: {}", e))?;
+    let mut hasher = D::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("読み込みエラー: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
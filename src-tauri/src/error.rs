@@ -8,10 +8,33 @@ pub enum AppError {
     Io(String),
     #[error("設定エラー: {0}")]
     Settings(String),
+    #[error("キャンセルされました")]
+    Cancelled,
+    #[error("アクセスが拒否されました: {0}")]
+    PermissionDenied(String),
+    #[error("ワーカーエラー: {0}")]
+    WorkerCrashed(String),
+}
+
+impl AppError {
+    /// Machine-readable code sent to the frontend alongside the message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidRequest(_) => "invalidRequest",
+            Self::Io(_) => "io",
+            Self::Settings(_) => "settings",
+            Self::Cancelled => "cancelled",
+            Self::PermissionDenied(_) => "permissionDenied",
+            Self::WorkerCrashed(_) => "workerCrashed",
+        }
+    }
 }
 
 impl From<std::io::Error> for AppError {
     fn from(value: std::io::Error) -> Self {
-        Self::Io(value.to_string())
+        match value.kind() {
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied(value.to_string()),
+            _ => Self::Io(value.to_string()),
+        }
     }
 }
@@ -2,38 +2,59 @@ use crate::error::AppError;
 use crate::file_collect::{collect_targets_with_extensions, JPEG_ALLOWED_EXTENSIONS};
 use crate::fs_atomic::atomic_write_replace;
 use crate::model::{
-    ExifOffsetExecuteDetail, ExifOffsetExecuteResponse, ExifOffsetPreviewItem,
+    CollisionPolicy, ExifOffsetExecuteDetail, ExifOffsetExecuteResponse, ExifOffsetPreviewItem,
     ExifOffsetPreviewRequest, ExifOffsetPreviewResponse, ExecuteStatus, OperationProgressEvent,
-    PreviewStatus,
+    OperationSummary, PreviewStatus,
 };
+use crate::path_norm::{destination_key, relative_or_portable_absolute, to_windows_extended};
+use crate::progress_eta::EtaTracker;
 use chrono::NaiveDateTime;
 use exif::{In, Reader, Tag, Value};
+use std::collections::HashSet;
 use std::fs;
 use std::io::BufReader;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// EXIF datetime tag IDs
 const TAG_DATETIME: u16 = 0x0132;
 const TAG_DATETIME_ORIGINAL: u16 = 0x9003;
 const TAG_DATETIME_DIGITIZED: u16 = 0x9004;
 const TAG_EXIF_IFD_POINTER: u16 = 0x8769;
+const TAG_SUBSEC_TIME_ORIGINAL: u16 = 0x9291;
 
-/// Read the primary EXIF datetime string from a JPEG file.
+/// Subsecond strings must fit the 4-byte inline ASCII slot (3 digits + NUL).
+fn validate_subseconds(value: Option<&str>) -> Result<Option<&str>, AppError> {
+    let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    if value.len() > 3 || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(AppError::InvalidRequest(
+            "サブ秒は1〜3桁の数字で指定してください".to_string(),
+        ));
+    }
+    Ok(Some(value))
+}
+
+/// Read the primary EXIF datetime string from a JPEG file, with the tag name it came from.
 /// Priority: DateTimeOriginal > DateTimeDigitized > DateTime
-fn read_exif_datetime_string(path: &Path) -> Option<String> {
-    let file = fs::File::open(path).ok()?;
+fn read_exif_datetime_string(path: &Path) -> Option<(String, &'static str)> {
+    let file = fs::File::open(to_windows_extended(path)).ok()?;
     let mut reader = BufReader::new(file);
     let exif = Reader::new().read_from_container(&mut reader).ok()?;
 
-    let tags = [Tag::DateTimeOriginal, Tag::DateTimeDigitized, Tag::DateTime];
-    for tag in tags {
+    let tags = [
+        (Tag::DateTimeOriginal, "DateTimeOriginal"),
+        (Tag::DateTimeDigitized, "DateTimeDigitized"),
+        (Tag::DateTime, "DateTime"),
+    ];
+    for (tag, tag_name) in tags {
         if let Some(field) = exif.get_field(tag, In::PRIMARY) {
             if let Value::Ascii(ref vec) = field.value {
                 if !vec.is_empty() {
                     if let Ok(s) = String::from_utf8(vec[0].clone()) {
                         let trimmed = s.trim().trim_matches('\0');
                         if !trimmed.is_empty() {
-                            return Some(trimmed.to_string());
+                            return Some((trimmed.to_string(), tag_name));
                         }
                     }
                 }
@@ -52,10 +73,12 @@ fn apply_offset(datetime_str: &str, offset_seconds: i64) -> Option<String> {
 }
 
 pub fn preview(request: &ExifOffsetPreviewRequest) -> Result<ExifOffsetPreviewResponse, AppError> {
+    validate_subseconds(request.offset_subseconds.as_deref())?;
     let collect = collect_targets_with_extensions(
         &request.input_paths,
         request.include_subfolders,
         JPEG_ALLOWED_EXTENSIONS,
+        request.max_files,
     )
     .map_err(AppError::InvalidRequest)?;
 
@@ -78,16 +101,18 @@ pub fn preview(request: &ExifOffsetPreviewRequest) -> Result<ExifOffsetPreviewRe
     for file in &collect.files {
         let path_str = file.to_string_lossy().to_string();
         match read_exif_datetime_string(file) {
-            Some(original) => {
+            Some((original, tag_name)) => {
                 match apply_offset(&original, request.offset_seconds) {
                     Some(corrected) => {
                         ready += 1;
                         items.push(ExifOffsetPreviewItem {
                             source_path: path_str,
                             original_datetime: Some(original),
+                            source_tag: Some(tag_name.to_string()),
                             corrected_datetime: Some(corrected),
                             status: PreviewStatus::Ready,
                             reason: None,
+                            suspected_anomaly: false,
                         });
                     }
                     None => {
@@ -95,9 +120,11 @@ pub fn preview(request: &ExifOffsetPreviewRequest) -> Result<ExifOffsetPreviewRe
                         items.push(ExifOffsetPreviewItem {
                             source_path: path_str,
                             original_datetime: Some(original),
+                            source_tag: Some(tag_name.to_string()),
                             corrected_datetime: None,
                             status: PreviewStatus::Skipped,
                             reason: Some("オフセット適用後の日時が範囲外です".to_string()),
+                            suspected_anomaly: false,
                         });
                     }
                 }
@@ -107,22 +134,69 @@ pub fn preview(request: &ExifOffsetPreviewRequest) -> Result<ExifOffsetPreviewRe
                 items.push(ExifOffsetPreviewItem {
                     source_path: path_str,
                     original_datetime: None,
+                    source_tag: None,
                     corrected_datetime: None,
                     status: PreviewStatus::Skipped,
                     reason: Some("EXIF日時情報がありません".to_string()),
+                    suspected_anomaly: false,
                 });
             }
         }
     }
 
+    let sorted_by_gap = sort_by_gap_from_median(&mut items);
+
     Ok(ExifOffsetPreviewResponse {
         total: ready + skipped,
         ready,
         skipped,
         items,
+        sorted_by_gap,
+        warnings: collect.warnings(),
     })
 }
 
+/// Corrected times further than this from the median are flagged.
+const ANOMALY_GAP_SECONDS: i64 = 3600;
+
+/// Flags Ready items far from the median corrected time and returns them sorted by
+/// descending gap, so files still off after correction stand out.
+fn sort_by_gap_from_median(
+    items: &mut [ExifOffsetPreviewItem],
+) -> Option<Vec<ExifOffsetPreviewItem>> {
+    let corrected: Vec<(usize, i64)> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| matches!(item.status, PreviewStatus::Ready))
+        .filter_map(|(index, item)| {
+            let value = item.corrected_datetime.as_deref()?;
+            let naive = NaiveDateTime::parse_from_str(value, "%Y:%m:%d %H:%M:%S").ok()?;
+            Some((index, naive.and_utc().timestamp()))
+        })
+        .collect();
+    if corrected.is_empty() {
+        return None;
+    }
+
+    let mut timestamps: Vec<i64> = corrected.iter().map(|&(_, ts)| ts).collect();
+    timestamps.sort_unstable();
+    let median = timestamps[timestamps.len() / 2];
+
+    let mut gaps: Vec<(usize, i64)> = corrected
+        .into_iter()
+        .map(|(index, ts)| (index, (ts - median).abs()))
+        .collect();
+    for &(index, gap) in &gaps {
+        items[index].suspected_anomaly = gap > ANOMALY_GAP_SECONDS;
+    }
+    gaps.sort_by_key(|&(_, gap)| std::cmp::Reverse(gap));
+    Some(
+        gaps.into_iter()
+            .map(|(index, _)| items[index].clone())
+            .collect(),
+    )
+}
+
 pub fn execute<FCancel, FProgress>(
     request: &ExifOffsetPreviewRequest,
     is_cancelled: FCancel,
@@ -132,12 +206,17 @@ where
     FCancel: Fn() -> bool,
     FProgress: FnMut(OperationProgressEvent),
 {
+    let subseconds = validate_subseconds(request.offset_subseconds.as_deref())?;
     let collect = collect_targets_with_extensions(
         &request.input_paths,
         request.include_subfolders,
         JPEG_ALLOWED_EXTENSIONS,
+        request.max_files,
     )
     .map_err(AppError::InvalidRequest)?;
+    if is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
 
     let total = collect.files.len();
     let mut details = Vec::with_capacity(total);
@@ -146,6 +225,19 @@ where
     let mut skipped = 0usize;
     let mut processed = 0usize;
     let mut canceled = false;
+    let mut eta = EtaTracker::new();
+    let dry_run = request.dry_run.unwrap_or(false);
+    let output_dir = request
+        .output_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+    let conflict_policy = request
+        .conflict_policy
+        .clone()
+        .unwrap_or(CollisionPolicy::Sequence);
+    let mut used_destinations: HashSet<String> = HashSet::new();
 
     for file in &collect.files {
         if !canceled && is_cancelled() {
@@ -159,6 +251,7 @@ where
             processed += 1;
             details.push(ExifOffsetExecuteDetail {
                 source_path: path_str.clone(),
+                destination_path: None,
                 status: ExecuteStatus::Skipped,
                 reason: Some("キャンセルされました".to_string()),
             });
@@ -172,18 +265,22 @@ where
                 current_path: Some(path_str),
                 done: false,
                 canceled,
+                eta_seconds: eta.record(total.saturating_sub(processed)),
+                bytes_processed: 0,
+                total_bytes: 0,
             });
             continue;
         }
 
         // Check EXIF datetime exists
         let original = match read_exif_datetime_string(file) {
-            Some(dt) => dt,
+            Some((dt, _)) => dt,
             None => {
                 skipped += 1;
                 processed += 1;
                 details.push(ExifOffsetExecuteDetail {
                     source_path: path_str.clone(),
+                    destination_path: None,
                     status: ExecuteStatus::Skipped,
                     reason: Some("EXIF日時情報がありません".to_string()),
                 });
@@ -197,6 +294,9 @@ where
                     current_path: Some(path_str),
                     done: false,
                     canceled,
+                    eta_seconds: eta.record(total.saturating_sub(processed)),
+                    bytes_processed: 0,
+                    total_bytes: 0,
                 });
                 continue;
             }
@@ -209,6 +309,7 @@ where
                 processed += 1;
                 details.push(ExifOffsetExecuteDetail {
                     source_path: path_str.clone(),
+                    destination_path: None,
                     status: ExecuteStatus::Skipped,
                     reason: Some("オフセット適用後の日時が範囲外です".to_string()),
                 });
@@ -222,24 +323,81 @@ where
                     current_path: Some(path_str),
                     done: false,
                     canceled,
+                    eta_seconds: eta.record(total.saturating_sub(processed)),
+                    bytes_processed: 0,
+                    total_bytes: 0,
                 });
                 continue;
             }
         };
 
-        match modify_exif_dates(file, request.offset_seconds) {
+        let destination = match &output_dir {
+            Some(dir) => {
+                let relative = relative_or_portable_absolute(file, collect.input_root.as_deref());
+                match resolve_destination_for_policy(
+                    &dir.join(relative),
+                    &mut used_destinations,
+                    &conflict_policy,
+                ) {
+                    Ok(destination) => destination,
+                    Err(reason) => {
+                        skipped += 1;
+                        processed += 1;
+                        details.push(ExifOffsetExecuteDetail {
+                            source_path: path_str.clone(),
+                            destination_path: None,
+                            status: ExecuteStatus::Skipped,
+                            reason: Some(reason),
+                        });
+                        report_progress(OperationProgressEvent {
+                            operation: "exifOffset".to_string(),
+                            processed,
+                            total,
+                            succeeded,
+                            failed,
+                            skipped,
+                            current_path: Some(path_str),
+                            done: false,
+                            canceled,
+                            eta_seconds: eta.record(total.saturating_sub(processed)),
+                            bytes_processed: 0,
+                            total_bytes: 0,
+                        });
+                        continue;
+                    }
+                }
+            }
+            None => file.clone(),
+        };
+        let destination_path = output_dir
+            .as_ref()
+            .map(|_| destination.to_string_lossy().to_string());
+
+        match modify_exif_dates(
+            file,
+            &destination,
+            request.offset_seconds,
+            subseconds,
+            dry_run,
+        ) {
             Ok(_) => {
                 succeeded += 1;
                 details.push(ExifOffsetExecuteDetail {
                     source_path: path_str.clone(),
+                    destination_path,
                     status: ExecuteStatus::Succeeded,
-                    reason: Some(format!("{} → {}", original, corrected)),
+                    reason: Some(if dry_run {
+                        format!("{} → {}（ドライラン）", original, corrected)
+                    } else {
+                        format!("{} → {}", original, corrected)
+                    }),
                 });
             }
             Err(e) => {
                 failed += 1;
                 details.push(ExifOffsetExecuteDetail {
                     source_path: path_str.clone(),
+                    destination_path,
                     status: ExecuteStatus::Failed,
                     reason: Some(e),
                 });
@@ -257,6 +415,9 @@ where
             current_path: Some(path_str),
             done: false,
             canceled,
+            eta_seconds: eta.record(total.saturating_sub(processed)),
+            bytes_processed: 0,
+            total_bytes: 0,
         });
     }
 
@@ -270,23 +431,91 @@ where
         current_path: None,
         done: true,
         canceled,
+        eta_seconds: None,
+        bytes_processed: 0,
+        total_bytes: 0,
     });
 
     Ok(ExifOffsetExecuteResponse {
-        processed: succeeded + failed + skipped,
-        succeeded,
-        failed,
-        skipped,
+        summary: OperationSummary::new(
+            "exifOffset",
+            succeeded + failed + skipped,
+            succeeded,
+            failed,
+            skipped,
+            None,
+            canceled,
+        )
+        .with_warnings(collect.warnings()),
         details,
     })
 }
 
+/// Resolve the output path for a file when `output_dir` is set.
+/// Returns `Err(reason)` when the file should be skipped.
+fn resolve_destination_for_policy(
+    base: &Path,
+    used_keys: &mut HashSet<String>,
+    policy: &CollisionPolicy,
+) -> Result<PathBuf, String> {
+    let key = destination_key(base);
+    let collision = used_keys.contains(&key) || base.exists();
+    match policy {
+        CollisionPolicy::Overwrite => {
+            used_keys.insert(key);
+            Ok(base.to_path_buf())
+        }
+        CollisionPolicy::Skip => {
+            if collision {
+                Err("出力先の競合によりスキップされました".to_string())
+            } else {
+                used_keys.insert(key);
+                Ok(base.to_path_buf())
+            }
+        }
+        CollisionPolicy::Sequence | CollisionPolicy::PrefixParent => {
+            let stem = base
+                .file_stem()
+                .and_then(|value| value.to_str())
+                .unwrap_or("file");
+            let ext = base
+                .extension()
+                .and_then(|value| value.to_str())
+                .unwrap_or("");
+            let mut candidate = base.to_path_buf();
+            let mut suffix = 1usize;
+            loop {
+                let key = destination_key(&candidate);
+                if !used_keys.contains(&key) && !candidate.exists() {
+                    used_keys.insert(key);
+                    return Ok(candidate);
+                }
+                let file_name = if ext.is_empty() {
+                    format!("{}_{}", stem, suffix)
+                } else {
+                    format!("{}_{}.{}", stem, suffix, ext)
+                };
+                candidate = base.with_file_name(file_name);
+                suffix += 1;
+            }
+        }
+    }
+}
+
 /// Modify EXIF datetime fields in a JPEG file by binary patching.
 ///
 /// EXIF datetime fields are fixed-length ASCII "YYYY:MM:DD HH:MM:SS\0" (20 bytes).
-/// We overwrite them in-place with the offset-adjusted value.
-fn modify_exif_dates(path: &Path, offset_seconds: i64) -> Result<(), String> {
-    let data = fs::read(path)
+/// We overwrite them with the offset-adjusted value and write the result to
+/// `destination`, which is the source itself for in-place modification.
+/// With `dry_run` the patch is computed but never written back.
+fn modify_exif_dates(
+    path: &Path,
+    destination: &Path,
+    offset_seconds: i64,
+    subseconds: Option<&str>,
+    dry_run: bool,
+) -> Result<(), String> {
+    let data = fs::read(to_windows_extended(path))
         .map_err(|e| format!("ファイルの読み込みに失敗しました: {}", e))?;
 
     if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
@@ -339,12 +568,16 @@ fn modify_exif_dates(path: &Path, offset_seconds: i64) -> Result<(), String> {
                 && &modified[seg_start..seg_start + 6] == b"Exif\0\0"
             {
                 let tiff_start = seg_start + 6;
-                if let Ok(changed) =
-                    patch_exif_dates(&mut modified, tiff_start, seg_end, offset_seconds)
-                {
-                    if changed {
-                        any_modified = true;
-                    }
+                match patch_exif_dates(
+                    &mut modified,
+                    tiff_start,
+                    seg_end,
+                    offset_seconds,
+                    subseconds,
+                ) {
+                    Ok(changed) => any_modified |= changed,
+                    Err(PatchError::SubSec(message)) => return Err(message),
+                    Err(PatchError::Malformed) => {}
                 }
             }
         }
@@ -356,7 +589,15 @@ fn modify_exif_dates(path: &Path, offset_seconds: i64) -> Result<(), String> {
         return Err("書き換え可能なEXIF日時フィールドが見つかりません".to_string());
     }
 
-    atomic_write_replace(path, &modified)?;
+    if !dry_run {
+        if destination != path {
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(to_windows_extended(parent))
+                    .map_err(|e| format!("出力先フォルダの作成に失敗しました: {}", e))?;
+            }
+        }
+        atomic_write_replace(destination, &modified).map_err(|e| e.to_string())?;
+    }
     Ok(())
 }
 
@@ -391,27 +632,37 @@ fn read_u32(data: &[u8], offset: usize, order: ByteOrder) -> u32 {
     }
 }
 
+enum PatchError {
+    /// Not a TIFF block we can patch; the segment is left alone.
+    Malformed,
+    /// The subsecond offset could not be applied; the file must not be written.
+    SubSec(String),
+}
+
 /// Patch all EXIF datetime fields within the TIFF data.
+/// `subseconds`, when given, is added to SubSecTimeOriginal with the sign of
+/// `offset_seconds`; any carry moves DateTimeOriginal along with it.
 fn patch_exif_dates(
     data: &mut Vec<u8>,
     tiff_start: usize,
     seg_end: usize,
     offset_seconds: i64,
-) -> Result<bool, String> {
+    subseconds: Option<&str>,
+) -> Result<bool, PatchError> {
     if tiff_start + 8 > seg_end {
-        return Err("TIFF header too short".to_string());
+        return Err(PatchError::Malformed);
     }
 
     let order = match &data[tiff_start..tiff_start + 2] {
         b"II" => ByteOrder::Little,
         b"MM" => ByteOrder::Big,
-        _ => return Err("Unknown byte order".to_string()),
+        _ => return Err(PatchError::Malformed),
     };
 
     // Verify TIFF magic 42
     let magic = read_u16(data, tiff_start + 2, order);
     if magic != 42 {
-        return Err("Invalid TIFF magic".to_string());
+        return Err(PatchError::Malformed);
     }
 
     let ifd0_offset = read_u32(data, tiff_start + 4, order) as usize;
@@ -450,30 +701,66 @@ fn patch_exif_dates(
     }
 
     // Walk Exif IFD
-    if let Some(exif_ifd_abs) = exif_ifd_offset {
-        if exif_ifd_abs + 2 <= seg_end {
-            let entry_count = read_u16(data, exif_ifd_abs, order) as usize;
-            for i in 0..entry_count {
-                let entry_abs = exif_ifd_abs + 2 + i * 12;
-                if entry_abs + 12 > seg_end {
-                    break;
-                }
-                let tag = read_u16(data, entry_abs, order);
-                let dtype = read_u16(data, entry_abs + 2, order);
-                let count = read_u32(data, entry_abs + 4, order) as usize;
-
-                if (tag == TAG_DATETIME_ORIGINAL || tag == TAG_DATETIME_DIGITIZED)
-                    && dtype == 2
-                    && count == 20
-                {
-                    let value_offset = read_u32(data, entry_abs + 8, order) as usize;
-                    let abs_offset = tiff_start + value_offset;
-                    if abs_offset + 20 <= seg_end {
-                        if patch_datetime_at(data, abs_offset, offset_seconds) {
-                            changed = true;
-                        }
-                    }
-                }
+    let Some(exif_ifd_abs) = exif_ifd_offset.filter(|abs| abs + 2 <= seg_end) else {
+        if subseconds.is_some() {
+            return Err(PatchError::SubSec(SUBSEC_MISSING_MESSAGE.to_string()));
+        }
+        return Ok(changed);
+    };
+    let entry_count = read_u16(data, exif_ifd_abs, order) as usize;
+    let entries: Vec<usize> = (0..entry_count)
+        .map(|i| exif_ifd_abs + 2 + i * 12)
+        .take_while(|entry_abs| entry_abs + 12 <= seg_end)
+        .collect();
+
+    // The subsecond carry has to be known before DateTimeOriginal is patched.
+    let mut carry_seconds = 0i64;
+    if let Some(subseconds) = subseconds {
+        let entry_abs = entries
+            .iter()
+            .copied()
+            .find(|&entry_abs| {
+                read_u16(data, entry_abs, order) == TAG_SUBSEC_TIME_ORIGINAL
+                    && read_u16(data, entry_abs + 2, order) == 2
+            })
+            .ok_or_else(|| PatchError::SubSec(SUBSEC_MISSING_MESSAGE.to_string()))?;
+        let count = read_u32(data, entry_abs + 4, order) as usize;
+        // Values of up to 4 bytes live in the entry's value field itself.
+        let value_abs = if count <= 4 {
+            entry_abs + 8
+        } else {
+            tiff_start + read_u32(data, entry_abs + 8, order) as usize
+        };
+        if value_abs + count > seg_end {
+            return Err(PatchError::SubSec(SUBSEC_MISSING_MESSAGE.to_string()));
+        }
+        carry_seconds = add_subseconds_at(
+            &mut data[value_abs..value_abs + count],
+            subseconds,
+            offset_seconds < 0,
+        )
+        .map_err(PatchError::SubSec)?;
+        changed = true;
+    }
+
+    for entry_abs in entries {
+        let tag = read_u16(data, entry_abs, order);
+        let dtype = read_u16(data, entry_abs + 2, order);
+        let count = read_u32(data, entry_abs + 4, order) as usize;
+
+        if (tag == TAG_DATETIME_ORIGINAL || tag == TAG_DATETIME_DIGITIZED)
+            && dtype == 2
+            && count == 20
+        {
+            let value_offset = read_u32(data, entry_abs + 8, order) as usize;
+            let abs_offset = tiff_start + value_offset;
+            let offset = if tag == TAG_DATETIME_ORIGINAL {
+                offset_seconds + carry_seconds
+            } else {
+                offset_seconds
+            };
+            if abs_offset + 20 <= seg_end && patch_datetime_at(data, abs_offset, offset) {
+                changed = true;
             }
         }
     }
@@ -481,6 +768,46 @@ fn patch_exif_dates(
     Ok(changed)
 }
 
+const SUBSEC_MISSING_MESSAGE: &str = "SubSecTimeOriginal がないためサブ秒を反映できません";
+
+/// Adds (or subtracts) `subseconds` as a decimal fraction to the ASCII SubSecTime value
+/// in `field` and returns the whole seconds carried out of it.
+fn add_subseconds_at(field: &mut [u8], subseconds: &str, negative: bool) -> Result<i64, String> {
+    let current: String = field
+        .iter()
+        .take_while(|&&b| b != 0)
+        .map(|&b| b as char)
+        .collect();
+    let current = current.trim();
+    if !current.bytes().all(|b| b.is_ascii_digit()) {
+        return Err("SubSecTimeOriginal の値が不正です".to_string());
+    }
+    // One byte is kept for the NUL terminator.
+    let digits = current.len().max(subseconds.len()).min(9);
+    if digits + 1 > field.len() {
+        return Err("サブ秒の桁数が SubSecTimeOriginal の領域に収まりません".to_string());
+    }
+    let scaled = |value: &str| -> i64 {
+        let mut padded: String = value.chars().take(digits).collect();
+        while padded.len() < digits {
+            padded.push('0');
+        }
+        padded.parse().unwrap_or(0)
+    };
+    let scale = 10i64.pow(digits as u32);
+    let delta = if negative {
+        -scaled(subseconds)
+    } else {
+        scaled(subseconds)
+    };
+    let total = scaled(current) + delta;
+
+    let written = format!("{:0width$}", total.rem_euclid(scale), width = digits);
+    field.fill(0);
+    field[..digits].copy_from_slice(written.as_bytes());
+    Ok(total.div_euclid(scale))
+}
+
 /// Patch a single datetime field at the given byte offset.
 /// Returns true if the field was successfully patched.
 fn patch_datetime_at(data: &mut Vec<u8>, offset: usize, offset_seconds: i64) -> bool {
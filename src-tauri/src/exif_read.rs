@@ -0,0 +1,128 @@
+use crate::model::ExifReadResult;
+use crate::path_norm::to_windows_extended;
+use exif::{In, Reader, Value};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+
+const TAG_EXPOSURE_TIME: u16 = 0x829A;
+const TAG_F_NUMBER: u16 = 0x829D;
+const TAG_FOCAL_LENGTH: u16 = 0x920A;
+
+pub fn batch_get_exif(paths: &[String], tags: &[u16]) -> Vec<ExifReadResult> {
+    paths
+        .par_iter()
+        .map(|path| match read_fields(Path::new(path), tags) {
+            Ok(fields) => ExifReadResult {
+                path: path.clone(),
+                fields,
+                error: None,
+            },
+            Err(error) => ExifReadResult {
+                path: path.clone(),
+                fields: HashMap::new(),
+                error: Some(error),
+            },
+        })
+        .collect()
+}
+
+fn read_fields(path: &Path, tags: &[u16]) -> Result<HashMap<u16, String>, String> {
+    let file = fs::File::open(to_windows_extended(path))
+        .map_err(|e| format!("ファイルを開けません: {}", e))?;
+    let mut reader = BufReader::new(file);
+    let exif = Reader::new()
+        .read_from_container(&mut reader)
+        .map_err(|e| format!("EXIFを読み取れません: {}", e))?;
+
+    let mut fields = HashMap::new();
+    for field in exif.fields() {
+        if field.ifd_num != In::PRIMARY {
+            continue;
+        }
+        let number = field.tag.number();
+        if tags.contains(&number) && !fields.contains_key(&number) {
+            fields.insert(number, format_exif_value(number, &field.value));
+        }
+    }
+    Ok(fields)
+}
+
+/// Formats an EXIF value for display. Exposure, aperture and focal length get photographic notation.
+pub fn format_exif_value(tag: u16, value: &Value) -> String {
+    match (tag, value) {
+        (TAG_EXPOSURE_TIME, Value::Rational(values)) if !values.is_empty() => {
+            let exposure = values[0];
+            if exposure.num == 0 || exposure.denom == 0 {
+                "0".to_string()
+            } else if exposure.num < exposure.denom {
+                format!(
+                    "1/{}",
+                    (exposure.denom as f64 / exposure.num as f64).round()
+                )
+            } else {
+                format!("{}s", trim_decimal(exposure.to_f64()))
+            }
+        }
+        (TAG_F_NUMBER, Value::Rational(values)) if !values.is_empty() && values[0].denom != 0 => {
+            format!("f/{}", trim_decimal(values[0].to_f64()))
+        }
+        (TAG_FOCAL_LENGTH, Value::Rational(values))
+            if !values.is_empty() && values[0].denom != 0 =>
+        {
+            format!("{}mm", trim_decimal(values[0].to_f64()))
+        }
+        (_, Value::Rational(values)) => join(values.iter().map(|v| {
+            if v.denom == 0 {
+                format!("{}/{}", v.num, v.denom)
+            } else {
+                trim_decimal(v.to_f64())
+            }
+        })),
+        (_, Value::SRational(values)) => join(values.iter().map(|v| {
+            if v.denom == 0 {
+                format!("{}/{}", v.num, v.denom)
+            } else {
+                trim_decimal(v.to_f64())
+            }
+        })),
+        (_, Value::Short(values)) => join(values.iter().map(u16::to_string)),
+        (_, Value::Long(values)) => join(values.iter().map(u32::to_string)),
+        (_, Value::SShort(values)) => join(values.iter().map(i16::to_string)),
+        (_, Value::SLong(values)) => join(values.iter().map(i32::to_string)),
+        (_, Value::SByte(values)) => join(values.iter().map(i8::to_string)),
+        (_, Value::Float(values)) => join(values.iter().map(|v| trim_decimal(f64::from(*v)))),
+        (_, Value::Double(values)) => join(values.iter().map(|v| trim_decimal(*v))),
+        (_, Value::Ascii(values)) => values
+            .iter()
+            .map(|bytes| {
+                String::from_utf8_lossy(bytes)
+                    .trim_end_matches('\0')
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        (_, Value::Byte(bytes)) | (_, Value::Undefined(bytes, _)) => {
+            if bytes.len() <= 16 {
+                join(bytes.iter().map(|b| format!("{:02X}", b)))
+            } else {
+                format!("{} bytes", bytes.len())
+            }
+        }
+        (_, Value::Unknown(..)) => String::new(),
+    }
+}
+
+fn join(values: impl Iterator<Item = String>) -> String {
+    values.collect::<Vec<_>>().join(", ")
+}
+
+fn trim_decimal(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
@@ -1,5 +1,6 @@
-use crate::path_norm::safe_canonicalize;
-use std::collections::BTreeSet;
+use crate::model::ExtensionCount;
+use crate::path_norm::{path_depth_below, safe_canonicalize, to_windows_extended};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
@@ -11,6 +12,54 @@ pub const RENAME_ALLOWED_EXTENSIONS: &[&str] = &[
 ];
 
 pub const JPEG_ALLOWED_EXTENSIONS: &[&str] = &["jpg", "jpeg"];
+/// Standalone XMP sidecars; metadata strip accepts them when `xmp` is selected.
+pub const XMP_ALLOWED_EXTENSIONS: &[&str] = &["xmp"];
+
+/// Collection stops at this many files unless the request sets `max_files`.
+pub const DEFAULT_MAX_FILES: usize = 10_000;
+pub const MAX_FILES_WARNING: &str = "ファイル数の上限に達しました";
+
+/// Allowed extensions per operation; `None` means any extension is accepted.
+pub fn supported_extensions() -> HashMap<String, Option<Vec<String>>> {
+    let to_vec = |list: &[&str]| Some(list.iter().map(|ext| ext.to_string()).collect());
+    HashMap::from([
+        ("rename".to_string(), to_vec(RENAME_ALLOWED_EXTENSIONS)),
+        ("compress".to_string(), to_vec(JPEG_ALLOWED_EXTENSIONS)),
+        (
+            "metadataStrip".to_string(),
+            to_vec(&[JPEG_ALLOWED_EXTENSIONS, XMP_ALLOWED_EXTENSIONS].concat()),
+        ),
+        ("exifOffset".to_string(), to_vec(JPEG_ALLOWED_EXTENSIONS)),
+        ("reorient".to_string(), to_vec(JPEG_ALLOWED_EXTENSIONS)),
+        ("delete".to_string(), None),
+    ])
+}
+
+/// Per-extension file count and size, sorted by extension. Extensions are lowercased.
+pub fn count_by_extension(files: &[PathBuf]) -> Vec<ExtensionCount> {
+    let mut counts: BTreeMap<String, (usize, u64)> = BTreeMap::new();
+    for file in files {
+        let extension = file
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        let size = fs::metadata(to_windows_extended(file))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let entry = counts.entry(extension).or_default();
+        entry.0 += 1;
+        entry.1 += size;
+    }
+    counts
+        .into_iter()
+        .map(|(extension, (count, total_size))| ExtensionCount {
+            extension,
+            count,
+            total_size,
+        })
+        .collect()
+}
 
 #[derive(Debug, Clone)]
 pub struct CollectResult {
@@ -18,20 +67,95 @@ pub struct CollectResult {
     pub input_root: Option<PathBuf>,
     pub single_input_root: bool,
     pub skipped_by_extension: usize,
+    /// Entries below the input paths that could not be read; they are left out of `files`.
+    pub permission_denied: Vec<PathBuf>,
+    /// A matching file was left out because `max_files` had been reached.
+    pub truncated: bool,
 }
 
+impl CollectResult {
+    /// Restores all-or-nothing semantics when `fail_on_permission_denied` is requested.
+    pub fn ensure_accessible(&self, fail_on_permission_denied: bool) -> Result<(), String> {
+        match self.permission_denied.first() {
+            Some(path) if fail_on_permission_denied => Err(format!(
+                "アクセスが拒否されました（{}件）: {}",
+                self.permission_denied.len(),
+                path.to_string_lossy()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Top-level warnings for preview and execute responses, including every entry
+    /// skipped because access was denied.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.truncated {
+            warnings.push(MAX_FILES_WARNING.to_string());
+        }
+        warnings.extend(self.permission_denied.iter().map(|path| {
+            format!(
+                "アクセスが拒否されたため除外しました: {}",
+                path.to_string_lossy()
+            )
+        }));
+        warnings
+    }
+}
+
+/// `max_depth` as accepted by `collect_targets_with_depth`; see `depth_limit`.
 pub fn collect_rename_targets(
+    input_paths: &[String],
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+) -> Result<CollectResult, String> {
+    collect_targets_with_depth(input_paths, max_depth, RENAME_ALLOWED_EXTENSIONS, max_files)
+}
+
+/// Combines the subfolder toggle with an optional request depth; a depth of 0 is
+/// treated as 1 so the input folder's own files are always included.
+pub fn depth_limit(include_subfolders: bool, max_depth: Option<usize>) -> Option<usize> {
+    if include_subfolders {
+        max_depth.map(|depth| depth.max(1))
+    } else {
+        Some(1)
+    }
+}
+
+/// Collects every file regardless of extension.
+pub fn collect_all_targets(
     input_paths: &[String],
     include_subfolders: bool,
+    max_files: Option<usize>,
 ) -> Result<CollectResult, String> {
-    collect_targets_with_extensions(input_paths, include_subfolders, RENAME_ALLOWED_EXTENSIONS)
+    collect_targets_with_extensions(input_paths, include_subfolders, &[], max_files)
 }
 
+/// An empty `allowed_extensions` slice accepts all files. `max_files` defaults to
+/// `DEFAULT_MAX_FILES`; reaching it stops the scan and sets `truncated`.
 pub fn collect_targets_with_extensions(
     input_paths: &[String],
     include_subfolders: bool,
     allowed_extensions: &[&str],
+    max_files: Option<usize>,
+) -> Result<CollectResult, String> {
+    collect_targets_with_depth(
+        input_paths,
+        (!include_subfolders).then_some(1),
+        allowed_extensions,
+        max_files,
+    )
+}
+
+/// `max_depth` counts levels below each input folder: `Some(1)` is the folder's own
+/// files, `None` is unlimited.
+pub fn collect_targets_with_depth(
+    input_paths: &[String],
+    max_depth: Option<usize>,
+    allowed_extensions: &[&str],
+    max_files: Option<usize>,
 ) -> Result<CollectResult, String> {
+    let max_files = max_files.unwrap_or(DEFAULT_MAX_FILES);
     if input_paths.is_empty() {
         return Err("入力パスが指定されていません".to_string());
     }
@@ -50,9 +174,18 @@ pub fn collect_targets_with_extensions(
 
     let mut files = BTreeSet::new();
     let mut skipped_by_extension = 0usize;
+    let mut permission_denied = Vec::new();
+    let mut truncated = false;
     for path in &resolved_inputs {
+        if truncated {
+            break;
+        }
         if path.is_file() {
             if has_allowed_extension(path, allowed_extensions) {
+                if files.len() >= max_files {
+                    truncated = true;
+                    break;
+                }
                 files.insert(path.clone());
             } else {
                 skipped_by_extension += 1;
@@ -62,9 +195,12 @@ pub fn collect_targets_with_extensions(
         if path.is_dir() {
             skipped_by_extension += collect_from_dir(
                 path,
-                include_subfolders,
+                max_depth,
                 allowed_extensions,
                 &mut files,
+                &mut permission_denied,
+                max_files,
+                &mut truncated,
             )?;
         }
     }
@@ -82,25 +218,54 @@ pub fn collect_targets_with_extensions(
         single_input_root: input_root.is_some(),
         input_root,
         skipped_by_extension,
+        permission_denied,
+        truncated,
     })
 }
 
 fn collect_from_dir(
     dir: &Path,
-    include_subfolders: bool,
+    max_depth: Option<usize>,
     allowed_extensions: &[&str],
     files: &mut BTreeSet<PathBuf>,
+    permission_denied: &mut Vec<PathBuf>,
+    max_files: usize,
+    truncated: &mut bool,
 ) -> Result<usize, String> {
     let mut skipped = 0usize;
-    if include_subfolders {
-        for entry in WalkDir::new(dir).into_iter() {
-            let entry = entry.map_err(|e| format!("フォルダの走査に失敗しました: {}", e))?;
+    if max_depth != Some(1) {
+        let walker = WalkDir::new(dir).into_iter().filter_entry(|entry| {
+            max_depth.is_none_or(|max| {
+                path_depth_below(entry.path(), dir).is_some_and(|depth| depth <= max)
+            })
+        });
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                // Only the input folder itself is fatal; denied subfolders are reported.
+                Err(error) if error.depth() > 0 && is_permission_denied(error.io_error()) => {
+                    permission_denied.push(error.path().unwrap_or(dir).to_path_buf());
+                    continue;
+                }
+                Err(error) => return Err(format!("フォルダの走査に失敗しました: {}", error)),
+            };
             if entry.file_type().is_file() {
                 if has_allowed_extension(entry.path(), allowed_extensions) {
-                    files.insert(
-                        safe_canonicalize(entry.path())
-                            .map_err(|e| format!("パスの正規化に失敗しました: {}", e))?,
-                    );
+                    if files.len() >= max_files {
+                        *truncated = true;
+                        break;
+                    }
+                    match safe_canonicalize(entry.path()) {
+                        Ok(path) => {
+                            files.insert(path);
+                        }
+                        Err(error) if is_permission_denied(Some(&error)) => {
+                            permission_denied.push(entry.path().to_path_buf());
+                        }
+                        Err(error) => {
+                            return Err(format!("パスの正規化に失敗しました: {}", error));
+                        }
+                    }
                 } else {
                     skipped += 1;
                 }
@@ -111,14 +276,37 @@ fn collect_from_dir(
 
     let entries = fs::read_dir(dir).map_err(|e| format!("フォルダの読み込みに失敗しました: {}", e))?;
     for entry in entries {
-        let entry = entry.map_err(|e| format!("フォルダエントリの読み込みに失敗しました: {}", e))?;
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) if is_permission_denied(Some(&error)) => {
+                permission_denied.push(dir.to_path_buf());
+                continue;
+            }
+            Err(error) => {
+                return Err(format!(
+                    "フォルダエントリの読み込みに失敗しました: {}",
+                    error
+                ))
+            }
+        };
         let path = entry.path();
         if path.is_file() {
             if has_allowed_extension(&path, allowed_extensions) {
-                files.insert(
-                    safe_canonicalize(&path)
-                        .map_err(|e| format!("パスの正規化に失敗しました: {}", e))?,
-                );
+                if files.len() >= max_files {
+                    *truncated = true;
+                    break;
+                }
+                match safe_canonicalize(&path) {
+                    Ok(canonical) => {
+                        files.insert(canonical);
+                    }
+                    Err(error) if is_permission_denied(Some(&error)) => {
+                        permission_denied.push(path);
+                    }
+                    Err(error) => {
+                        return Err(format!("パスの正規化に失敗しました: {}", error));
+                    }
+                }
             } else {
                 skipped += 1;
             }
@@ -127,17 +315,39 @@ fn collect_from_dir(
     Ok(skipped)
 }
 
+fn is_permission_denied(error: Option<&std::io::Error>) -> bool {
+    error.is_some_and(|error| error.kind() == std::io::ErrorKind::PermissionDenied)
+}
+
 fn has_allowed_extension(path: &Path, allowed: &[&str]) -> bool {
+    if allowed.is_empty() {
+        return true;
+    }
     path.extension()
         .and_then(|ext| ext.to_str())
         .map(|ext| {
             let ext = ext.to_ascii_lowercase();
-            allowed.iter().any(|item| *item == ext)
+            let ext = normalize_extension_alias(&ext);
+            allowed
+                .iter()
+                .any(|item| normalize_extension_alias(item) == ext)
         })
         .unwrap_or(false)
 }
 
-fn find_common_parent(files: &[PathBuf]) -> Option<PathBuf> {
+/// Maps a lowercase extension to its canonical spelling so that e.g. `jpeg`
+/// and `jpg` match each other.
+pub fn normalize_extension_alias(ext: &str) -> &str {
+    match ext {
+        "jpeg" => "jpg",
+        "tiff" => "tif",
+        "mpeg" => "mpg",
+        other => other,
+    }
+}
+
+/// Deepest folder that contains the parent of every given path.
+pub fn find_common_parent(files: &[PathBuf]) -> Option<PathBuf> {
     if files.is_empty() {
         return None;
     }
@@ -0,0 +1,148 @@
+use crate::error::AppError;
+use crate::model::{FileInfo, FileInfoResult, PathValidation};
+use crate::path_norm::to_windows_extended;
+use chrono::{DateTime, Local};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static PROBE_SEQ: AtomicU64 = AtomicU64::new(0);
+
+pub fn get_file_info(path: &str) -> Result<FileInfo, AppError> {
+    let target = to_windows_extended(Path::new(path));
+    let metadata = fs::metadata(&target).map_err(|e| {
+        AppError::InvalidRequest(format!("ファイル情報を取得できません `{}`: {}", path, e))
+    })?;
+    let is_directory = metadata.is_dir();
+
+    let modified_at = metadata
+        .modified()
+        .map(|time| DateTime::<Local>::from(time).to_rfc3339())
+        .map_err(AppError::from)?;
+    let created_at = metadata
+        .created()
+        .ok()
+        .map(|time| DateTime::<Local>::from(time).to_rfc3339());
+
+    // imagesize only parses the header, so this stays cheap for non-images too.
+    let dimensions = if is_directory {
+        None
+    } else {
+        imagesize::size(&target).ok()
+    };
+
+    Ok(FileInfo {
+        path: path.to_string(),
+        size_bytes: if is_directory { 0 } else { metadata.len() },
+        modified_at,
+        created_at,
+        image_width: dimensions.as_ref().map(|size| size.width as u32),
+        image_height: dimensions.as_ref().map(|size| size.height as u32),
+        is_directory,
+    })
+}
+
+/// Unreadable paths get an `error` entry instead of failing the whole batch.
+pub fn batch_get_file_info(paths: &[String]) -> Vec<FileInfoResult> {
+    paths
+        .par_iter()
+        .map(|path| match get_file_info(path) {
+            Ok(info) => FileInfoResult {
+                path: path.to_string(),
+                info: Some(info),
+                error: None,
+            },
+            Err(error) => FileInfoResult {
+                path: path.to_string(),
+                info: None,
+                error: Some(error.to_string()),
+            },
+        })
+        .collect()
+}
+
+pub fn validate_paths(paths: &[String]) -> Vec<PathValidation> {
+    // Probe each folder once: parallel probes of a folder listed twice would collide.
+    let dirs: HashSet<PathBuf> = paths
+        .iter()
+        .map(|path| to_windows_extended(Path::new(path.trim())))
+        .filter(|target| target.is_dir())
+        .collect();
+    let writable_dirs: HashMap<PathBuf, bool> = dirs
+        .into_par_iter()
+        .map(|dir| {
+            let writable = directory_accepts_files(&dir);
+            (dir, writable)
+        })
+        .collect();
+    paths
+        .par_iter()
+        .map(|path| validate_path(path, &writable_dirs))
+        .collect()
+}
+
+fn validate_path(path: &str, writable_dirs: &HashMap<PathBuf, bool>) -> PathValidation {
+    let target = to_windows_extended(Path::new(path.trim()));
+    let Ok(metadata) = fs::metadata(&target) else {
+        return PathValidation {
+            path: path.to_string(),
+            exists: false,
+            is_file: false,
+            is_directory: false,
+            is_readable: false,
+            is_writable: false,
+            size_bytes: None,
+        };
+    };
+    let is_directory = metadata.is_dir();
+    let (is_readable, is_writable) = if is_directory {
+        (
+            fs::read_dir(&target).is_ok(),
+            writable_dirs.get(&target).copied().unwrap_or(false),
+        )
+    } else {
+        let parent_writable = target
+            .parent()
+            .and_then(|parent| fs::metadata(parent).ok())
+            .is_some_and(|parent| !parent.permissions().readonly());
+        (
+            fs::File::open(&target).is_ok(),
+            parent_writable && !metadata.permissions().readonly(),
+        )
+    };
+
+    PathValidation {
+        path: path.to_string(),
+        exists: true,
+        is_file: metadata.is_file(),
+        is_directory,
+        is_readable,
+        is_writable,
+        size_bytes: (!is_directory).then_some(metadata.len()),
+    }
+}
+
+/// Directory permission bits are unreliable on Windows, so probe with a real file.
+/// The name is unique per probe so a leftover from a crashed run cannot block it.
+fn directory_accepts_files(dir: &Path) -> bool {
+    let probe = dir.join(format!(
+        ".cfm-write-probe-{}-{}-{}",
+        std::process::id(),
+        Local::now().timestamp_nanos_opt().unwrap_or(0),
+        PROBE_SEQ.fetch_add(1, Ordering::Relaxed)
+    ));
+    match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+    {
+        Ok(file) => {
+            drop(file);
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
@@ -1,11 +1,21 @@
 use crate::error::AppError;
-use crate::fs_atomic::atomic_copy_replace;
-use crate::path_norm::safe_canonicalize;
+use crate::file_collect::{find_common_parent, DEFAULT_MAX_FILES, MAX_FILES_WARNING};
+use crate::fs_atomic::{
+    atomic_copy_replace, atomic_move_replace, atomic_write_replace, dry_run_check,
+    remove_emptied_dirs, AtomicMoveNote,
+};
+use crate::path_norm::{
+    destination_key, normalize_unicode, safe_canonicalize, to_windows_extended,
+    validate_output_suffix,
+};
 use crate::model::{
-    CollisionPolicy, ExecuteStatus, FlattenExecuteDetail, FlattenExecuteResponse,
-    FlattenPreviewItem, FlattenPreviewRequest, FlattenPreviewResponse, OperationProgressEvent,
-    PreviewStatus,
+    CollisionPolicy, DateGroupBy, ExecuteStatus, FlattenExecuteDetail, FlattenExecuteResponse,
+    FlattenPreviewItem, FlattenPreviewRequest, FlattenPreviewResponse, FlattenUndoEntry,
+    OperationProgressEvent, OperationSummary, PreviewStatus, RenameSource,
 };
+use crate::organize::{date_path, parse_date_format};
+use crate::progress_eta::EtaTracker;
+use crate::rename::resolve_timestamp;
 use chrono::Local;
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
@@ -22,10 +32,34 @@ struct PlannedFlatten {
     destination: PathBuf,
     status: PreviewStatus,
     reason: Option<String>,
+    sidecar_of: Option<PathBuf>,
 }
 
+struct FlattenPlan {
+    output_dir: PathBuf,
+    plan: Vec<PlannedFlatten>,
+    collisions: usize,
+    hidden_files_skipped: usize,
+    /// Collection warnings such as hitting `max_files`.
+    warnings: Vec<String>,
+}
+
+/// Sidecar extensions kept next to their primary file when pairing is enabled.
+const SIDECAR_EXTENSIONS: &[&str] = &["xmp", "thm", "pp3", "dop"];
+
+/// OS metadata files skipped unless `include_system_files` is set (case-insensitive).
+const DEFAULT_SYSTEM_EXCLUDES: &[&str] = &[".DS_Store", "desktop.ini", "Thumbs.db"];
+
 pub fn preview(request: &FlattenPreviewRequest) -> Result<FlattenPreviewResponse, AppError> {
-    let (output_dir, plan, collisions) = build_plan(request)?;
+    let FlattenPlan {
+        output_dir,
+        plan,
+        collisions,
+        hidden_files_skipped,
+        mut warnings,
+    } = build_plan(request)?;
+    let in_place = request.in_place.unwrap_or(false);
+    let action = if in_place { "move" } else { "copy" };
     let mut ready = 0usize;
     let mut skipped = 0usize;
     let items = plan
@@ -40,10 +74,23 @@ pub fn preview(request: &FlattenPreviewRequest) -> Result<FlattenPreviewResponse
                 destination_path: item.destination.to_string_lossy().to_string(),
                 status: item.status.clone(),
                 reason: item.reason.clone(),
+                is_sidecar_of: item
+                    .sidecar_of
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().to_string()),
+                action: action.to_string(),
             }
         })
         .collect();
 
+    if in_place {
+        warnings.push("元のサブフォルダ構造が削除されます".to_string());
+    } else {
+        for input_dir in resolve_input_dirs(request)? {
+            warnings.extend(adjacent_output_warning(&input_dir, &output_dir));
+        }
+    }
+
     Ok(FlattenPreviewResponse {
         output_dir: output_dir.to_string_lossy().to_string(),
         items,
@@ -51,6 +98,8 @@ pub fn preview(request: &FlattenPreviewRequest) -> Result<FlattenPreviewResponse
         ready,
         skipped,
         collisions,
+        hidden_files_skipped,
+        warnings,
     })
 }
 
@@ -63,8 +112,20 @@ where
     FCancel: Fn() -> bool,
     FProgress: FnMut(OperationProgressEvent),
 {
-    let (output_dir, plan, _) = build_plan(request)?;
-    fs::create_dir_all(&output_dir)?;
+    let FlattenPlan {
+        output_dir,
+        plan,
+        warnings,
+        ..
+    } = build_plan(request)?;
+    if is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
+    let dry_run = request.dry_run.unwrap_or(false);
+    let in_place = request.in_place.unwrap_or(false);
+    if !dry_run {
+        fs::create_dir_all(&output_dir)?;
+    }
 
     let total = plan.len();
     let mut details = Vec::with_capacity(total);
@@ -73,6 +134,19 @@ where
     let mut skipped = 0usize;
     let mut processed = 0usize;
     let mut canceled = false;
+    let mut eta = EtaTracker::new();
+    let source_sizes: HashMap<String, u64> = plan
+        .iter()
+        .filter(|item| matches!(item.status, PreviewStatus::Ready))
+        .map(|item| {
+            let size = fs::metadata(to_windows_extended(&item.source))
+                .map(|metadata| metadata.len())
+                .unwrap_or(0);
+            (item.source.to_string_lossy().to_string(), size)
+        })
+        .collect();
+    let total_bytes: u64 = source_sizes.values().sum();
+    let mut bytes_processed = 0u64;
 
     let cancel_requested = Arc::new(AtomicBool::new(false));
     let worker_cancel = Arc::clone(&cancel_requested);
@@ -83,7 +157,12 @@ where
         worker_plan
             .into_par_iter()
             .for_each_with(tx, |sender, item| {
-                let detail = execute_one_flatten(&item, worker_cancel.load(Ordering::SeqCst));
+                let detail = execute_one_flatten(
+                    &item,
+                    worker_cancel.load(Ordering::SeqCst),
+                    dry_run,
+                    in_place,
+                );
                 let _ = sender.send(detail);
             });
     });
@@ -98,7 +177,11 @@ where
             Ok(detail) => {
                 processed += 1;
                 match detail.status {
-                    ExecuteStatus::Succeeded => succeeded += 1,
+                    ExecuteStatus::Succeeded => {
+                        succeeded += 1;
+                        bytes_processed +=
+                            source_sizes.get(&detail.source_path).copied().unwrap_or(0);
+                    }
                     ExecuteStatus::Failed => failed += 1,
                     ExecuteStatus::Skipped => skipped += 1,
                 }
@@ -114,6 +197,9 @@ where
                     current_path,
                     done: false,
                     canceled,
+                    eta_seconds: eta.record(total.saturating_sub(processed)),
+                    bytes_processed,
+                    total_bytes,
                 });
             }
             Err(mpsc::RecvTimeoutError::Timeout) => continue,
@@ -123,6 +209,21 @@ where
 
     let _ = worker.join();
 
+    if in_place && !dry_run && !canceled {
+        remove_emptied_source_dirs(&output_dir, &details);
+    }
+
+    if let Some(manifest_path) = request
+        .undo_manifest_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        if !dry_run {
+            write_undo_manifest(Path::new(manifest_path), &details, !in_place)?;
+        }
+    }
+
     report_progress(OperationProgressEvent {
         operation: "flatten".to_string(),
         processed,
@@ -133,19 +234,144 @@ where
         current_path: None,
         done: true,
         canceled,
+        eta_seconds: None,
+        bytes_processed,
+        total_bytes,
     });
 
     Ok(FlattenExecuteResponse {
         output_dir: output_dir.to_string_lossy().to_string(),
-        processed: succeeded + failed + skipped,
-        succeeded,
-        failed,
-        skipped,
+        summary: OperationSummary::new(
+            "flatten",
+            succeeded + failed + skipped,
+            succeeded,
+            failed,
+            skipped,
+            None,
+            canceled,
+        )
+        .with_warnings(warnings),
         details,
     })
 }
 
-fn execute_one_flatten(item: &PlannedFlatten, canceled: bool) -> FlattenExecuteDetail {
+/// Replay a flatten undo manifest: moved files go back to their original path, copies
+/// are deleted while the original is still there.
+pub fn execute_undo(manifest_path: &str) -> Result<FlattenExecuteResponse, AppError> {
+    let manifest_path = PathBuf::from(manifest_path.trim());
+    let body = fs::read_to_string(&manifest_path).map_err(|e| {
+        AppError::InvalidRequest(format!("Undoマニフェストを読み込めません: {}", e))
+    })?;
+    let entries: Vec<FlattenUndoEntry> = serde_json::from_str(&body).map_err(|e| {
+        AppError::InvalidRequest(format!("Undoマニフェストの形式が不正です: {}", e))
+    })?;
+
+    let mut details = Vec::with_capacity(entries.len());
+    let mut succeeded = 0usize;
+    let mut failed = 0usize;
+    let mut skipped = 0usize;
+
+    for entry in &entries {
+        let from = PathBuf::from(&entry.from);
+        let to = PathBuf::from(&entry.to);
+        let (status, reason) = if !from.is_file() {
+            skipped += 1;
+            (
+                ExecuteStatus::Skipped,
+                Some("元に戻すファイルが見つかりません".to_string()),
+            )
+        } else if entry.copied && to.is_file() {
+            match fs::remove_file(to_windows_extended(&from)) {
+                Ok(()) => {
+                    succeeded += 1;
+                    (
+                        ExecuteStatus::Succeeded,
+                        Some("複製を削除しました".to_string()),
+                    )
+                }
+                Err(error) => {
+                    failed += 1;
+                    (
+                        ExecuteStatus::Failed,
+                        Some(format!("複製の削除に失敗しました: {}", error)),
+                    )
+                }
+            }
+        } else if let Err(error) = to.parent().map_or(Ok(()), |parent| {
+            fs::create_dir_all(to_windows_extended(parent))
+        }) {
+            failed += 1;
+            (
+                ExecuteStatus::Failed,
+                Some(format!("出力先フォルダの作成に失敗しました: {}", error)),
+            )
+        } else {
+            match atomic_move_replace(&from, &to) {
+                Ok(note) => {
+                    succeeded += 1;
+                    (ExecuteStatus::Succeeded, note.into_reason())
+                }
+                Err(error) => {
+                    failed += 1;
+                    (ExecuteStatus::Failed, Some(error))
+                }
+            }
+        };
+        details.push(FlattenExecuteDetail {
+            source_path: entry.from.clone(),
+            destination_path: entry.to.clone(),
+            status,
+            reason,
+        });
+    }
+
+    let output_dir = entries
+        .first()
+        .and_then(|entry| Path::new(&entry.from).parent())
+        .map(|parent| parent.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(FlattenExecuteResponse {
+        output_dir,
+        summary: OperationSummary::new(
+            "flatten",
+            succeeded + failed + skipped,
+            succeeded,
+            failed,
+            skipped,
+            None,
+            false,
+        ),
+        details,
+    })
+}
+
+fn write_undo_manifest(
+    path: &Path,
+    details: &[FlattenExecuteDetail],
+    copied: bool,
+) -> Result<(), AppError> {
+    let entries: Vec<FlattenUndoEntry> = details
+        .iter()
+        .filter(|detail| matches!(detail.status, ExecuteStatus::Succeeded))
+        .map(|detail| FlattenUndoEntry {
+            from: detail.destination_path.clone(),
+            to: detail.source_path.clone(),
+            copied,
+        })
+        .collect();
+    let body = serde_json::to_string_pretty(&entries).map_err(|e| AppError::Io(e.to_string()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    atomic_write_replace(path, body.as_bytes())
+}
+
+fn execute_one_flatten(
+    item: &PlannedFlatten,
+    canceled: bool,
+    dry_run: bool,
+    in_place: bool,
+) -> FlattenExecuteDetail {
     if canceled || matches!(item.status, PreviewStatus::Skipped) {
         return FlattenExecuteDetail {
             source_path: item.source.to_string_lossy().to_string(),
@@ -159,6 +385,23 @@ fn execute_one_flatten(item: &PlannedFlatten, canceled: bool) -> FlattenExecuteD
         };
     }
 
+    if dry_run {
+        return match dry_run_check(&item.source) {
+            Ok(note) => FlattenExecuteDetail {
+                source_path: item.source.to_string_lossy().to_string(),
+                destination_path: item.destination.to_string_lossy().to_string(),
+                status: ExecuteStatus::Succeeded,
+                reason: note,
+            },
+            Err(error) => FlattenExecuteDetail {
+                source_path: item.source.to_string_lossy().to_string(),
+                destination_path: item.destination.to_string_lossy().to_string(),
+                status: ExecuteStatus::Failed,
+                reason: Some(error),
+            },
+        };
+    }
+
     if let Some(parent) = item.destination.parent() {
         if let Err(error) = fs::create_dir_all(parent) {
             return FlattenExecuteDetail {
@@ -170,12 +413,17 @@ fn execute_one_flatten(item: &PlannedFlatten, canceled: bool) -> FlattenExecuteD
         }
     }
 
-    match atomic_copy_replace(&item.source, &item.destination) {
-        Ok(()) => FlattenExecuteDetail {
+    let result = if in_place {
+        atomic_move_replace(&item.source, &item.destination).map(AtomicMoveNote::into_reason)
+    } else {
+        atomic_copy_replace(&item.source, &item.destination).map(|()| None)
+    };
+    match result {
+        Ok(reason) => FlattenExecuteDetail {
             source_path: item.source.to_string_lossy().to_string(),
             destination_path: item.destination.to_string_lossy().to_string(),
             status: ExecuteStatus::Succeeded,
-            reason: None,
+            reason,
         },
         Err(error) => FlattenExecuteDetail {
             source_path: item.source.to_string_lossy().to_string(),
@@ -186,36 +434,116 @@ fn execute_one_flatten(item: &PlannedFlatten, canceled: bool) -> FlattenExecuteD
     }
 }
 
-fn build_plan(
-    request: &FlattenPreviewRequest,
-) -> Result<(PathBuf, Vec<PlannedFlatten>, usize), AppError> {
-    let input_dir = PathBuf::from(request.input_dir.trim());
-    if !input_dir.exists() {
+/// Removes subfolders left empty by an in-place flatten, walking up from the source
+/// folders of files this run moved. Pre-existing empty folders and folders that still
+/// hold files (e.g. skipped hidden files) are kept.
+fn remove_emptied_source_dirs(root: &Path, details: &[FlattenExecuteDetail]) {
+    let parents = details
+        .iter()
+        .filter(|detail| matches!(detail.status, ExecuteStatus::Succeeded))
+        .filter_map(|detail| {
+            Path::new(&detail.source_path)
+                .parent()
+                .map(Path::to_path_buf)
+        });
+    remove_emptied_dirs(parents, &[root.to_path_buf()]);
+}
+
+fn is_system_file(name: &str) -> bool {
+    DEFAULT_SYSTEM_EXCLUDES
+        .iter()
+        .any(|excluded| excluded.eq_ignore_ascii_case(name))
+}
+
+fn build_plan(request: &FlattenPreviewRequest) -> Result<FlattenPlan, AppError> {
+    let input_dirs = resolve_input_dirs(request)?;
+    let in_place = request.in_place.unwrap_or(false);
+    if in_place && input_dirs.len() > 1 {
         return Err(AppError::InvalidRequest(
-            "入力フォルダが存在しません".to_string(),
+            "インプレース平坦化では入力フォルダを1つだけ指定してください".to_string(),
         ));
     }
-    if !input_dir.is_dir() {
+    let output_root = match input_dirs.as_slice() {
+        [single] => Some(single.clone()),
+        _ => find_common_parent(&input_dirs),
+    };
+
+    if let Some(suffix) = request.output_suffix.as_deref() {
+        validate_output_suffix(suffix).map_err(AppError::InvalidRequest)?;
+    }
+    let output_dir = match output_root.as_deref() {
+        Some(root) if in_place => root.to_path_buf(),
+        Some(root) => resolve_output_dir(
+            root,
+            request.output_dir.as_deref(),
+            request.output_suffix.as_deref().unwrap_or("_flattened_"),
+        )?,
+        None => match request.output_dir.as_deref().map(str::trim) {
+            Some(raw) if !raw.is_empty() => PathBuf::from(raw),
+            _ => {
+                return Err(AppError::InvalidRequest(
+                    "共通の親フォルダがないため出力先フォルダの指定が必要です".to_string(),
+                ));
+            }
+        },
+    };
+    for input_dir in &input_dirs {
+        validate_output_dir(input_dir, &output_dir, in_place)?;
+    }
+    // Capture dates fall back to the modified time, as in rename/organize.
+    let date_source = match request.group_by_date {
+        Some(DateGroupBy::ByModified) => Some(RenameSource::ModifiedOnly),
+        Some(DateGroupBy::ByCaptureDate) => Some(RenameSource::CaptureThenModified),
+        Some(DateGroupBy::None) | None => None,
+    };
+    if in_place && date_source.is_some() {
         return Err(AppError::InvalidRequest(
-            "入力パスはフォルダである必要があります".to_string(),
+            "インプレース平坦化では日付フォルダを作成できません".to_string(),
         ));
     }
-    let input_dir = safe_canonicalize(&input_dir).map_err(AppError::from)?;
-
-    let output_dir = resolve_output_dir(&input_dir, request.output_dir.as_deref())?;
-    validate_output_dir(&input_dir, &output_dir)?;
+    let date_segments = match date_source {
+        Some(_) => parse_date_format(request.date_group_format.as_deref().unwrap_or("YYYY-MM-DD"))?,
+        None => Vec::new(),
+    };
+    let ffprobe_cache = HashMap::new();
 
-    let mut sources: Vec<PathBuf> = WalkDir::new(&input_dir)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|entry| entry.file_type().is_file())
-        .map(|entry| entry.path().to_path_buf())
+    let mut sources: Vec<PathBuf> = input_dirs
+        .iter()
+        .flat_map(|input_dir| {
+            WalkDir::new(input_dir)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_file())
+                .map(|entry| entry.path().to_path_buf())
+        })
         .collect();
+    let include_hidden = request.include_hidden.unwrap_or(false);
+    let include_system_files = request.include_system_files.unwrap_or(false);
+    let before_filter = sources.len();
+    sources.retain(|source| {
+        let name = source
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("");
+        (include_hidden || !name.starts_with('.'))
+            && (include_system_files || !is_system_file(name))
+    });
+    let hidden_files_skipped = before_filter - sources.len();
+    if in_place {
+        // Files already at the top level stay put; they still count as collision targets.
+        sources.retain(|source| source.parent() != Some(output_dir.as_path()));
+    }
     sources.sort_by(|a, b| {
         a.to_string_lossy()
             .to_ascii_lowercase()
             .cmp(&b.to_string_lossy().to_ascii_lowercase())
     });
+    let max_files = request.max_files.unwrap_or(DEFAULT_MAX_FILES);
+    let mut warnings = Vec::new();
+    if sources.len() > max_files {
+        sources.truncate(max_files);
+        warnings.push(MAX_FILES_WARNING.to_string());
+    }
 
     if sources.is_empty() {
         return Err(AppError::InvalidRequest(
@@ -223,17 +551,54 @@ fn build_plan(
         ));
     }
 
+    let mut sidecars_by_primary = if request.preserve_sidecar_pairing.unwrap_or(false) {
+        pair_sidecars(&sources)
+    } else {
+        HashMap::new()
+    };
+    let paired: HashSet<PathBuf> = sidecars_by_primary.values().flatten().cloned().collect();
+
     let mut used_destinations = HashSet::new();
     let mut collisions = 0usize;
     let mut plan = Vec::with_capacity(sources.len());
     for source in sources {
+        if paired.contains(&source) {
+            continue;
+        }
+        let sidecars = sidecars_by_primary.remove(&source).unwrap_or_default();
         let file_name = source
             .file_name()
             .and_then(|name| name.to_str())
-            .ok_or_else(|| AppError::InvalidRequest("無効なファイル名です".to_string()))?
-            .to_string();
-        let base_destination = output_dir.join(&file_name);
-        let base_key = base_destination.to_string_lossy().to_ascii_lowercase();
+            .ok_or_else(|| AppError::InvalidRequest("無効なファイル名です".to_string()))?;
+        let file_name = normalize_unicode(file_name, request.unicode_normalize.as_ref());
+        let target_dir = match &date_source {
+            Some(date_source) => match resolve_timestamp(&source, date_source, &ffprobe_cache) {
+                Some((timestamp, _)) => output_dir.join(date_path(&timestamp, &date_segments)),
+                None => {
+                    plan.push(PlannedFlatten {
+                        destination: output_dir.join(&file_name),
+                        source,
+                        status: PreviewStatus::Skipped,
+                        reason: Some("タイムスタンプを取得できません".to_string()),
+                        sidecar_of: None,
+                    });
+                    if let Some(primary) = plan.last().cloned() {
+                        for sidecar in sidecars {
+                            plan.push(plan_sidecar(
+                                &primary,
+                                sidecar,
+                                &request.conflict_policy,
+                                &mut used_destinations,
+                            ));
+                        }
+                    }
+                    continue;
+                }
+            },
+            None => output_dir.clone(),
+        };
+        let base_destination = target_dir.join(&file_name);
+        let base_key = destination_key(&base_destination);
         let is_collision = used_destinations.contains(&base_key) || base_destination.exists();
         if is_collision {
             collisions += 1;
@@ -251,6 +616,7 @@ fn build_plan(
                     } else {
                         None
                     },
+                    sidecar_of: None,
                 });
             }
             CollisionPolicy::Skip => {
@@ -260,6 +626,7 @@ fn build_plan(
                         destination: base_destination,
                         status: PreviewStatus::Skipped,
                         reason: Some("ファイル名の競合によりスキップされました".to_string()),
+                        sidecar_of: None,
                     });
                 } else {
                     used_destinations.insert(base_key);
@@ -268,6 +635,7 @@ fn build_plan(
                         destination: base_destination,
                         status: PreviewStatus::Ready,
                         reason: None,
+                        sidecar_of: None,
                     });
                 }
             }
@@ -283,9 +651,53 @@ fn build_plan(
                     } else {
                         None
                     },
+                    sidecar_of: None,
+                });
+            }
+            CollisionPolicy::PrefixParent => {
+                let prefixed = if is_collision {
+                    prefix_parent_destination(&base_destination, &source)
+                } else {
+                    None
+                };
+                let (destination, reason) = match prefixed {
+                    Some(prefixed) => {
+                        let destination = uniquify_destination(&prefixed, &mut used_destinations);
+                        let reason = if destination == prefixed {
+                            "競合のため親フォルダ名を付与しました"
+                        } else {
+                            "競合のため親フォルダ名と連番サフィックスを付与しました"
+                        };
+                        (destination, Some(reason.to_string()))
+                    }
+                    None => {
+                        let destination =
+                            uniquify_destination(&base_destination, &mut used_destinations);
+                        let reason = (destination != base_destination)
+                            .then(|| "競合のため連番サフィックスを付与しました".to_string());
+                        (destination, reason)
+                    }
+                };
+                plan.push(PlannedFlatten {
+                    source,
+                    destination,
+                    status: PreviewStatus::Ready,
+                    reason,
+                    sidecar_of: None,
                 });
             }
         }
+
+        if let Some(primary) = plan.last().cloned() {
+            for sidecar in sidecars {
+                plan.push(plan_sidecar(
+                    &primary,
+                    sidecar,
+                    &request.conflict_policy,
+                    &mut used_destinations,
+                ));
+            }
+        }
     }
 
     // When overwrite policy is used and multiple sources map to the same
@@ -296,7 +708,7 @@ fn build_plan(
         let mut last_ready: HashMap<String, usize> = HashMap::new();
         for (i, item) in plan.iter().enumerate() {
             if matches!(item.status, PreviewStatus::Ready) {
-                let key = item.destination.to_string_lossy().to_ascii_lowercase();
+                let key = destination_key(&item.destination);
                 last_ready.insert(key, i);
             }
         }
@@ -304,7 +716,7 @@ fn build_plan(
             if !matches!(item.status, PreviewStatus::Ready) {
                 continue;
             }
-            let key = item.destination.to_string_lossy().to_ascii_lowercase();
+            let key = destination_key(&item.destination);
             if let Some(&last) = last_ready.get(&key) {
                 if i < last {
                     item.status = PreviewStatus::Skipped;
@@ -315,10 +727,20 @@ fn build_plan(
         }
     }
 
-    Ok((output_dir, plan, collisions))
+    Ok(FlattenPlan {
+        output_dir,
+        plan,
+        collisions,
+        hidden_files_skipped,
+        warnings,
+    })
 }
 
-fn resolve_output_dir(input_dir: &Path, output_dir: Option<&str>) -> Result<PathBuf, AppError> {
+fn resolve_output_dir(
+    input_dir: &Path,
+    output_dir: Option<&str>,
+    suffix_tag: &str,
+) -> Result<PathBuf, AppError> {
     if let Some(raw) = output_dir {
         let trimmed = raw.trim();
         if trimmed.is_empty() {
@@ -336,14 +758,67 @@ fn resolve_output_dir(input_dir: &Path, output_dir: Option<&str>) -> Result<Path
         .and_then(|name| name.to_str())
         .ok_or_else(|| AppError::InvalidRequest("入力フォルダの名前が無効です".to_string()))?;
     let timestamp = Local::now().format("%Y%m%d%H%M%S");
-    let base_name = format!("{}_flattened_{}", dirname, timestamp);
+    let base_name = format!("{}{}{}", dirname, suffix_tag, timestamp);
     let candidate = parent.join(base_name);
     Ok(uniquify_directory(candidate))
 }
 
-fn validate_output_dir(input_dir: &Path, output_dir: &Path) -> Result<(), AppError> {
+/// `input_dirs` wins over `input_dir` when non-empty. Nested inputs are rejected
+/// so no file is collected twice.
+fn resolve_input_dirs(request: &FlattenPreviewRequest) -> Result<Vec<PathBuf>, AppError> {
+    let raw_dirs: Vec<&str> = match request.input_dirs.as_deref() {
+        Some(dirs) if dirs.iter().any(|dir| !dir.trim().is_empty()) => dirs
+            .iter()
+            .map(|dir| dir.trim())
+            .filter(|dir| !dir.is_empty())
+            .collect(),
+        _ => vec![request.input_dir.trim()],
+    };
+
+    let mut input_dirs = Vec::with_capacity(raw_dirs.len());
+    for raw in raw_dirs {
+        let input_dir = PathBuf::from(raw);
+        if !input_dir.exists() {
+            return Err(AppError::InvalidRequest(
+                "入力フォルダが存在しません".to_string(),
+            ));
+        }
+        if !input_dir.is_dir() {
+            return Err(AppError::InvalidRequest(
+                "入力パスはフォルダである必要があります".to_string(),
+            ));
+        }
+        let input_dir = safe_canonicalize(&input_dir).map_err(AppError::from)?;
+        if !input_dirs.contains(&input_dir) {
+            input_dirs.push(input_dir);
+        }
+    }
+
+    for (index, dir) in input_dirs.iter().enumerate() {
+        if input_dirs
+            .iter()
+            .enumerate()
+            .any(|(other_index, other)| other_index != index && dir.starts_with(other))
+        {
+            return Err(AppError::InvalidRequest(format!(
+                "入力フォルダが他の入力フォルダの内部にあります: {}",
+                dir.to_string_lossy()
+            )));
+        }
+    }
+    Ok(input_dirs)
+}
+
+fn validate_output_dir(
+    input_dir: &Path,
+    output_dir: &Path,
+    in_place: bool,
+) -> Result<(), AppError> {
     let output_canonical = safe_canonicalize(output_dir)
         .unwrap_or_else(|_| output_dir.to_path_buf());
+    if in_place && output_canonical == input_dir {
+        return Ok(());
+    }
     if output_canonical == input_dir {
         return Err(AppError::InvalidRequest(
             "出力先フォルダは入力フォルダと同じにできません".to_string(),
@@ -354,9 +829,45 @@ fn validate_output_dir(input_dir: &Path, output_dir: &Path) -> Result<(), AppErr
             "出力先フォルダは入力フォルダの内部にできません".to_string(),
         ));
     }
+    if input_dir.starts_with(&output_canonical) {
+        return Err(AppError::InvalidRequest(
+            "出力先フォルダは入力フォルダの親フォルダにできません".to_string(),
+        ));
+    }
     Ok(())
 }
 
+/// Warns when the output sits right next to the input among its sibling folders,
+/// where a later flatten over the parent could pick up this run's output.
+fn adjacent_output_warning(input_dir: &Path, output_dir: &Path) -> Option<String> {
+    let output_canonical = safe_canonicalize(output_dir)
+        .unwrap_or_else(|_| output_dir.to_path_buf());
+    let parent = input_dir.parent()?;
+    if output_canonical.parent()? != parent {
+        return None;
+    }
+    let input_name = input_dir.file_name()?.to_os_string();
+    let output_name = output_canonical.file_name()?.to_os_string();
+    let mut names: Vec<_> = fs::read_dir(parent)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name())
+        .collect();
+    if !names.contains(&output_name) {
+        names.push(output_name.clone());
+    }
+    names.sort();
+    let input_index = names.iter().position(|name| *name == input_name)?;
+    let output_index = names.iter().position(|name| *name == output_name)?;
+    (input_index.abs_diff(output_index) == 1).then(|| {
+        format!(
+            "出力先フォルダ「{}」が入力フォルダと同じ階層で隣り合っています。次回の平坦化で今回の出力を取り込まないよう注意してください",
+            output_name.to_string_lossy()
+        )
+    })
+}
+
 fn uniquify_directory(base: PathBuf) -> PathBuf {
     if !base.exists() {
         return base;
@@ -379,11 +890,138 @@ fn uniquify_directory(base: PathBuf) -> PathBuf {
     }
 }
 
+/// Map each primary file to the sidecars (`IMG.xmp` or `IMG.CR2.xmp`) sitting beside it.
+fn pair_sidecars(sources: &[PathBuf]) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let is_sidecar = |path: &Path| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                SIDECAR_EXTENSIONS
+                    .iter()
+                    .any(|s| ext.eq_ignore_ascii_case(s))
+            })
+    };
+    let mut primaries: HashMap<(PathBuf, String), PathBuf> = HashMap::new();
+    for source in sources.iter().filter(|path| !is_sidecar(path)) {
+        let parent = source.parent().map(Path::to_path_buf).unwrap_or_default();
+        for key in [source.file_name(), source.file_stem()]
+            .into_iter()
+            .flatten()
+        {
+            primaries
+                .entry((parent.clone(), key.to_string_lossy().to_ascii_lowercase()))
+                .or_insert_with(|| source.clone());
+        }
+    }
+
+    let mut paired: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    for sidecar in sources.iter().filter(|path| is_sidecar(path)) {
+        let parent = sidecar.parent().map(Path::to_path_buf).unwrap_or_default();
+        let Some(stem) = sidecar.file_stem() else {
+            continue;
+        };
+        let key = (parent, stem.to_string_lossy().to_ascii_lowercase());
+        if let Some(primary) = primaries.get(&key) {
+            paired
+                .entry(primary.clone())
+                .or_default()
+                .push(sidecar.clone());
+        }
+    }
+    paired
+}
+
+/// Sidecars follow their primary: same status, and a destination derived from the
+/// primary's final name so renames caused by collisions keep the pair together.
+fn plan_sidecar(
+    primary: &PlannedFlatten,
+    sidecar: PathBuf,
+    policy: &CollisionPolicy,
+    used_destinations: &mut HashSet<String>,
+) -> PlannedFlatten {
+    let destination = sidecar_destination(&primary.source, &primary.destination, &sidecar);
+    if !matches!(primary.status, PreviewStatus::Ready) {
+        return PlannedFlatten {
+            source: sidecar,
+            destination,
+            status: PreviewStatus::Skipped,
+            reason: Some("対応するファイルがスキップされたためスキップされました".to_string()),
+            sidecar_of: Some(primary.source.clone()),
+        };
+    }
+
+    let key = destination_key(&destination);
+    let is_collision = used_destinations.contains(&key) || destination.exists();
+    let (status, reason) = match (is_collision, policy) {
+        (false, _) => (PreviewStatus::Ready, None),
+        (true, CollisionPolicy::Overwrite) => (
+            PreviewStatus::Ready,
+            Some("競合ポリシーにより上書きされます".to_string()),
+        ),
+        (true, _) => (
+            PreviewStatus::Skipped,
+            Some("出力先の競合によりスキップされました".to_string()),
+        ),
+    };
+    if matches!(status, PreviewStatus::Ready) {
+        used_destinations.insert(key);
+    }
+    PlannedFlatten {
+        source: sidecar,
+        destination,
+        status,
+        reason,
+        sidecar_of: Some(primary.source.clone()),
+    }
+}
+
+fn sidecar_destination(
+    primary_source: &Path,
+    primary_destination: &Path,
+    sidecar: &Path,
+) -> PathBuf {
+    let sidecar_name = sidecar
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let sidecar_lower = sidecar_name.to_ascii_lowercase();
+    let source_name = primary_source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let destination_name = primary_destination
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let destination_stem = primary_destination
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let file_name = if sidecar_lower.starts_with(&format!("{}.", source_name)) {
+        format!("{}{}", destination_name, &sidecar_name[source_name.len()..])
+    } else {
+        let suffix = sidecar_name
+            .rfind('.')
+            .map(|index| &sidecar_name[index..])
+            .unwrap_or("");
+        format!("{}{}", destination_stem, suffix)
+    };
+    primary_destination.with_file_name(file_name)
+}
+
+/// `<parent>_<name>` next to `base`, where `<parent>` is the source's immediate folder name.
+fn prefix_parent_destination(base: &Path, source: &Path) -> Option<PathBuf> {
+    let parent_name = source.parent()?.file_name()?.to_str()?;
+    let file_name = base.file_name()?.to_str()?;
+    Some(base.with_file_name(format!("{}_{}", parent_name, file_name)))
+}
+
 fn uniquify_destination(base: &Path, used_destinations: &mut HashSet<String>) -> PathBuf {
     let mut candidate = base.to_path_buf();
     let mut suffix = 1usize;
     loop {
-        let key = candidate.to_string_lossy().to_ascii_lowercase();
+        let key = destination_key(&candidate);
         if !used_destinations.contains(&key) && !candidate.exists() {
             used_destinations.insert(key);
             return candidate;
@@ -1,17 +1,31 @@
+use crate::error::AppError;
+use crate::path_norm::to_windows_extended;
 use chrono::Local;
+use std::collections::HashSet;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 
-pub fn atomic_write_replace(destination: &Path, bytes: &[u8]) -> Result<(), String> {
+pub fn atomic_write_replace(destination: &Path, bytes: &[u8]) -> Result<(), AppError> {
+    let destination = &to_windows_extended(destination);
     let temp = temp_path_for(destination, "tmpwrite");
-    fs::write(&temp, bytes).map_err(|e| format!("一時ファイルの書き込みに失敗しました: {}", e))?;
+    fs::write(&temp, bytes).map_err(|e| {
+        let message = format!("一時ファイルの書き込みに失敗しました: {}", e);
+        if e.kind() == io::ErrorKind::PermissionDenied {
+            AppError::PermissionDenied(message)
+        } else {
+            AppError::Io(message)
+        }
+    })?;
     atomic_replace(&temp, destination).map_err(|e| {
         let _ = fs::remove_file(&temp);
-        e
+        AppError::Io(e)
     })
 }
 
 pub fn atomic_copy_replace(source: &Path, destination: &Path) -> Result<(), String> {
+    let source = &to_windows_extended(source);
+    let destination = &to_windows_extended(destination);
     let temp = temp_path_for(destination, "tmpcopy");
     fs::copy(source, &temp).map_err(|e| format!("一時ファイルへのコピーに失敗しました: {}", e))?;
     atomic_replace(&temp, destination).map_err(|e| {
@@ -20,31 +34,75 @@ pub fn atomic_copy_replace(source: &Path, destination: &Path) -> Result<(), Stri
     })
 }
 
-pub fn atomic_move_replace(source: &Path, destination: &Path) -> Result<Option<String>, String> {
+/// Prefix marking a successful move that needed the copy fallback.
+pub const COPY_FALLBACK_PREFIX: &str = "⚠️ ";
+
+/// How a successful `atomic_move_replace` got the file into place.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AtomicMoveNote {
+    /// Source and destination were already the same.
+    NoChange,
+    /// Plain rename.
+    Moved,
+    /// Existing destination replaced via copy, then the source was deleted.
+    CopiedAndDeleted,
+    /// Rename failed (typically across devices); carries the rename error.
+    CopyFallback(String),
+}
+
+impl AtomicMoveNote {
+    /// Detail reason for the UI; fallbacks carry `COPY_FALLBACK_PREFIX`.
+    pub fn into_reason(self) -> Option<String> {
+        match self {
+            AtomicMoveNote::NoChange => Some("変更なし".to_string()),
+            AtomicMoveNote::Moved => None,
+            AtomicMoveNote::CopiedAndDeleted => Some("コピー+置換で移動しました".to_string()),
+            AtomicMoveNote::CopyFallback(error) => Some(format!(
+                "{}コピー+置換のフォールバックで移動しました（{}）",
+                COPY_FALLBACK_PREFIX, error
+            )),
+        }
+    }
+
+    pub fn is_copy_fallback(&self) -> bool {
+        matches!(self, AtomicMoveNote::CopyFallback(_))
+    }
+}
+
+pub fn atomic_move_replace(source: &Path, destination: &Path) -> Result<AtomicMoveNote, String> {
     if source == destination {
-        return Ok(Some("変更なし".to_string()));
+        return Ok(AtomicMoveNote::NoChange);
     }
 
     if !destination.exists() {
-        match fs::rename(source, destination) {
-            Ok(_) => return Ok(None),
+        match fs::rename(to_windows_extended(source), to_windows_extended(destination)) {
+            Ok(_) => return Ok(AtomicMoveNote::Moved),
             Err(rename_error) => {
                 atomic_copy_replace(source, destination)?;
-                fs::remove_file(source).map_err(|remove_error| {
+                fs::remove_file(to_windows_extended(source)).map_err(|remove_error| {
                     format!(
                         "リネーム失敗: {}; コピー後の元ファイル削除にも失敗しました: {}",
                         rename_error, remove_error
                     )
                 })?;
-                return Ok(Some("コピー+置換のフォールバックで移動しました".to_string()));
+                return Ok(AtomicMoveNote::CopyFallback(rename_error.to_string()));
             }
         }
     }
 
     atomic_copy_replace(source, destination)?;
-    fs::remove_file(source)
+    fs::remove_file(to_windows_extended(source))
         .map_err(|e| format!("置換は成功しましたが元ファイルの削除に失敗しました: {}", e))?;
-    Ok(Some("コピー+置換で移動しました".to_string()))
+    Ok(AtomicMoveNote::CopiedAndDeleted)
+}
+
+/// Dry-run stand-in for a file operation: verifies the source is still there without touching it.
+pub fn dry_run_check(source: &Path) -> Result<Option<String>, String> {
+    if to_windows_extended(source).is_file() {
+        Ok(Some("ドライラン: ファイルは変更されていません".to_string()))
+    } else {
+        Err("元ファイルが見つかりません".to_string())
+    }
 }
 
 fn temp_path_for(destination: &Path, tag: &str) -> PathBuf {
@@ -104,3 +162,31 @@ fn replace_file_windows(destination: &Path, replacement: &Path) -> Result<(), St
     }
     Ok(())
 }
+
+/// Removes each of `dirs` once it is empty, then its parents, deepest first. Stops at
+/// (and never removes) the `stop_at` folders and anything outside them.
+pub fn remove_emptied_dirs(dirs: impl IntoIterator<Item = PathBuf>, stop_at: &[PathBuf]) {
+    let mut candidates: Vec<PathBuf> = dirs
+        .into_iter()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    // Deepest first so nested empty folders collapse bottom-up.
+    candidates.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+
+    for dir in candidates {
+        let mut current = Some(dir.as_path());
+        while let Some(path) = current {
+            if stop_at.iter().any(|root| root == path)
+                || !stop_at.iter().any(|root| path.starts_with(root))
+            {
+                break;
+            }
+            // remove_dir only succeeds on empty folders.
+            if fs::remove_dir(to_windows_extended(path)).is_err() {
+                break;
+            }
+            current = path.parent();
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::error::AppError;
+use crate::model::HistoryEntry;
+use chrono::Local;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const HISTORY_FILE_NAME: &str = "operation_history.jsonl";
+
+// Serialises appends/truncation so concurrent operations never interleave lines.
+static HISTORY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+pub fn new_entry<T: Serialize>(
+    operation: &str,
+    request: &T,
+    succeeded: usize,
+    failed: usize,
+    skipped: usize,
+) -> HistoryEntry {
+    let now = Local::now();
+    HistoryEntry {
+        id: now.format("%Y%m%d%H%M%S%6f").to_string(),
+        operation: operation.to_string(),
+        timestamp: now.to_rfc3339(),
+        processed: succeeded + failed + skipped,
+        succeeded,
+        failed,
+        skipped,
+        canceled: false,
+        cancel_reason: None,
+        elapsed_ms: None,
+        parameters: serde_json::to_value(request).unwrap_or(serde_json::Value::Null),
+    }
+}
+
+pub fn record(app: &AppHandle, entry: HistoryEntry) -> Result<(), AppError> {
+    let path = history_file_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::Io(e.to_string()))?;
+    }
+    let mut line = serde_json::to_string(&entry).map_err(|e| AppError::Io(e.to_string()))?;
+    line.push('\n');
+
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|e| AppError::Io(e.to_string()))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| AppError::Io(format!("操作履歴の書き込みに失敗しました: {}", e)))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| AppError::Io(format!("操作履歴の書き込みに失敗しました: {}", e)))
+}
+
+/// Last `limit` entries in chronological order. Unreadable lines are ignored.
+pub fn load(app: &AppHandle, limit: usize) -> Result<Vec<HistoryEntry>, AppError> {
+    let path = history_file_path(app)?;
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|e| AppError::Io(e.to_string()))?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AppError::Io(format!("操作履歴の読み込みに失敗しました: {}", e)))?;
+    let entries: Vec<HistoryEntry> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let start = entries.len().saturating_sub(limit);
+    Ok(entries.into_iter().skip(start).collect())
+}
+
+pub fn clear(app: &AppHandle) -> Result<(), AppError> {
+    let path = history_file_path(app)?;
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|e| AppError::Io(e.to_string()))?;
+    if !path.exists() {
+        return Ok(());
+    }
+    fs::write(&path, "").map_err(|e| AppError::Io(format!("操作履歴の削除に失敗しました: {}", e)))
+}
+
+fn history_file_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let mut dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::Io(e.to_string()))?;
+    dir.push(HISTORY_FILE_NAME);
+    Ok(dir)
+}
@@ -1,31 +1,49 @@
 mod compress;
 mod delete;
+mod duplicate_detect;
 mod error;
 mod exif_offset;
+mod exif_read;
 mod file_collect;
+mod file_info;
 mod flatten;
 mod fs_atomic;
+mod history;
 mod metadata_strip;
 mod model;
+mod organize;
 mod path_norm;
+mod preview_sort;
+mod progress_eta;
 mod rename;
+mod reorient;
 mod resize;
+mod result_export;
 mod settings;
+mod watch_folder;
 mod worker_bridge;
 
 use crate::error::AppError;
 use crate::model::{
     AppSettings, CompressCollectInfoResponse, CompressEstimateResponse, CompressExecuteResponse,
-    CompressPreviewRequest, CompressPreviewResponse, DeleteExecuteResponse, DeletePreviewRequest,
-    DeletePreviewResponse, ExifOffsetExecuteResponse, ExifOffsetPreviewRequest,
-    ExifOffsetPreviewResponse, FlattenExecuteResponse, FlattenPreviewRequest,
-    FlattenPreviewResponse, ImportConflictPreview, MetadataStripExecuteResponse,
-    MetadataStripPreviewRequest, MetadataStripPreviewResponse, RenameExecuteResponse,
-    RenamePreviewRequest, RenamePreviewResponse, RenameTemplateTag, ResizeCollectInfoResponse,
-    ResizeExecuteResponse, ResizePreviewRequest, ResizePreviewResponse,
+    CompressPreviewRequest, CompressPreviewResponse, DeleteExecuteResponse, DeleteMode,
+    DeletePreviewRequest, DeletePreviewResponse, DuplicateDetectRequest, DuplicateDetectResponse,
+    ErrorResponse, ExifOffsetExecuteResponse, ExifOffsetPreviewRequest, ExifOffsetPreviewResponse,
+    ExifReadResult, FileInfo, FileInfoResult, FlattenExecuteResponse, FlattenPreviewRequest,
+    FlattenPreviewResponse, HistoryEntry, ImportConflictPreview, ImportMergePreview,
+    MetadataStripExecuteResponse, MetadataStripPresetInfo, MetadataStripPreviewRequest,
+    MetadataStripPreviewResponse, OperationProgressEvent, OperationResultRow, OperationSummary,
+    OrganizeByDateExecuteResponse, OrganizeByDatePreviewResponse, OrganizeByDateRequest,
+    PathValidation, RenameExecuteResponse, RenamePreviewRequest, RenamePreviewResponse,
+    RenameTemplateTag, ReorientExecuteResponse, ReorientPreviewRequest, ReorientPreviewResponse,
+    ResizeCollectInfoResponse, ResizeExecuteResponse, ResizePreviewRequest, ResizePreviewResponse,
+    TemplateWarning, WatchFolderConfig,
 };
 use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
 use tauri::AppHandle;
 use tauri::Emitter;
 
@@ -49,58 +67,156 @@ fn execute_rename(
     request: RenamePreviewRequest,
 ) -> Result<RenameExecuteResponse, String> {
     CANCEL_REQUESTED.store(false, Ordering::SeqCst);
-    rename::execute(
+    let run = HistoryRun::start();
+    let result = rename::execute(
         &request,
         || CANCEL_REQUESTED.load(Ordering::SeqCst),
-        |event| {
-            let _ = app.emit("operation-progress", event);
-        },
-    )
-    .map_err(error_to_string)
+        |event| run.emit(&app, event),
+    );
+    finish_with_history(&app, "rename", &request, &run, result, |response| {
+        &mut response.summary
+    })
 }
 
 #[tauri::command]
-fn preview_delete(request: DeletePreviewRequest) -> Result<DeletePreviewResponse, String> {
+fn preview_delete(
+    app: AppHandle,
+    mut request: DeletePreviewRequest,
+) -> Result<DeletePreviewResponse, String> {
+    if matches!(request.mode, DeleteMode::Retreat) {
+        fill_default_output_dir(&app, "delete_retreat", &mut request.retreat_dir);
+    }
     delete::preview(&request).map_err(error_to_string)
 }
 
 #[tauri::command]
 fn execute_delete(
     app: AppHandle,
-    request: DeletePreviewRequest,
+    mut request: DeletePreviewRequest,
+) -> Result<DeleteExecuteResponse, String> {
+    if matches!(request.mode, DeleteMode::Retreat) {
+        fill_default_output_dir(&app, "delete_retreat", &mut request.retreat_dir);
+    }
+    CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+    let run = HistoryRun::start();
+    let result = delete::execute(
+        &request,
+        || CANCEL_REQUESTED.load(Ordering::SeqCst),
+        |event| run.emit(&app, event),
+    );
+    finish_with_history(&app, "delete", &request, &run, result, |response| {
+        &mut response.summary
+    })
+}
+
+#[tauri::command]
+fn preview_duplicate_detect(
+    request: DuplicateDetectRequest,
+) -> Result<DuplicateDetectResponse, String> {
+    duplicate_detect::preview(&request).map_err(error_to_string)
+}
+
+#[tauri::command]
+fn execute_duplicate_detect(
+    app: AppHandle,
+    request: DuplicateDetectRequest,
 ) -> Result<DeleteExecuteResponse, String> {
     CANCEL_REQUESTED.store(false, Ordering::SeqCst);
-    delete::execute(
+    let run = HistoryRun::start();
+    let result = duplicate_detect::execute(
         &request,
         || CANCEL_REQUESTED.load(Ordering::SeqCst),
-        |event| {
-            let _ = app.emit("operation-progress", event);
-        },
+        |event| run.emit(&app, event),
+    );
+    finish_with_history(
+        &app,
+        "duplicate_detect",
+        &request,
+        &run,
+        result,
+        |response| &mut response.summary,
     )
-    .map_err(error_to_string)
 }
 
 #[tauri::command]
-fn preview_flatten(request: FlattenPreviewRequest) -> Result<FlattenPreviewResponse, String> {
+fn preview_flatten(
+    app: AppHandle,
+    mut request: FlattenPreviewRequest,
+) -> Result<FlattenPreviewResponse, String> {
+    fill_default_output_dir(&app, "flatten", &mut request.output_dir);
+    fill_default_output_suffix(&app, "flatten", &mut request.output_suffix);
     flatten::preview(&request).map_err(error_to_string)
 }
 
 #[tauri::command]
 fn execute_flatten(
     app: AppHandle,
-    request: FlattenPreviewRequest,
+    mut request: FlattenPreviewRequest,
 ) -> Result<FlattenExecuteResponse, String> {
+    fill_default_output_dir(&app, "flatten", &mut request.output_dir);
+    fill_default_output_suffix(&app, "flatten", &mut request.output_suffix);
     CANCEL_REQUESTED.store(false, Ordering::SeqCst);
-    flatten::execute(
+    let run = HistoryRun::start();
+    let result = flatten::execute(
         &request,
         || CANCEL_REQUESTED.load(Ordering::SeqCst),
-        |event| {
-            let _ = app.emit("operation-progress", event);
-        },
+        |event| run.emit(&app, event),
+    );
+    finish_with_history(&app, "flatten", &request, &run, result, |response| {
+        &mut response.summary
+    })
+}
+
+#[tauri::command]
+fn execute_flatten_undo(
+    app: AppHandle,
+    manifest_path: String,
+) -> Result<FlattenExecuteResponse, String> {
+    let run = HistoryRun::start();
+    let result = flatten::execute_undo(&manifest_path);
+    finish_with_history(
+        &app,
+        "flatten_undo",
+        &serde_json::json!({ "manifestPath": manifest_path }),
+        &run,
+        result,
+        |response| &mut response.summary,
     )
+}
+
+#[tauri::command]
+fn preview_organize_by_date(
+    app: AppHandle,
+    request: OrganizeByDateRequest,
+) -> Result<OrganizeByDatePreviewResponse, String> {
+    organize::preview(&request, |event| {
+        let _ = app.emit("operation-progress", event);
+    })
     .map_err(error_to_string)
 }
 
+#[tauri::command]
+fn execute_organize_by_date(
+    app: AppHandle,
+    request: OrganizeByDateRequest,
+) -> Result<OrganizeByDateExecuteResponse, String> {
+    CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+    let run = HistoryRun::start();
+    let result = organize::execute(
+        &request,
+        || CANCEL_REQUESTED.load(Ordering::SeqCst),
+        |event| run.emit(&app, event),
+    );
+    finish_with_history(
+        &app,
+        "organize_by_date",
+        &request,
+        &run,
+        result,
+        |response| &mut response.summary,
+    )
+}
+
 #[tauri::command]
 fn compress_collect_info(
     input_paths: Vec<String>,
@@ -133,40 +249,89 @@ async fn compress_estimate(
         .map_err(error_to_string)
     })
     .await
-    .map_err(|e| e.to_string())?
+    .map_err(join_error_to_string)?
 }
 
 #[tauri::command]
 async fn preview_compress(
     app: AppHandle,
-    request: CompressPreviewRequest,
+    mut request: CompressPreviewRequest,
 ) -> Result<CompressPreviewResponse, String> {
+    fill_default_output_dir(&app, "compress", &mut request.output_dir);
+    fill_default_output_suffix(&app, "compress", &mut request.output_suffix);
     tauri::async_runtime::spawn_blocking(move || {
         compress::preview(&request, &app).map_err(error_to_string)
     })
     .await
-    .map_err(|e| e.to_string())?
+    .map_err(join_error_to_string)?
 }
 
 #[tauri::command]
 async fn execute_compress(
     app: AppHandle,
-    request: CompressPreviewRequest,
+    mut request: CompressPreviewRequest,
 ) -> Result<CompressExecuteResponse, String> {
+    fill_default_output_dir(&app, "compress", &mut request.output_dir);
+    fill_default_output_suffix(&app, "compress", &mut request.output_suffix);
     CANCEL_REQUESTED.store(false, Ordering::SeqCst);
     tauri::async_runtime::spawn_blocking(move || {
-        compress::execute(
+        let run = HistoryRun::start();
+        let result = compress::execute(
             &app,
             &request,
             || CANCEL_REQUESTED.load(Ordering::SeqCst),
-            |event| {
-                let _ = app.emit("operation-progress", event);
-            },
-        )
-        .map_err(error_to_string)
+            |event| run.emit(&app, event),
+        );
+        finish_with_history(&app, "compress", &request, &run, result, |response| {
+            &mut response.summary
+        })
+    })
+    .await
+    .map_err(join_error_to_string)?
+}
+
+#[tauri::command]
+fn export_compress_batch_manifest(
+    request: CompressPreviewRequest,
+    output_path: String,
+) -> Result<(), String> {
+    result_export::export_compress_batch_manifest(&request, &output_path).map_err(error_to_string)
+}
+
+#[tauri::command]
+async fn replay_compress_from_manifest(
+    app: AppHandle,
+    manifest_path: String,
+) -> Result<CompressExecuteResponse, String> {
+    let mut request = result_export::import_compress_batch_manifest(&manifest_path)
+        .map_err(error_to_string)?
+        .request;
+    let missing = compress::take_missing_inputs(&mut request);
+    fill_default_output_dir(&app, "compress", &mut request.output_dir);
+    fill_default_output_suffix(&app, "compress", &mut request.output_suffix);
+    CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+    tauri::async_runtime::spawn_blocking(move || {
+        let run = HistoryRun::start();
+        let result = if request.input_paths.is_empty() {
+            Ok(compress::missing_inputs_response(&request, &missing))
+        } else {
+            compress::execute(
+                &app,
+                &request,
+                || CANCEL_REQUESTED.load(Ordering::SeqCst),
+                |event| run.emit(&app, event),
+            )
+            .map(|mut response| {
+                compress::append_missing_inputs(&mut response, &missing);
+                response
+            })
+        };
+        finish_with_history(&app, "compress", &request, &run, result, |response| {
+            &mut response.summary
+        })
     })
     .await
-    .map_err(|e| e.to_string())?
+    .map_err(join_error_to_string)?
 }
 
 #[tauri::command]
@@ -182,21 +347,37 @@ fn execute_exif_offset(
     request: ExifOffsetPreviewRequest,
 ) -> Result<ExifOffsetExecuteResponse, String> {
     CANCEL_REQUESTED.store(false, Ordering::SeqCst);
-    exif_offset::execute(
+    let run = HistoryRun::start();
+    let result = exif_offset::execute(
         &request,
         || CANCEL_REQUESTED.load(Ordering::SeqCst),
-        |event| {
-            let _ = app.emit("operation-progress", event);
-        },
-    )
-    .map_err(error_to_string)
+        |event| run.emit(&app, event),
+    );
+    finish_with_history(&app, "exif_offset", &request, &run, result, |response| {
+        &mut response.summary
+    })
+}
+
+#[tauri::command]
+async fn batch_get_exif(paths: Vec<String>, tags: Vec<u16>) -> Result<Vec<ExifReadResult>, String> {
+    tauri::async_runtime::spawn_blocking(move || exif_read::batch_get_exif(&paths, &tags))
+        .await
+        .map_err(join_error_to_string)
 }
 
 #[tauri::command]
 fn preview_metadata_strip(
+    app: AppHandle,
     request: MetadataStripPreviewRequest,
 ) -> Result<MetadataStripPreviewResponse, String> {
-    metadata_strip::preview(&request).map_err(error_to_string)
+    let scan_threads = settings::load_settings(&app)
+        .ok()
+        .and_then(|settings| settings.metadata_scan_threads)
+        .unwrap_or(metadata_strip::METADATA_SCAN_THREADS);
+    metadata_strip::preview(&request, scan_threads, |event| {
+        let _ = app.emit("operation-progress", event);
+    })
+    .map_err(error_to_string)
 }
 
 #[tauri::command]
@@ -205,14 +386,51 @@ fn execute_metadata_strip(
     request: MetadataStripPreviewRequest,
 ) -> Result<MetadataStripExecuteResponse, String> {
     CANCEL_REQUESTED.store(false, Ordering::SeqCst);
-    metadata_strip::execute(
+    let run = HistoryRun::start();
+    let result = metadata_strip::execute(
         &request,
         || CANCEL_REQUESTED.load(Ordering::SeqCst),
-        |event| {
-            let _ = app.emit("operation-progress", event);
-        },
-    )
-    .map_err(error_to_string)
+        |event| run.emit(&app, event),
+    );
+    finish_with_history(&app, "metadata_strip", &request, &run, result, |response| {
+        &mut response.summary
+    })
+}
+
+#[tauri::command]
+fn preview_reorient(request: ReorientPreviewRequest) -> Result<ReorientPreviewResponse, String> {
+    reorient::preview(&request).map_err(error_to_string)
+}
+
+#[tauri::command]
+fn execute_reorient(
+    app: AppHandle,
+    request: ReorientPreviewRequest,
+) -> Result<ReorientExecuteResponse, String> {
+    CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+    let run = HistoryRun::start();
+    let result = reorient::execute(
+        &request,
+        || CANCEL_REQUESTED.load(Ordering::SeqCst),
+        |event| run.emit(&app, event),
+    );
+    finish_with_history(&app, "reorient", &request, &run, result, |response| {
+        &mut response.summary
+    })
+}
+
+#[tauri::command]
+fn start_watch_folder(app: AppHandle, mut config: WatchFolderConfig) -> Result<String, String> {
+    if let Some(request) = config.compress_request.as_mut() {
+        fill_default_output_dir(&app, "compress", &mut request.output_dir);
+        fill_default_output_suffix(&app, "compress", &mut request.output_suffix);
+    }
+    watch_folder::start(&app, config).map_err(error_to_string)
+}
+
+#[tauri::command]
+fn stop_watch_folder(id: String) -> Result<(), String> {
+    watch_folder::stop(&id).map_err(error_to_string)
 }
 
 #[tauri::command]
@@ -231,7 +449,7 @@ async fn preview_resize(
         resize::preview(&request).map_err(error_to_string)
     })
     .await
-    .map_err(|e| e.to_string())?
+    .map_err(join_error_to_string)?
 }
 
 #[tauri::command]
@@ -241,18 +459,19 @@ async fn execute_resize(
 ) -> Result<ResizeExecuteResponse, String> {
     CANCEL_REQUESTED.store(false, Ordering::SeqCst);
     tauri::async_runtime::spawn_blocking(move || {
-        resize::execute(
+        let run = HistoryRun::start();
+        let result = resize::execute(
             &app,
             &request,
             || CANCEL_REQUESTED.load(Ordering::SeqCst),
-            |event| {
-                let _ = app.emit("operation-progress", event);
-            },
-        )
-        .map_err(error_to_string)
+            |event| run.emit(&app, event),
+        );
+        finish_with_history(&app, "resize", &request, &run, result, |response| {
+            &mut response.summary
+        })
     })
     .await
-    .map_err(|e| e.to_string())?
+    .map_err(join_error_to_string)?
 }
 
 #[tauri::command]
@@ -270,6 +489,36 @@ fn list_rename_template_tags() -> Vec<RenameTemplateTag> {
     rename::template_tags()
 }
 
+#[tauri::command]
+fn list_template_warnings(template: String) -> Result<Vec<TemplateWarning>, String> {
+    rename::template_warnings(&template)
+        .map_err(|message| error_to_string(AppError::InvalidRequest(message)))
+}
+
+#[tauri::command]
+fn sort_preview_items(
+    items: Vec<serde_json::Value>,
+    field: String,
+    ascending: bool,
+) -> Result<Vec<serde_json::Value>, String> {
+    preview_sort::sort_preview_items(items, &field, ascending).map_err(error_to_string)
+}
+
+#[tauri::command]
+fn list_metadata_strip_presets() -> Vec<MetadataStripPresetInfo> {
+    metadata_strip::preset_infos()
+}
+
+#[tauri::command]
+fn load_operation_history(app: AppHandle, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+    history::load(&app, limit).map_err(error_to_string)
+}
+
+#[tauri::command]
+fn clear_operation_history(app: AppHandle) -> Result<(), String> {
+    history::clear(&app).map_err(error_to_string)
+}
+
 #[tauri::command]
 fn load_settings(app: AppHandle) -> Result<AppSettings, String> {
     settings::load_settings(&app).map_err(error_to_string)
@@ -280,6 +529,31 @@ fn save_settings(app: AppHandle, settings: AppSettings) -> Result<(), String> {
     settings::save_settings(&app, &settings).map_err(error_to_string)
 }
 
+#[tauri::command]
+fn get_default_output_dir(app: AppHandle, operation: String) -> Result<Option<String>, String> {
+    settings::default_output_dir(&app, &operation).map_err(error_to_string)
+}
+
+#[tauri::command]
+fn get_invalid_output_directories(app: AppHandle) -> Result<Vec<String>, String> {
+    settings::invalid_output_directories(&app).map_err(error_to_string)
+}
+
+#[tauri::command]
+fn reset_settings(app: AppHandle) -> Result<AppSettings, String> {
+    settings::reset_settings(&app).map_err(error_to_string)
+}
+
+#[tauri::command]
+fn reset_settings_section(app: AppHandle, section: String) -> Result<AppSettings, String> {
+    settings::reset_settings_section(&app, &section).map_err(error_to_string)
+}
+
+#[tauri::command]
+fn list_rename_template_groups(app: AppHandle) -> Result<Vec<String>, String> {
+    settings::list_rename_template_groups(&app).map_err(error_to_string)
+}
+
 #[tauri::command]
 fn get_settings_path(app: AppHandle) -> Result<String, String> {
     settings::settings_file_path(&app)
@@ -292,6 +566,37 @@ fn export_settings(app: AppHandle, output_path: String) -> Result<(), String> {
     settings::export_settings_to_path(&app, &output_path).map_err(error_to_string)
 }
 
+#[tauri::command]
+fn export_operation_result_csv(
+    details: Vec<OperationResultRow>,
+    output_path: String,
+) -> Result<(), String> {
+    result_export::export_csv(&details, &output_path).map_err(error_to_string)
+}
+
+#[tauri::command]
+fn export_operation_result_json(
+    details: Vec<OperationResultRow>,
+    output_path: String,
+) -> Result<(), String> {
+    result_export::export_json(&details, &output_path).map_err(error_to_string)
+}
+
+#[tauri::command]
+fn export_preview_manifest(
+    manifest_type: String,
+    items: Vec<serde_json::Value>,
+    output_path: String,
+) -> Result<(), String> {
+    result_export::export_preview_manifest(&manifest_type, items, &output_path)
+        .map_err(error_to_string)
+}
+
+#[tauri::command]
+fn import_preview_manifest(input_path: String) -> Result<serde_json::Value, String> {
+    result_export::import_preview_manifest(&input_path).map_err(error_to_string)
+}
+
 #[tauri::command]
 fn import_settings(
     app: AppHandle,
@@ -311,18 +616,177 @@ fn preview_import_conflicts(
     settings::preview_import_conflicts(&app, &input_path).map_err(error_to_string)
 }
 
+#[tauri::command]
+fn preview_import_merge(app: AppHandle, input_path: String) -> Result<ImportMergePreview, String> {
+    settings::preview_import_merge(&app, &input_path).map_err(error_to_string)
+}
+
 #[tauri::command]
 fn open_settings_folder(app: AppHandle) -> Result<(), String> {
     settings::open_settings_folder(&app).map_err(error_to_string)
 }
 
+#[tauri::command]
+fn get_file_info(path: String) -> Result<FileInfo, String> {
+    file_info::get_file_info(&path).map_err(error_to_string)
+}
+
+#[tauri::command]
+async fn batch_get_file_info(paths: Vec<String>) -> Result<Vec<FileInfoResult>, String> {
+    tauri::async_runtime::spawn_blocking(move || file_info::batch_get_file_info(&paths))
+        .await
+        .map_err(join_error_to_string)
+}
+
+#[tauri::command]
+async fn validate_paths(paths: Vec<String>) -> Result<Vec<PathValidation>, String> {
+    tauri::async_runtime::spawn_blocking(move || file_info::validate_paths(&paths))
+        .await
+        .map_err(join_error_to_string)
+}
+
+#[tauri::command]
+fn list_supported_extensions() -> HashMap<String, Option<Vec<String>>> {
+    file_collect::supported_extensions()
+}
+
+#[tauri::command]
+fn get_common_parent(paths: Vec<String>) -> Result<Option<String>, String> {
+    let canonical = paths
+        .iter()
+        .map(|path| {
+            path_norm::safe_canonicalize(std::path::Path::new(path.trim()))
+                .map_err(|e| AppError::Io(format!("パスを解決できません `{}`: {}", path, e)))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(error_to_string)?;
+    Ok(file_collect::find_common_parent(&canonical).map(|path| path.to_string_lossy().to_string()))
+}
+
 #[tauri::command]
 fn is_directory_path(path: String) -> bool {
     std::path::Path::new(path.trim()).is_dir()
 }
 
+/// Start time and latest progress of an execute command, kept for its history entry.
+struct HistoryRun {
+    started: Instant,
+    last_progress: Mutex<Option<OperationProgressEvent>>,
+}
+
+impl HistoryRun {
+    fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            last_progress: Mutex::new(None),
+        }
+    }
+
+    fn emit(&self, app: &AppHandle, event: OperationProgressEvent) {
+        if let Ok(mut last) = self.last_progress.lock() {
+            *last = Some(event.clone());
+        }
+        let _ = app.emit("operation-progress", event);
+    }
+}
+
+/// Records history for completed and cancelled runs, then converts the result for the
+/// frontend. Cancelled runs use the counts from the last progress event.
+/// Also stamps the elapsed time onto the response summary.
+/// History is best-effort: a failed write never fails the operation itself.
+fn finish_with_history<T: serde::Serialize, R>(
+    app: &AppHandle,
+    operation: &str,
+    request: &T,
+    run: &HistoryRun,
+    result: Result<R, AppError>,
+    summary: impl Fn(&mut R) -> &mut OperationSummary,
+) -> Result<R, String> {
+    record_history(app, operation, request, run, result, summary).map_err(error_to_string)
+}
+
+/// `finish_with_history` without the frontend conversion, for runs not started by a command.
+fn record_history<T: serde::Serialize, R>(
+    app: &AppHandle,
+    operation: &str,
+    request: &T,
+    run: &HistoryRun,
+    mut result: Result<R, AppError>,
+    summary: impl Fn(&mut R) -> &mut OperationSummary,
+) -> Result<R, AppError> {
+    let elapsed_ms = run.started.elapsed().as_millis() as u64;
+    let last = run
+        .last_progress
+        .lock()
+        .ok()
+        .and_then(|last| last.clone());
+    let canceled = matches!(result, Err(AppError::Cancelled))
+        || last.as_ref().is_some_and(|event| event.canceled);
+    let (processed, succeeded, failed, skipped) = match (&mut result, &last) {
+        (Ok(_) | Err(AppError::Cancelled), Some(event)) if canceled => {
+            (event.processed, event.succeeded, event.failed, event.skipped)
+        }
+        (Ok(response), _) => {
+            let summary = summary(response);
+            (summary.processed, summary.succeeded, summary.failed, summary.skipped)
+        }
+        (Err(AppError::Cancelled), None) => (0, 0, 0, 0),
+        (Err(_), _) => return result,
+    };
+    if let Ok(response) = &mut result {
+        summary(response).elapsed_ms = Some(elapsed_ms);
+    }
+
+    let mut entry = history::new_entry(operation, request, succeeded, failed, skipped);
+    entry.processed = processed;
+    entry.canceled = canceled;
+    entry.cancel_reason = canceled.then(|| "ユーザーによりキャンセルされました".to_string());
+    entry.elapsed_ms = Some(elapsed_ms);
+    let _ = history::record(app, entry);
+    result
+}
+
+/// Fills an unset output folder from the per-operation default in settings.
+/// Only used where an unset folder means auto-generate or error, never in-place.
+fn fill_default_output_dir(app: &AppHandle, operation: &str, output_dir: &mut Option<String>) {
+    if output_dir
+        .as_deref()
+        .is_some_and(|dir| !dir.trim().is_empty())
+    {
+        return;
+    }
+    if let Ok(Some(default)) = settings::default_output_dir(app, operation) {
+        *output_dir = Some(default);
+    }
+}
+
+fn fill_default_output_suffix(
+    app: &AppHandle,
+    operation: &str,
+    output_suffix: &mut Option<String>,
+) {
+    if output_suffix
+        .as_deref()
+        .is_some_and(|suffix| !suffix.trim().is_empty())
+    {
+        return;
+    }
+    if let Ok(Some(default)) = settings::default_output_suffix(app, operation) {
+        *output_suffix = Some(default);
+    }
+}
+
 fn error_to_string(error: AppError) -> String {
-    error.to_string()
+    let response = ErrorResponse {
+        code: error.code().to_string(),
+        message: error.to_string(),
+    };
+    serde_json::to_string(&response).unwrap_or(response.message)
+}
+
+/// A panicked or aborted blocking task, reported in the same JSON shape as other errors.
+fn join_error_to_string(error: tauri::Error) -> String {
+    error_to_string(AppError::Io(error.to_string()))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -335,33 +799,66 @@ pub fn run() {
             execute_rename,
             preview_delete,
             execute_delete,
+            preview_duplicate_detect,
+            execute_duplicate_detect,
             preview_flatten,
             execute_flatten,
+            execute_flatten_undo,
+            preview_organize_by_date,
+            execute_organize_by_date,
             compress_collect_info,
             compress_estimate,
             preview_compress,
             execute_compress,
+            export_compress_batch_manifest,
+            replay_compress_from_manifest,
             preview_exif_offset,
             execute_exif_offset,
+            batch_get_exif,
             preview_metadata_strip,
             execute_metadata_strip,
+            preview_reorient,
+            execute_reorient,
+            start_watch_folder,
+            stop_watch_folder,
             resize_collect_info,
             preview_resize,
             execute_resize,
             cancel_operation,
             is_ffprobe_available,
             list_rename_template_tags,
+            list_template_warnings,
+            list_metadata_strip_presets,
+            sort_preview_items,
+            load_operation_history,
+            clear_operation_history,
             load_settings,
             save_settings,
+            reset_settings,
+            reset_settings_section,
+            get_default_output_dir,
+            get_invalid_output_directories,
+            list_rename_template_groups,
             get_settings_path,
             export_settings,
+            export_operation_result_csv,
+            export_operation_result_json,
+            export_preview_manifest,
+            import_preview_manifest,
             import_settings,
             preview_import_conflicts,
+            preview_import_merge,
             open_settings_folder,
+            get_file_info,
+            batch_get_file_info,
+            validate_paths,
+            get_common_parent,
+            list_supported_extensions,
             is_directory_path
         ])
         .on_window_event(|_window, event| {
             if let tauri::WindowEvent::Destroyed = event {
+                watch_folder::stop_all();
                 worker_bridge::shutdown();
             }
         })
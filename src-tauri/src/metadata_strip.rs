@@ -1,13 +1,21 @@
 use crate::error::AppError;
-use crate::file_collect::{collect_targets_with_extensions, JPEG_ALLOWED_EXTENSIONS};
+use crate::file_collect::{
+    collect_targets_with_extensions, JPEG_ALLOWED_EXTENSIONS, XMP_ALLOWED_EXTENSIONS,
+};
 use crate::fs_atomic::atomic_write_replace;
 use crate::model::{
     ExecuteStatus, MetadataStripCategories, MetadataStripExecuteDetail,
-    MetadataStripExecuteResponse, MetadataStripPreviewItem, MetadataStripPreviewRequest,
-    MetadataStripPreviewResponse, OperationProgressEvent, PreviewStatus,
+    MetadataStripExecuteResponse, MetadataStripPreset, MetadataStripPresetInfo,
+    MetadataStripPreviewItem, MetadataStripPreviewRequest, MetadataStripPreviewResponse,
+    OperationProgressEvent, OperationSummary, PreviewStatus,
 };
+use crate::path_norm::to_windows_extended;
+use crate::progress_eta::EtaTracker;
+use rayon::prelude::*;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 
 // ===== TIFF Byte Order =====
 
@@ -149,6 +157,11 @@ const EXIF_DATETIME_TAGS: &[u16] = &[
     0x9290, 0x9291, 0x9292, // SubSecTime, SubSecTimeOriginal, SubSecTimeDigitized
 ];
 
+// GPS IFD tags adjusted by gps_blur
+const GPS_LATITUDE_TAG: u16 = 0x0002;
+const GPS_LONGITUDE_TAG: u16 = 0x0004;
+const GPS_ALTITUDE_TAG: u16 = 0x0006;
+
 // Thumbnail tags in IFD1
 const TAG_JPEG_INTERCHANGE_FORMAT: u16 = 0x0201;
 const TAG_JPEG_INTERCHANGE_FORMAT_LENGTH: u16 = 0x0202;
@@ -240,6 +253,41 @@ fn should_remove_exif_tag(tag: u16, cats: &MetadataStripCategories, is_full_clea
     false
 }
 
+/// Round latitude/longitude to 2 decimal places (~1 km) and drop altitude.
+/// Coordinates that are not the usual 3 RATIONALs are dropped rather than kept precise.
+fn blur_gps_entries(entries: Vec<IfdEntry>, order: ByteOrder) -> Vec<IfdEntry> {
+    entries
+        .into_iter()
+        .filter_map(|entry| match entry.tag {
+            GPS_ALTITUDE_TAG => None,
+            GPS_LATITUDE_TAG | GPS_LONGITUDE_TAG => blur_gps_coordinate(entry, order),
+            _ => Some(entry),
+        })
+        .collect()
+}
+
+fn blur_gps_coordinate(entry: IfdEntry, order: ByteOrder) -> Option<IfdEntry> {
+    if entry.dtype != 5 || entry.count != 3 || entry.data.len() < 24 {
+        return None;
+    }
+    let mut decimal = 0.0f64;
+    for (i, unit) in [1.0f64, 60.0, 3600.0].iter().enumerate() {
+        let numerator = read_u32_inline(&entry.data[i * 8..], order) as f64;
+        let denominator = read_u32_inline(&entry.data[i * 8 + 4..], order) as f64;
+        if denominator != 0.0 {
+            decimal += numerator / denominator / unit;
+        }
+    }
+    // Hundredths of a degree map exactly onto whole degrees + k*0.6 minutes.
+    let hundredths = (decimal * 100.0).round() as u32;
+    let mut data = Vec::with_capacity(24);
+    for (numerator, denominator) in [(hundredths / 100, 1), ((hundredths % 100) * 60, 100), (0, 1)] {
+        write_u32(&mut data, numerator, order);
+        write_u32(&mut data, denominator, order);
+    }
+    Some(IfdEntry { data, ..entry })
+}
+
 // ===== IFD Parsing =====
 
 fn parse_ifd_entries(
@@ -319,12 +367,14 @@ struct ScanResult {
     found_capture_datetime: bool,
     has_iptc: bool,
     has_xmp: bool,
+    has_icc_profile: bool,
+    has_mpf: bool,
     total_removable_tags: usize, // rough count of tags that could be stripped
     no_exif: bool,
 }
 
 fn scan_jpeg_metadata(path: &Path) -> Result<ScanResult, String> {
-    let data = fs::read(path).map_err(|e| format!("読み込みエラー: {}", e))?;
+    let data = fs::read(to_windows_extended(path)).map_err(|e| format!("読み込みエラー: {}", e))?;
 
     if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
         return Err("JPEGファイルではありません".to_string());
@@ -341,6 +391,8 @@ fn scan_jpeg_metadata(path: &Path) -> Result<ScanResult, String> {
         found_capture_datetime: false,
         has_iptc: false,
         has_xmp: false,
+        has_icc_profile: false,
+        has_mpf: false,
         total_removable_tags: 0,
         no_exif: true,
     };
@@ -394,6 +446,16 @@ fn scan_jpeg_metadata(path: &Path) -> Result<ScanResult, String> {
                     result.has_iptc = true;
                 }
             }
+            // APP2 (ICC profile)
+            0xE2 if seg_start + 12 <= seg_end
+                && &data[seg_start..seg_start + 12] == b"ICC_PROFILE\0" =>
+            {
+                result.has_icc_profile = true;
+            }
+            // APP2 (MPF index)
+            0xE2 if is_mpf_segment(&data, seg_start, seg_end) => {
+                result.has_mpf = true;
+            }
             _ => {}
         }
 
@@ -403,6 +465,11 @@ fn scan_jpeg_metadata(path: &Path) -> Result<ScanResult, String> {
     Ok(result)
 }
 
+/// APP2 payload starting with the Multi-Picture Format signature.
+fn is_mpf_segment(data: &[u8], payload_start: usize, seg_end: usize) -> bool {
+    payload_start + 4 <= seg_end && &data[payload_start..payload_start + 4] == b"MPF\0"
+}
+
 fn scan_tiff(data: &[u8], tiff_start: usize, seg_end: usize, result: &mut ScanResult) {
     if tiff_start + 8 > seg_end {
         return;
@@ -511,6 +578,8 @@ fn scan_tiff(data: &[u8], tiff_start: usize, seg_end: usize, result: &mut ScanRe
 struct StripResult {
     new_tiff: Vec<u8>,
     stripped_count: usize,
+    /// No IFD0 entries and no thumbnail survived, so the APP1 segment can be dropped.
+    is_empty: bool,
 }
 
 fn rebuild_tiff(
@@ -548,6 +617,33 @@ fn rebuild_tiff(
         (vec![], 0)
     };
 
+    // Parse GPS IFD when it is kept, so it can be re-emitted at its new offset
+    let gps_ifd_rel: Option<usize> = ifd0_entries
+        .iter()
+        .find(|e| e.tag == TAG_GPS_IFD_POINTER)
+        .and_then(|e| {
+            if e.data.len() >= 4 {
+                Some(read_u32_inline(&e.data, order) as usize)
+            } else {
+                None
+            }
+        });
+    let (gps_entries, gps_stripped) = match gps_ifd_rel {
+        Some(offset) if !should_remove_ifd0_tag(TAG_GPS_IFD_POINTER, cats, is_full_clean) => {
+            let (entries, _) = parse_ifd_entries(data, tiff_start, offset, seg_end, order);
+            let original_len = entries.len();
+            let entries = if cats.gps_blur {
+                blur_gps_entries(entries, order)
+            } else {
+                entries
+            };
+            let stripped = original_len - entries.len();
+            (entries, stripped)
+        }
+        _ => (vec![], 0),
+    };
+    let has_gps = !gps_entries.is_empty();
+
     // Parse IFD1 (thumbnail)
     let (ifd1_entries, _) = if ifd0_next_rel != 0 {
         parse_ifd_entries(data, tiff_start, ifd0_next_rel as usize, seg_end, order)
@@ -593,6 +689,9 @@ fn rebuild_tiff(
             if e.tag == TAG_EXIF_IFD_POINTER {
                 return has_exif; // Keep pointer only if Exif IFD has entries
             }
+            if e.tag == TAG_GPS_IFD_POINTER {
+                return has_gps;
+            }
             !should_remove_ifd0_tag(e.tag, cats, is_full_clean)
         })
         .cloned()
@@ -602,7 +701,7 @@ fn rebuild_tiff(
     let ifd0_stripped = ifd0_entries.len() - filtered_ifd0.len();
     let exif_stripped = exif_entries.len() - filtered_exif.len();
     let thumb_stripped = if cats.thumbnail { ifd1_entries.len() } else { 0 };
-    let stripped_count = ifd0_stripped + exif_stripped + thumb_stripped;
+    let stripped_count = ifd0_stripped + exif_stripped + gps_stripped + thumb_stripped;
 
     // ===== Build new TIFF =====
     let mut out: Vec<u8> = Vec::new();
@@ -623,6 +722,7 @@ fn rebuild_tiff(
     write_u16(&mut out, filtered_ifd0.len() as u16, order);
 
     let mut exif_ptr_field_pos: Option<usize> = None;
+    let mut gps_ptr_field_pos: Option<usize> = None;
     let mut ifd0_overflow_cursor = ifd0_data_area_start;
 
     for entry in &filtered_ifd0 {
@@ -633,6 +733,9 @@ fn rebuild_tiff(
         if entry.tag == TAG_EXIF_IFD_POINTER {
             exif_ptr_field_pos = Some(out.len());
             write_u32(&mut out, 0u32, order); // placeholder
+        } else if entry.tag == TAG_GPS_IFD_POINTER {
+            gps_ptr_field_pos = Some(out.len());
+            write_u32(&mut out, 0u32, order); // placeholder
         } else {
             let bc = entry.byte_count();
             if bc <= 4 {
@@ -656,7 +759,7 @@ fn rebuild_tiff(
 
     // Write IFD0 overflow data
     for entry in &filtered_ifd0 {
-        if entry.tag == TAG_EXIF_IFD_POINTER {
+        if entry.tag == TAG_EXIF_IFD_POINTER || entry.tag == TAG_GPS_IFD_POINTER {
             continue;
         }
         let bc = entry.byte_count();
@@ -717,6 +820,50 @@ fn rebuild_tiff(
         }
     }
 
+    // ===== GPS IFD =====
+    if has_gps {
+        let gps_ifd_pos = out.len() as u32;
+        if let Some(pos) = gps_ptr_field_pos {
+            patch_u32(&mut out, pos, gps_ifd_pos, order);
+        }
+
+        let gps_data_area_start = out.len() + 2 + gps_entries.len() * 12 + 4;
+        write_u16(&mut out, gps_entries.len() as u16, order);
+
+        let mut gps_overflow_cursor = gps_data_area_start;
+
+        for entry in &gps_entries {
+            write_u16(&mut out, entry.tag, order);
+            write_u16(&mut out, entry.dtype, order);
+            write_u32(&mut out, entry.count, order);
+
+            let bc = entry.byte_count();
+            if bc <= 4 {
+                out.extend_from_slice(&entry.data);
+                out.resize(out.len() + 4 - entry.data.len().min(4), 0);
+            } else {
+                write_u32(&mut out, gps_overflow_cursor as u32, order);
+                gps_overflow_cursor += bc;
+                if gps_overflow_cursor % 2 != 0 {
+                    gps_overflow_cursor += 1;
+                }
+            }
+        }
+
+        // GPS next_ifd = 0
+        write_u32(&mut out, 0u32, order);
+
+        for entry in &gps_entries {
+            let bc = entry.byte_count();
+            if bc > 4 {
+                out.extend_from_slice(&entry.data);
+                if out.len() % 2 != 0 {
+                    out.push(0);
+                }
+            }
+        }
+    }
+
     // ===== IFD1 (thumbnail) =====
     if thumbnail_data.is_some() {
         let ifd1_pos = out.len() as u32;
@@ -783,6 +930,7 @@ fn rebuild_tiff(
     Ok(StripResult {
         new_tiff: out,
         stripped_count,
+        is_empty: filtered_ifd0.is_empty() && thumbnail_data.is_none(),
     })
 }
 
@@ -792,9 +940,10 @@ fn strip_metadata_from_jpeg(
     path: &Path,
     cats: &MetadataStripCategories,
     is_full_clean: bool,
-) -> Result<(usize, bool, bool), String> {
-    // Returns (stripped_tag_count, stripped_iptc, stripped_xmp)
-    let data = fs::read(path).map_err(|e| format!("読み込みエラー: {}", e))?;
+    dry_run: bool,
+) -> Result<(usize, bool, bool, bool), String> {
+    // Returns (stripped_tag_count, stripped_iptc, stripped_xmp, stripped_icc_profile)
+    let data = fs::read(to_windows_extended(path)).map_err(|e| format!("読み込みエラー: {}", e))?;
 
     if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
         return Err("JPEGファイルではありません".to_string());
@@ -808,6 +957,8 @@ fn strip_metadata_from_jpeg(
     let mut stripped_tags = 0usize;
     let mut stripped_iptc = false;
     let mut stripped_xmp = false;
+    let mut stripped_icc_profile = false;
+    let mut stripped_mpf = false;
 
     let mut pos = 2usize;
     while pos < data.len() {
@@ -885,6 +1036,10 @@ fn strip_metadata_from_jpeg(
                         };
 
                         match rebuild_tiff(&data, tiff_start, seg_end, order, cats, is_full_clean) {
+                            Ok(strip_result) if strip_result.is_empty => {
+                                // Nothing left to keep: drop the whole APP1
+                                stripped_tags += strip_result.stripped_count;
+                            }
                             Ok(strip_result) => {
                                 stripped_tags += strip_result.stripped_count;
                                 // Build new APP1 segment: "Exif\0\0" + new TIFF
@@ -937,6 +1092,22 @@ fn strip_metadata_from_jpeg(
                     out.extend_from_slice(&data[pos..seg_end]);
                 }
             }
+            0xE2 => {
+                // APP2
+                if cats.icc_profile
+                    && seg_payload_start + 12 <= seg_end
+                    && &data[seg_payload_start..seg_payload_start + 12] == b"ICC_PROFILE\0"
+                {
+                    stripped_icc_profile = true;
+                    // Remove: don't copy
+                } else if cats.thumbnail && is_mpf_segment(&data, seg_payload_start, seg_end) {
+                    // The MPF index references the embedded thumbnail/preview images;
+                    // drop the whole segment rather than rewriting its IFD.
+                    stripped_mpf = true;
+                } else {
+                    out.extend_from_slice(&data[pos..seg_end]);
+                }
+            }
             _ => {
                 // All other segments: keep as-is
                 out.extend_from_slice(&data[pos..seg_end]);
@@ -946,16 +1117,255 @@ fn strip_metadata_from_jpeg(
         pos = seg_end;
     }
 
-    if stripped_tags == 0 && !stripped_iptc && !stripped_xmp {
+    if stripped_tags == 0
+        && !stripped_iptc
+        && !stripped_xmp
+        && !stripped_icc_profile
+        && !stripped_mpf
+    {
+        return Err("削除するメタデータが見つかりませんでした".to_string());
+    }
+
+    let out = ensure_leading_app0(out);
+
+    if !dry_run {
+        atomic_write_replace(path, &out).map_err(|e| e.to_string())?;
+    }
+    Ok((stripped_tags, stripped_iptc, stripped_xmp, stripped_icc_profile))
+}
+
+/// Header segments before SOS as `(marker, start, end)`; stops at the first
+/// standalone or malformed marker.
+fn header_segments(data: &[u8]) -> Vec<(u8, usize, usize)> {
+    let mut segments = Vec::new();
+    let mut pos = 2usize;
+    while pos + 4 <= data.len() && data[pos] == 0xFF {
+        let marker = data[pos + 1];
+        if marker == 0xDA || marker == 0xD8 || marker == 0xD9 || marker == 0x00 {
+            break;
+        }
+        if (0xD0..=0xD7).contains(&marker) {
+            break;
+        }
+        let seg_len = ((data[pos + 2] as usize) << 8) | (data[pos + 3] as usize);
+        if seg_len < 2 || pos + 2 + seg_len > data.len() {
+            break;
+        }
+        segments.push((marker, pos, pos + 2 + seg_len));
+        pos += 2 + seg_len;
+    }
+    segments
+}
+
+/// Keeps the first segment after SOI an APP0 or Exif APP1. When stripping leaves
+/// another segment in front, the first APP0 is moved directly after SOI, since some
+/// decoders only recognise JFIF there.
+fn ensure_leading_app0(out: Vec<u8>) -> Vec<u8> {
+    let segments = header_segments(&out);
+    let Some(&(first_marker, first_start, first_end)) = segments.first() else {
+        return out;
+    };
+    let first_is_exif =
+        first_start + 10 <= first_end && &out[first_start + 4..first_start + 10] == b"Exif\0\0";
+    if first_marker == 0xE0 || (first_marker == 0xE1 && first_is_exif) {
+        return out;
+    }
+    let Some(&(_, app0_start, app0_end)) = segments.iter().find(|(marker, _, _)| *marker == 0xE0)
+    else {
+        return out;
+    };
+
+    let mut reordered = Vec::with_capacity(out.len());
+    reordered.extend_from_slice(&out[..first_start]);
+    reordered.extend_from_slice(&out[app0_start..app0_end]);
+    reordered.extend_from_slice(&out[first_start..app0_start]);
+    reordered.extend_from_slice(&out[app0_end..]);
+    reordered
+}
+
+// ===== XMP sidecars =====
+
+/// Sidecar properties removed per category, with the label shown in previews.
+fn xmp_sidecar_groups(
+    cats: &MetadataStripCategories,
+) -> Vec<(&'static str, &'static [&'static str])> {
+    let mut groups: Vec<(&'static str, &'static [&'static str])> = Vec::new();
+    if cats.gps {
+        groups.push(("GPS/位置情報", &["exif:GPSLatitude", "exif:GPSLongitude"]));
+    }
+    if cats.author_copyright {
+        groups.push(("作者/著作権", &["dc:creator", "xmpRights:Marked"]));
+    }
+    if cats.comments {
+        groups.push(("コメント/説明", &["photoshop:Instructions"]));
+    }
+    groups
+}
+
+fn is_xmp_sidecar(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("xmp"))
+}
+
+fn metadata_strip_extensions(cats: &MetadataStripCategories) -> Vec<&'static str> {
+    let mut extensions = JPEG_ALLOWED_EXTENSIONS.to_vec();
+    if cats.xmp {
+        extensions.extend_from_slice(XMP_ALLOWED_EXTENSIONS);
+    }
+    extensions
+}
+
+/// Byte range of the first `name` property, either as an element (`<name>…</name>`,
+/// `<name/>`) or as an attribute (`name="…"`), including its leading whitespace.
+fn xmp_property_span(text: &str, name: &str) -> Option<std::ops::Range<usize>> {
+    let leading_ws = |pos: usize| text[..pos].trim_end_matches(char::is_whitespace).len();
+    let mut search = 0;
+    while let Some(found) = text[search..].find(name) {
+        let start = search + found;
+        let end = start + name.len();
+        search = end;
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        match (before, after) {
+            (Some('<'), Some(c)) if c.is_whitespace() || c == '>' || c == '/' => {
+                let open_end = end + text[end..].find('>')? + 1;
+                if text[..open_end].ends_with("/>") {
+                    return Some(leading_ws(start - 1)..open_end);
+                }
+                let close = format!("</{}>", name);
+                let close_start = open_end + text[open_end..].find(&close)?;
+                return Some(leading_ws(start - 1)..close_start + close.len());
+            }
+            (Some(c), Some('=')) if c.is_whitespace() => {
+                let quote = text[end + 1..].chars().next()?;
+                if quote != '"' && quote != '\'' {
+                    continue;
+                }
+                let value_end = end + 2 + text[end + 2..].find(quote)?;
+                return Some(leading_ws(start)..value_end + 1);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Plain string surgery instead of an XML parser; returns the new text, the labels
+/// of the categories found and the number of properties removed.
+fn strip_xmp_text(text: &str, cats: &MetadataStripCategories) -> (String, Vec<String>, usize) {
+    let mut out = text.to_string();
+    let mut found_categories = Vec::new();
+    let mut removed = 0usize;
+    for (label, names) in xmp_sidecar_groups(cats) {
+        let before = removed;
+        for name in names {
+            while let Some(span) = xmp_property_span(&out, name) {
+                out.replace_range(span, "");
+                removed += 1;
+            }
+        }
+        if removed > before {
+            found_categories.push(label.to_string());
+        }
+    }
+    (out, found_categories, removed)
+}
+
+fn read_xmp_sidecar(path: &Path) -> Result<String, String> {
+    fs::read_to_string(to_windows_extended(path))
+        .map_err(|e| format!("XMPファイルの読み込みに失敗しました: {}", e))
+}
+
+/// Returns the number of properties removed; nothing is written on `dry_run`.
+fn strip_xmp_sidecar(
+    path: &Path,
+    cats: &MetadataStripCategories,
+    dry_run: bool,
+) -> Result<usize, String> {
+    let text = read_xmp_sidecar(path)?;
+    let (out, _, removed) = strip_xmp_text(&text, cats);
+    if removed == 0 {
         return Err("削除するメタデータが見つかりませんでした".to_string());
     }
+    if !dry_run {
+        atomic_write_replace(path, out.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    Ok(removed)
+}
 
-    atomic_write_replace(path, &out)?;
-    Ok((stripped_tags, stripped_iptc, stripped_xmp))
+fn preview_xmp_sidecar(path: &Path, cats: &MetadataStripCategories) -> MetadataStripPreviewItem {
+    let path_str = path.to_string_lossy().to_string();
+    let (found_categories, tags_to_strip, reason) = match read_xmp_sidecar(path) {
+        Ok(text) => {
+            let (_, found_categories, removed) = strip_xmp_text(&text, cats);
+            let reason = (removed == 0).then(|| "削除対象のメタデータがありません".to_string());
+            (found_categories, removed, reason)
+        }
+        Err(e) => (Vec::new(), 0, Some(e)),
+    };
+    MetadataStripPreviewItem {
+        source_path: path_str,
+        found_categories,
+        tags_to_strip,
+        has_iptc: false,
+        has_xmp: true,
+        has_icc_profile: false,
+        has_mpf: false,
+        status: if reason.is_none() {
+            PreviewStatus::Ready
+        } else {
+            PreviewStatus::Skipped
+        },
+        reason,
+    }
 }
 
 // ===== Preset resolution =====
 
+pub fn preset_infos() -> Vec<MetadataStripPresetInfo> {
+    [
+        (
+            MetadataStripPreset::SnsPublish,
+            "snsPublish",
+            "SNS公開用",
+            "位置情報・カメラ情報・コメント・サムネイルを削除",
+        ),
+        (
+            MetadataStripPreset::Delivery,
+            "delivery",
+            "納品用",
+            "カメラ情報・ソフトウェア・コメントを削除",
+        ),
+        (
+            MetadataStripPreset::PrivacyMinimal,
+            "privacyMinimal",
+            "プライバシー最小",
+            "位置情報とコメントのみを削除し、その他は保持",
+        ),
+        (
+            MetadataStripPreset::FullClean,
+            "fullClean",
+            "完全クリーン",
+            "すべてのメタデータを削除",
+        ),
+        (
+            MetadataStripPreset::Custom,
+            "custom",
+            "カスタム",
+            "削除するカテゴリを個別に指定",
+        ),
+    ]
+    .into_iter()
+    .map(|(preset, id, label, description)| MetadataStripPresetInfo {
+        id: id.to_string(),
+        label: label.to_string(),
+        description: description.to_string(),
+        default_categories: preset_to_categories(&preset, &MetadataStripCategories::default()),
+    })
+    .collect()
+}
+
 fn preset_to_categories(
     preset: &crate::model::MetadataStripPreset,
     custom: &MetadataStripCategories,
@@ -964,6 +1374,7 @@ fn preset_to_categories(
     match preset {
         SnsPublish => MetadataStripCategories {
             gps: true,
+            gps_blur: false,
             camera_lens: true,
             software: false,
             author_copyright: false,
@@ -971,11 +1382,13 @@ fn preset_to_categories(
             thumbnail: true,
             iptc: false,
             xmp: false,
+            icc_profile: false,
             shooting_settings: false,
             capture_datetime: false,
         },
         Delivery => MetadataStripCategories {
             gps: false,
+            gps_blur: false,
             camera_lens: true,
             software: true,
             author_copyright: false,
@@ -983,11 +1396,27 @@ fn preset_to_categories(
             thumbnail: false,
             iptc: false,
             xmp: false,
+            icc_profile: false,
+            shooting_settings: false,
+            capture_datetime: false,
+        },
+        PrivacyMinimal => MetadataStripCategories {
+            gps: true,
+            gps_blur: false,
+            camera_lens: false,
+            software: false,
+            author_copyright: false,
+            comments: true,
+            thumbnail: false,
+            iptc: false,
+            xmp: false,
+            icc_profile: false,
             shooting_settings: false,
             capture_datetime: false,
         },
         FullClean => MetadataStripCategories {
             gps: true,
+            gps_blur: false,
             camera_lens: true,
             software: true,
             author_copyright: true,
@@ -995,6 +1424,7 @@ fn preset_to_categories(
             thumbnail: true,
             iptc: true,
             xmp: true,
+            icc_profile: true,
             shooting_settings: true,
             capture_datetime: true,
         },
@@ -1008,13 +1438,20 @@ fn is_full_clean_preset(preset: &crate::model::MetadataStripPreset) -> bool {
 
 // ===== Preview =====
 
-pub fn preview(
+pub fn preview<FProgress>(
     request: &MetadataStripPreviewRequest,
-) -> Result<MetadataStripPreviewResponse, AppError> {
+    scan_threads: usize,
+    mut report_progress: FProgress,
+) -> Result<MetadataStripPreviewResponse, AppError>
+where
+    FProgress: FnMut(OperationProgressEvent),
+{
+    let cats = preset_to_categories(&request.preset, &request.categories);
     let collect = collect_targets_with_extensions(
         &request.input_paths,
         request.include_subfolders,
-        JPEG_ALLOWED_EXTENSIONS,
+        &metadata_strip_extensions(&cats),
+        request.max_files,
     )
     .map_err(AppError::InvalidRequest)?;
 
@@ -1030,18 +1467,28 @@ pub fn preview(
         return Err(AppError::InvalidRequest(msg));
     }
 
-    let cats = preset_to_categories(&request.preset, &request.categories);
-
     let mut items = Vec::with_capacity(collect.files.len());
     let mut ready = 0usize;
     let mut skipped = 0usize;
 
-    for file in &collect.files {
+    let (sidecars, jpegs): (Vec<PathBuf>, Vec<PathBuf>) = collect
+        .files
+        .iter()
+        .cloned()
+        .partition(|path| is_xmp_sidecar(path));
+    let scans = scan_files_parallel(&jpegs, scan_threads, &mut report_progress);
+
+    for (file, scan_result) in scans {
         let path_str = file.to_string_lossy().to_string();
 
-        match scan_jpeg_metadata(file) {
+        match scan_result {
             Ok(scan) => {
-                if scan.no_exif && !scan.has_iptc && !scan.has_xmp {
+                if scan.no_exif
+                    && !scan.has_iptc
+                    && !scan.has_xmp
+                    && !scan.has_icc_profile
+                    && !scan.has_mpf
+                {
                     skipped += 1;
                     items.push(MetadataStripPreviewItem {
                         source_path: path_str,
@@ -1049,6 +1496,8 @@ pub fn preview(
                         tags_to_strip: 0,
                         has_iptc: false,
                         has_xmp: false,
+                        has_icc_profile: false,
+                        has_mpf: false,
                         status: PreviewStatus::Skipped,
                         reason: Some("メタデータがありません".to_string()),
                     });
@@ -1060,6 +1509,8 @@ pub fn preview(
 
                 if cats.gps && scan.found_gps {
                     found_categories.push("GPS/位置情報".to_string());
+                } else if cats.gps_blur && scan.found_gps {
+                    found_categories.push("GPS/位置情報(精度低下)".to_string());
                 }
                 if cats.camera_lens && scan.found_camera_lens {
                     found_categories.push("カメラ/レンズ情報".to_string());
@@ -1082,6 +1533,12 @@ pub fn preview(
                 if cats.xmp && scan.has_xmp {
                     found_categories.push("XMP(APP1)".to_string());
                 }
+                if cats.icc_profile && scan.has_icc_profile {
+                    found_categories.push("ICCプロファイル(APP2)".to_string());
+                }
+                if cats.thumbnail && scan.has_mpf {
+                    found_categories.push("マルチピクチャ(MPF/APP2)".to_string());
+                }
                 if cats.shooting_settings && scan.found_shooting_settings {
                     found_categories.push("撮影時設定".to_string());
                 }
@@ -1099,6 +1556,8 @@ pub fn preview(
                         tags_to_strip: 0,
                         has_iptc: scan.has_iptc,
                         has_xmp: scan.has_xmp,
+                        has_icc_profile: scan.has_icc_profile,
+                        has_mpf: scan.has_mpf,
                         status: PreviewStatus::Skipped,
                         reason: Some("削除対象のメタデータがありません".to_string()),
                     });
@@ -1110,6 +1569,8 @@ pub fn preview(
                         tags_to_strip,
                         has_iptc: scan.has_iptc,
                         has_xmp: scan.has_xmp,
+                        has_icc_profile: scan.has_icc_profile,
+                        has_mpf: scan.has_mpf,
                         status: PreviewStatus::Ready,
                         reason: None,
                     });
@@ -1123,6 +1584,8 @@ pub fn preview(
                     tags_to_strip: 0,
                     has_iptc: false,
                     has_xmp: false,
+                    has_icc_profile: false,
+                    has_mpf: false,
                     status: PreviewStatus::Skipped,
                     reason: Some(e),
                 });
@@ -1130,14 +1593,101 @@ pub fn preview(
         }
     }
 
+    for sidecar in &sidecars {
+        let item = preview_xmp_sidecar(sidecar, &cats);
+        match item.status {
+            PreviewStatus::Ready => ready += 1,
+            PreviewStatus::Skipped => skipped += 1,
+        }
+        items.push(item);
+    }
+
     Ok(MetadataStripPreviewResponse {
         total: ready + skipped,
         ready,
         skipped,
         items,
+        warnings: collect.warnings(),
     })
 }
 
+/// Used when `AppSettings.metadata_scan_threads` is unset. Kept small since the drive
+/// type (SSD vs. spinning disk) can't be detected portably.
+pub const METADATA_SCAN_THREADS: usize = 4;
+
+const SCAN_PANIC_MESSAGE: &str = "メタデータ解析中に内部エラーが発生しました";
+
+/// Scans run on a dedicated rayon pool of `threads` workers while this thread reports
+/// progress in 10% steps.
+fn scan_files_parallel<'a, FProgress>(
+    files: &'a [PathBuf],
+    threads: usize,
+    report_progress: &mut FProgress,
+) -> Vec<(&'a PathBuf, Result<ScanResult, String>)>
+where
+    FProgress: FnMut(OperationProgressEvent),
+{
+    let total = files.len();
+    let processed = AtomicUsize::new(0);
+    let step = (total / 10).max(1);
+    let progress_event = |processed: usize, done: bool| OperationProgressEvent {
+        operation: "metadataStrip".to_string(),
+        processed,
+        total,
+        succeeded: processed,
+        failed: 0,
+        skipped: 0,
+        current_path: Some("メタデータ解析中...".to_string()),
+        done,
+        canceled: false,
+        eta_seconds: None,
+        bytes_processed: 0,
+        total_bytes: 0,
+    };
+
+    report_progress(progress_event(0, false));
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.max(1))
+        .build()
+        .ok();
+    let scans = std::thread::scope(|scope| {
+        let worker = scope.spawn(|| {
+            let scan = || {
+                files
+                    .par_iter()
+                    .map(|file| {
+                        let result = scan_jpeg_metadata(file);
+                        processed.fetch_add(1, Ordering::Relaxed);
+                        (file, result)
+                    })
+                    .collect::<Vec<_>>()
+            };
+            match &pool {
+                Some(pool) => pool.install(scan),
+                None => scan(),
+            }
+        });
+        let mut last_reported = 0usize;
+        while !worker.is_finished() {
+            std::thread::sleep(Duration::from_millis(100));
+            let current = processed.load(Ordering::Relaxed);
+            if current >= last_reported + step {
+                last_reported = current;
+                report_progress(progress_event(current, false));
+            }
+        }
+        // A panicking scan must not make files silently disappear from the preview.
+        worker.join().unwrap_or_else(|_| {
+            files
+                .iter()
+                .map(|file| (file, Err(SCAN_PANIC_MESSAGE.to_string())))
+                .collect()
+        })
+    });
+    report_progress(progress_event(total, true));
+    scans
+}
+
 // ===== Execute =====
 
 pub fn execute<FCancel, FProgress>(
@@ -1149,14 +1699,19 @@ where
     FCancel: Fn() -> bool,
     FProgress: FnMut(OperationProgressEvent),
 {
+    let cats = preset_to_categories(&request.preset, &request.categories);
     let collect = collect_targets_with_extensions(
         &request.input_paths,
         request.include_subfolders,
-        JPEG_ALLOWED_EXTENSIONS,
+        &metadata_strip_extensions(&cats),
+        request.max_files,
     )
     .map_err(AppError::InvalidRequest)?;
+    let warnings = collect.warnings();
+    if is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
 
-    let cats = preset_to_categories(&request.preset, &request.categories);
     let is_full_clean = is_full_clean_preset(&request.preset);
 
     let total = collect.files.len();
@@ -1166,6 +1721,8 @@ where
     let mut skipped = 0usize;
     let mut processed = 0usize;
     let mut canceled = false;
+    let mut eta = EtaTracker::new();
+    let dry_run = request.dry_run.unwrap_or(false);
 
     for file in &collect.files {
         if !canceled && is_cancelled() {
@@ -1182,6 +1739,7 @@ where
                 stripped_tags: 0,
                 stripped_iptc: false,
                 stripped_xmp: false,
+                stripped_icc_profile: false,
                 status: ExecuteStatus::Skipped,
                 reason: Some("キャンセルされました".to_string()),
             });
@@ -1195,20 +1753,33 @@ where
                 current_path: Some(path_str),
                 done: false,
                 canceled,
+                eta_seconds: eta.record(total.saturating_sub(processed)),
+                bytes_processed: 0,
+                total_bytes: 0,
             });
             continue;
         }
 
-        match strip_metadata_from_jpeg(file, &cats, is_full_clean) {
-            Ok((stripped_tags, stripped_iptc, stripped_xmp)) => {
+        let result = if is_xmp_sidecar(file) {
+            strip_xmp_sidecar(file, &cats, dry_run).map(|removed| (removed, false, false, false))
+        } else {
+            strip_metadata_from_jpeg(file, &cats, is_full_clean, dry_run)
+        };
+        match result {
+            Ok((stripped_tags, stripped_iptc, stripped_xmp, stripped_icc_profile)) => {
                 succeeded += 1;
                 details.push(MetadataStripExecuteDetail {
                     source_path: path_str.clone(),
                     stripped_tags,
                     stripped_iptc,
                     stripped_xmp,
+                    stripped_icc_profile,
                     status: ExecuteStatus::Succeeded,
-                    reason: None,
+                    reason: if dry_run {
+                        Some("ドライラン: ファイルは変更されていません".to_string())
+                    } else {
+                        None
+                    },
                 });
             }
             Err(e) => {
@@ -1220,6 +1791,7 @@ where
                         stripped_tags: 0,
                         stripped_iptc: false,
                         stripped_xmp: false,
+                        stripped_icc_profile: false,
                         status: ExecuteStatus::Skipped,
                         reason: Some(e),
                     });
@@ -1230,6 +1802,7 @@ where
                         stripped_tags: 0,
                         stripped_iptc: false,
                         stripped_xmp: false,
+                        stripped_icc_profile: false,
                         status: ExecuteStatus::Failed,
                         reason: Some(e),
                     });
@@ -1248,6 +1821,9 @@ where
             current_path: Some(path_str),
             done: false,
             canceled,
+            eta_seconds: eta.record(total.saturating_sub(processed)),
+            bytes_processed: 0,
+            total_bytes: 0,
         });
     }
 
@@ -1261,13 +1837,135 @@ where
         current_path: None,
         done: true,
         canceled,
+        eta_seconds: None,
+        bytes_processed: 0,
+        total_bytes: 0,
     });
 
     Ok(MetadataStripExecuteResponse {
-        processed: succeeded + failed + skipped,
-        succeeded,
-        failed,
-        skipped,
+        summary: OperationSummary::new(
+            "metadataStrip",
+            succeeded + failed + skipped,
+            succeeded,
+            failed,
+            skipped,
+            None,
+            canceled,
+        )
+        .with_warnings(warnings),
         details,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOI: [u8; 2] = [0xFF, 0xD8];
+    const SOS_AND_EOI: [u8; 6] = [0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9];
+
+    fn segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+        let len = (payload.len() + 2) as u16;
+        let mut seg = vec![0xFF, marker];
+        seg.extend_from_slice(&len.to_be_bytes());
+        seg.extend_from_slice(payload);
+        seg
+    }
+
+    fn jpeg(segments: &[&[u8]]) -> Vec<u8> {
+        let mut data = SOI.to_vec();
+        for seg in segments {
+            data.extend_from_slice(seg);
+        }
+        data.extend_from_slice(&SOS_AND_EOI);
+        data
+    }
+
+    #[test]
+    fn header_segments_stops_at_sos() {
+        let app0 = segment(0xE0, b"JFIF\0\x01\x01");
+        let dqt = segment(0xDB, &[0; 4]);
+        let data = jpeg(&[&app0, &dqt]);
+        let segments = header_segments(&data);
+        assert_eq!(
+            segments,
+            vec![
+                (0xE0, 2, 2 + app0.len()),
+                (0xDB, 2 + app0.len(), 2 + app0.len() + dqt.len()),
+            ]
+        );
+    }
+
+    #[test]
+    fn app0_kept_first_after_app1_is_stripped() {
+        // APP0 + Exif APP1 input with the APP1 removed: nothing to reorder.
+        let app0 = segment(0xE0, b"JFIF\0\x01\x01");
+        let dqt = segment(0xDB, &[0; 4]);
+        let stripped = jpeg(&[&app0, &dqt]);
+        assert_eq!(ensure_leading_app0(stripped.clone()), stripped);
+    }
+
+    #[test]
+    fn app0_moved_in_front_of_non_exif_app1() {
+        let xmp = segment(0xE1, b"http://ns.adobe.com/xap/1.0/\0");
+        let app0 = segment(0xE0, b"JFIF\0\x01\x01");
+        let dqt = segment(0xDB, &[0; 4]);
+        let out = ensure_leading_app0(jpeg(&[&xmp, &app0, &dqt]));
+        assert_eq!(out, jpeg(&[&app0, &xmp, &dqt]));
+    }
+
+    #[test]
+    fn exif_app1_before_app0_is_left_alone() {
+        let exif = segment(0xE1, b"Exif\0\0MM\0\x2A");
+        let app0 = segment(0xE0, b"JFIF\0\x01\x01");
+        let data = jpeg(&[&exif, &app0]);
+        assert_eq!(ensure_leading_app0(data.clone()), data);
+    }
+
+    #[test]
+    fn full_clean_drops_exif_app1_and_keeps_app0_first() {
+        let app0 = segment(0xE0, b"JFIF\0\x01\x01");
+        // Little-endian TIFF with a single IFD0 entry: Make = "Ab"
+        let mut exif_payload = b"Exif\0\0II\x2A\0\x08\0\0\0".to_vec();
+        exif_payload.extend_from_slice(&[0x01, 0x00, 0x0F, 0x01, 0x02, 0x00]);
+        exif_payload.extend_from_slice(&[0x03, 0x00, 0x00, 0x00, b'A', b'b', 0x00, 0x00]);
+        exif_payload.extend_from_slice(&[0x00; 4]);
+        let exif = segment(0xE1, &exif_payload);
+        let dqt = segment(0xDB, &[0; 4]);
+        let scan = [
+            0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0x00, 0x56, 0xFF, 0xD9,
+        ];
+
+        let mut data = SOI.to_vec();
+        for seg in [&app0, &exif, &dqt] {
+            data.extend_from_slice(seg);
+        }
+        data.extend_from_slice(&scan);
+
+        let path = std::env::temp_dir().join(format!("cfm-app0-{}.jpg", std::process::id()));
+        fs::write(&path, &data).unwrap();
+        let result =
+            strip_metadata_from_jpeg(&path, &MetadataStripCategories::default(), true, false);
+        let out = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(result.unwrap().0, 1);
+        let segments = header_segments(&out);
+        assert_eq!(segments.first().map(|s| s.0), Some(0xE0));
+        assert!(segments.iter().all(|s| s.0 != 0xE1));
+        assert!(out.ends_with(&scan));
+        let mut expected = SOI.to_vec();
+        expected.extend_from_slice(&app0);
+        expected.extend_from_slice(&dqt);
+        expected.extend_from_slice(&scan);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn no_app0_is_left_alone() {
+        let xmp = segment(0xE1, b"http://ns.adobe.com/xap/1.0/\0");
+        let dqt = segment(0xDB, &[0; 4]);
+        let data = jpeg(&[&xmp, &dqt]);
+        assert_eq!(ensure_leading_app0(data.clone()), data);
+    }
+}
@@ -20,6 +20,7 @@ where
             Item::Plain(s) => RenameTemplate {
                 name: s.clone(),
                 template: s,
+                group: None,
             },
         })
         .collect())
@@ -38,12 +39,33 @@ pub enum RenameSource {
 pub struct RenamePreviewRequest {
     pub input_paths: Vec<String>,
     pub include_subfolders: bool,
+    /// Levels below each input folder when `include_subfolders` is set; `None` is unlimited.
+    pub max_depth: Option<usize>,
+    /// Stops collection early; defaults to `file_collect::DEFAULT_MAX_FILES`.
+    pub max_files: Option<usize>,
     pub template: String,
     pub source: RenameSource,
     pub output_dir: Option<String>,
     pub duplicate_output: Option<bool>,
     pub conflict_policy: Option<CollisionPolicy>,
     pub use_ffprobe: Option<bool>,
+    pub unicode_normalize: Option<UnicodeNorm>,
+    pub dry_run: Option<bool>,
+    pub sequence_sort_by: Option<SequenceSortBy>,
+    /// Keep the source extension's case instead of lowercasing it.
+    pub preserve_extension_case: Option<bool>,
+    /// Annotate items whose timestamp looks like a camera clock failure.
+    pub warn_on_anomalous_datetime: Option<bool>,
+    /// Abort instead of skipping subfolders/files that cannot be read.
+    pub fail_on_permission_denied: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SequenceSortBy {
+    FileName,
+    CaptureDateTime,
+    FileModified,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +91,12 @@ pub struct RenamePreviewResponse {
     pub total: usize,
     pub ready: usize,
     pub skipped: usize,
+    pub anomalous_datetime_count: usize,
+    /// Files per timestamp source label (`EXIF`, `ffprobe`, `ファイル更新日時`, `なし`, ...).
+    pub timestamp_source_counts: HashMap<String, usize>,
+    pub source_by_extension: Vec<ExtensionCount>,
+    /// e.g. `file_collect::MAX_FILES_WARNING` when collection was truncated.
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -88,13 +116,60 @@ pub enum ExecuteStatus {
     Skipped,
 }
 
+/// Counts shared by every execute response; flattened so the JSON keeps its old shape.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct RenameExecuteResponse {
+pub struct OperationSummary {
+    pub operation: String,
     pub processed: usize,
     pub succeeded: usize,
     pub failed: usize,
     pub skipped: usize,
+    /// Filled in by the command layer once the run has finished.
+    #[serde(default)]
+    pub elapsed_ms: Option<u64>,
+    #[serde(default)]
+    pub canceled: bool,
+    /// Collection warnings such as hitting `max_files`.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl OperationSummary {
+    pub fn new(
+        operation: &str,
+        processed: usize,
+        succeeded: usize,
+        failed: usize,
+        skipped: usize,
+        elapsed_ms: Option<u64>,
+        canceled: bool,
+    ) -> Self {
+        Self {
+            operation: operation.to_string(),
+            processed,
+            succeeded,
+            failed,
+            skipped,
+            elapsed_ms,
+            canceled,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn with_warnings(mut self, warnings: Vec<String>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameExecuteResponse {
+    #[serde(flatten)]
+    pub summary: OperationSummary,
+    /// Moves that fell back to copy + delete (e.g. across drives).
+    pub copy_fallback_count: usize,
     pub details: Vec<RenameExecuteDetail>,
 }
 
@@ -106,15 +181,38 @@ pub struct RenameTemplateTag {
     pub description: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateWarning {
+    /// `low_uniqueness` or `static_template`.
+    pub code: String,
+    pub message: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeletePreviewRequest {
     pub input_paths: Vec<String>,
     pub include_subfolders: bool,
+    pub max_depth: Option<usize>,
+    pub max_files: Option<usize>,
     pub extensions: Vec<String>,
     pub mode: DeleteMode,
     pub retreat_dir: Option<String>,
     pub conflict_policy: Option<CollisionPolicy>,
+    pub dry_run: Option<bool>,
+    /// File name globs (e.g. `DSC_*.RAF`); combined with `extensions` using AND.
+    pub glob_patterns: Option<Vec<String>>,
+    /// Remove folders left empty under the input folders after execution.
+    pub remove_empty_dirs: Option<bool>,
+    /// Explicitly keep the folder skeleton; conflicts with `remove_empty_dirs`.
+    pub preserve_directory_structure: Option<bool>,
+    /// Abort instead of skipping subfolders/files that cannot be read.
+    pub fail_on_permission_denied: Option<bool>,
+    /// RFC 3339 or `YYYY-MM-DD` (local midnight); files modified at or after this are skipped.
+    pub modified_before: Option<String>,
+    /// Same format; files modified before this are skipped.
+    pub modified_after: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -134,6 +232,8 @@ pub struct DeletePreviewResponse {
     pub total: usize,
     pub ready: usize,
     pub skipped: usize,
+    pub source_by_extension: Vec<ExtensionCount>,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -149,10 +249,10 @@ pub struct DeleteExecuteDetail {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteExecuteResponse {
-    pub processed: usize,
-    pub succeeded: usize,
-    pub failed: usize,
-    pub skipped: usize,
+    #[serde(flatten)]
+    pub summary: OperationSummary,
+    /// Moves that fell back to copy + delete (e.g. across drives).
+    pub copy_fallback_count: usize,
     pub details: Vec<DeleteExecuteDetail>,
 }
 
@@ -162,14 +262,52 @@ pub enum CollisionPolicy {
     Overwrite,
     Sequence,
     Skip,
+    /// Prefix the conflicting file with its parent folder name.
+    PrefixParent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UnicodeNorm {
+    Nfc,
+    Nfd,
+    None,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FlattenPreviewRequest {
+    #[serde(default)]
     pub input_dir: String,
+    /// Several input folders flattened into one output; takes precedence over `input_dir`.
+    pub input_dirs: Option<Vec<String>>,
     pub output_dir: Option<String>,
     pub conflict_policy: CollisionPolicy,
+    pub unicode_normalize: Option<UnicodeNorm>,
+    pub dry_run: Option<bool>,
+    pub undo_manifest_path: Option<String>,
+    /// Move `.xmp`/`.thm`/`.pp3`/`.dop` sidecars along with the file they belong to.
+    pub preserve_sidecar_pairing: Option<bool>,
+    /// Replaces `_flattened_` in the auto-generated output folder name.
+    pub output_suffix: Option<String>,
+    /// Include dotfiles such as `._` resource forks (default false).
+    pub include_hidden: Option<bool>,
+    /// Include OS metadata files like `Thumbs.db` / `desktop.ini` (default false).
+    pub include_system_files: Option<bool>,
+    /// Move files up into the input folder itself instead of copying to a new folder.
+    pub in_place: Option<bool>,
+    pub max_files: Option<usize>,
+    pub group_by_date: Option<DateGroupBy>,
+    /// Date folder name such as `YYYY-MM-DD` (the default).
+    pub date_group_format: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DateGroupBy {
+    None,
+    ByModified,
+    ByCaptureDate,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -179,6 +317,9 @@ pub struct FlattenPreviewItem {
     pub destination_path: String,
     pub status: PreviewStatus,
     pub reason: Option<String>,
+    pub is_sidecar_of: Option<String>,
+    /// `copy`, or `move` for in-place flatten.
+    pub action: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -190,6 +331,9 @@ pub struct FlattenPreviewResponse {
     pub ready: usize,
     pub skipped: usize,
     pub collisions: usize,
+    /// Hidden and system files left out of the plan.
+    pub hidden_files_skipped: usize,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -205,18 +349,27 @@ pub struct FlattenExecuteDetail {
 #[serde(rename_all = "camelCase")]
 pub struct FlattenExecuteResponse {
     pub output_dir: String,
-    pub processed: usize,
-    pub succeeded: usize,
-    pub failed: usize,
-    pub skipped: usize,
+    #[serde(flatten)]
+    pub summary: OperationSummary,
     pub details: Vec<FlattenExecuteDetail>,
 }
 
+/// One inverse move recorded in a flatten undo manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlattenUndoEntry {
+    pub from: String,
+    pub to: String,
+    /// `from` is a copy and `to` was left in place, so undo deletes the copy.
+    #[serde(default)]
+    pub copied: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CompressPreviewRequest {
     pub input_paths: Vec<String>,
     pub include_subfolders: bool,
+    pub max_files: Option<usize>,
     pub resize_percent: f32,
     pub quality: u8,
     pub target_size_kb: Option<u64>,
@@ -224,6 +377,29 @@ pub struct CompressPreviewRequest {
     pub preserve_exif: bool,
     pub output_dir: Option<String>,
     pub conflict_policy: CollisionPolicy,
+    pub dry_run: Option<bool>,
+    pub min_source_size_kb: Option<u64>,
+    pub verify_output: Option<bool>,
+    /// Give up on a single file after this many milliseconds and mark it failed.
+    pub item_timeout_ms: Option<u64>,
+    /// Outputs below this size skip the temp-file + rename write path.
+    pub use_direct_write_threshold_bytes: Option<u64>,
+    /// Skip sources that a q95 re-encode barely shrinks.
+    pub skip_if_compressed: Option<bool>,
+    /// Replaces `_compressed_` in the auto-generated output folder name.
+    pub output_suffix: Option<String>,
+    /// Write `photo_compressed.jpg` next to each source; `output_dir` is ignored.
+    pub keep_original: Option<bool>,
+    pub aspect_ratio_filter: Option<AspectRatioFilter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AspectRatioFilter {
+    Portrait,
+    Landscape,
+    Square,
+    All,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -233,6 +409,12 @@ pub struct CompressPreviewItem {
     pub destination_path: String,
     pub source_size: u64,
     pub estimated_size: u64,
+    pub source_width: Option<u32>,
+    pub source_height: Option<u32>,
+    pub output_width: Option<u32>,
+    pub output_height: Option<u32>,
+    /// Share of `target_size_kb` weighted by this file's source size.
+    pub per_file_target_kb: Option<u64>,
     pub status: PreviewStatus,
     pub reason: Option<String>,
 }
@@ -249,7 +431,21 @@ pub struct CompressPreviewResponse {
     pub total: usize,
     pub ready: usize,
     pub skipped: usize,
-    pub warnings: usize,
+    /// Items whose estimated size falls outside the tolerance.
+    pub tolerance_warnings: usize,
+    pub filtered_by_size: usize,
+    pub filtered_by_aspect: usize,
+    pub suggest_iterations: Option<Vec<SuggestIteration>>,
+    pub warnings: Vec<String>,
+}
+
+/// One binary-search step taken while fitting the target size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestIteration {
+    pub resize_percent: f32,
+    pub estimated_ratio: f64,
+    pub estimated_bytes: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -260,6 +456,8 @@ pub struct CompressExecuteDetail {
     pub status: ExecuteStatus,
     pub output_size: Option<u64>,
     pub reason: Option<String>,
+    /// `"direct"` or `"atomic"`; empty when nothing was written.
+    pub write_mode: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -268,10 +466,8 @@ pub struct CompressExecuteResponse {
     pub output_dir: String,
     pub effective_resize_percent: f32,
     pub effective_quality: u8,
-    pub processed: usize,
-    pub succeeded: usize,
-    pub failed: usize,
-    pub skipped: usize,
+    #[serde(flatten)]
+    pub summary: OperationSummary,
     pub details: Vec<CompressExecuteDetail>,
 }
 
@@ -287,6 +483,9 @@ pub struct OperationProgressEvent {
     pub current_path: Option<String>,
     pub done: bool,
     pub canceled: bool,
+    pub eta_seconds: Option<u64>,
+    pub bytes_processed: u64,
+    pub total_bytes: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -311,6 +510,9 @@ pub enum DeleteMode {
 pub struct RenameTemplate {
     pub name: String,
     pub template: String,
+    /// Optional use-case group (e.g. "Wedding") for grouped selectors.
+    #[serde(default)]
+    pub group: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -320,7 +522,19 @@ pub struct AppSettings {
     #[serde(deserialize_with = "deserialize_rename_templates")]
     pub rename_templates: Vec<RenameTemplate>,
     pub output_directories: HashMap<String, String>,
+    /// Operation name → suffix for auto-generated output folders.
+    #[serde(default)]
+    pub output_suffixes: HashMap<String, String>,
     pub theme: ThemeMode,
+    /// Files the compress worker may decode at once (default 4) to bound memory use.
+    #[serde(default)]
+    pub compress_max_concurrent_decodes: Option<usize>,
+    /// Threads used to scan files during metadata strip preview (default 4).
+    #[serde(default)]
+    pub metadata_scan_threads: Option<usize>,
+    /// Keys of `output_directories` whose folder is missing; filled on load, never saved.
+    #[serde(skip)]
+    pub invalid_output_directories: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -332,6 +546,42 @@ pub struct ImportConflictPreview {
     pub theme_conflict: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternDiff {
+    pub name: String,
+    pub existing: DeletePattern,
+    pub imported: DeletePattern,
+    pub extensions_changed: bool,
+    pub mode_changed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateDiff {
+    pub name: String,
+    pub existing: RenameTemplate,
+    pub imported: RenameTemplate,
+    pub template_changed: bool,
+    pub group_changed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputDirDiff {
+    pub key: String,
+    pub existing_value: String,
+    pub imported_value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportMergePreview {
+    pub pattern_diffs: Vec<PatternDiff>,
+    pub template_diffs: Vec<TemplateDiff>,
+    pub output_dir_diffs: Vec<OutputDirDiff>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum ThemeMode {
@@ -345,6 +595,15 @@ pub enum ThemeMode {
 pub struct CompressCollectInfoResponse {
     pub file_count: usize,
     pub total_size: u64,
+    pub by_extension: Vec<ExtensionCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionCount {
+    pub extension: String,
+    pub count: usize,
+    pub total_size: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -353,6 +612,19 @@ pub struct CompressEstimateResponse {
     pub file_count: usize,
     pub total_source_size: u64,
     pub estimated_total_size: u64,
+    pub per_file_estimates: Option<Vec<PerFileEstimate>>,
+    pub estimate_confidence: Option<f64>,
+    pub samples_used: usize,
+    /// `uniform`, or `stratified` when source sizes vary widely.
+    pub sampling_strategy: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerFileEstimate {
+    pub path: String,
+    pub source_size: u64,
+    pub estimated_size: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -360,6 +632,8 @@ pub struct CompressEstimateResponse {
 pub struct EstimateProgressEvent {
     pub current: usize,
     pub total: usize,
+    /// Compression ratio over the samples finished so far.
+    pub partial_ratio: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -367,7 +641,14 @@ pub struct EstimateProgressEvent {
 pub struct ExifOffsetPreviewRequest {
     pub input_paths: Vec<String>,
     pub include_subfolders: bool,
+    pub max_files: Option<usize>,
     pub offset_seconds: i64,
+    /// Fractional-second digits (e.g. `"500"` = 0.5 s) added to SubSecTimeOriginal with the
+    /// sign of `offset_seconds`, carrying into DateTimeOriginal.
+    pub offset_subseconds: Option<String>,
+    pub dry_run: Option<bool>,
+    pub output_dir: Option<String>,
+    pub conflict_policy: Option<CollisionPolicy>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -375,9 +656,12 @@ pub struct ExifOffsetPreviewRequest {
 pub struct ExifOffsetPreviewItem {
     pub source_path: String,
     pub original_datetime: Option<String>,
+    pub source_tag: Option<String>,
     pub corrected_datetime: Option<String>,
     pub status: PreviewStatus,
     pub reason: Option<String>,
+    /// Corrected time is more than an hour from the median of all Ready files.
+    pub suspected_anomaly: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -387,12 +671,16 @@ pub struct ExifOffsetPreviewResponse {
     pub total: usize,
     pub ready: usize,
     pub skipped: usize,
+    /// Ready items, largest gap from the median corrected time first.
+    pub sorted_by_gap: Option<Vec<ExifOffsetPreviewItem>>,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExifOffsetExecuteDetail {
     pub source_path: String,
+    pub destination_path: Option<String>,
     pub status: ExecuteStatus,
     pub reason: Option<String>,
 }
@@ -400,19 +688,20 @@ pub struct ExifOffsetExecuteDetail {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExifOffsetExecuteResponse {
-    pub processed: usize,
-    pub succeeded: usize,
-    pub failed: usize,
-    pub skipped: usize,
+    #[serde(flatten)]
+    pub summary: OperationSummary,
     pub details: Vec<ExifOffsetExecuteDetail>,
 }
 
 // ===== Metadata Strip =====
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MetadataStripCategories {
     pub gps: bool,
+    /// Keep GPS but round coordinates to ~1 km; ignored when `gps` is set.
+    #[serde(default)]
+    pub gps_blur: bool,
     pub camera_lens: bool,
     pub software: bool,
     pub author_copyright: bool,
@@ -420,6 +709,9 @@ pub struct MetadataStripCategories {
     pub thumbnail: bool,
     pub iptc: bool,
     pub xmp: bool,
+    /// Also drop APP2 ICC profiles; off by default since it can shift colours.
+    #[serde(default)]
+    pub icc_profile: bool,
     pub shooting_settings: bool,
     #[serde(rename = "captureDateTime")]
     pub capture_datetime: bool,
@@ -430,17 +722,29 @@ pub struct MetadataStripCategories {
 pub enum MetadataStripPreset {
     SnsPublish,
     Delivery,
+    PrivacyMinimal,
     FullClean,
     Custom,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataStripPresetInfo {
+    pub id: String,
+    pub label: String,
+    pub description: String,
+    pub default_categories: MetadataStripCategories,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MetadataStripPreviewRequest {
     pub input_paths: Vec<String>,
     pub include_subfolders: bool,
+    pub max_files: Option<usize>,
     pub preset: MetadataStripPreset,
     pub categories: MetadataStripCategories,
+    pub dry_run: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -451,6 +755,8 @@ pub struct MetadataStripPreviewItem {
     pub tags_to_strip: usize,
     pub has_iptc: bool,
     pub has_xmp: bool,
+    pub has_icc_profile: bool,
+    pub has_mpf: bool,
     pub status: PreviewStatus,
     pub reason: Option<String>,
 }
@@ -462,6 +768,7 @@ pub struct MetadataStripPreviewResponse {
     pub total: usize,
     pub ready: usize,
     pub skipped: usize,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -471,6 +778,7 @@ pub struct MetadataStripExecuteDetail {
     pub stripped_tags: usize,
     pub stripped_iptc: bool,
     pub stripped_xmp: bool,
+    pub stripped_icc_profile: bool,
     pub status: ExecuteStatus,
     pub reason: Option<String>,
 }
@@ -478,10 +786,8 @@ pub struct MetadataStripExecuteDetail {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MetadataStripExecuteResponse {
-    pub processed: usize,
-    pub succeeded: usize,
-    pub failed: usize,
-    pub skipped: usize,
+    #[serde(flatten)]
+    pub summary: OperationSummary,
     pub details: Vec<MetadataStripExecuteDetail>,
 }
 
@@ -492,6 +798,7 @@ pub struct MetadataStripExecuteResponse {
 pub struct ResizePreviewRequest {
     pub input_paths: Vec<String>,
     pub include_subfolders: bool,
+    pub max_files: Option<usize>,
     pub size_px: u32,
     pub mode: String,
     pub small_image_policy: String,
@@ -524,6 +831,7 @@ pub struct ResizePreviewResponse {
     pub total: usize,
     pub ready: usize,
     pub skipped: usize,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -537,9 +845,307 @@ pub struct ResizeCollectInfoResponse {
 #[serde(rename_all = "camelCase")]
 pub struct ResizeExecuteResponse {
     pub output_dir: String,
+    #[serde(flatten)]
+    pub summary: OperationSummary,
+}
+
+// ===== Duplicate Detect =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HashAlgorithm {
+    Sha256,
+    Md5,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicateAction {
+    DeleteKeepFirst,
+    Retreat,
+    ReportOnly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateDetectRequest {
+    pub input_paths: Vec<String>,
+    pub include_subfolders: bool,
+    pub max_files: Option<usize>,
+    pub hash_algorithm: HashAlgorithm,
+    pub action: DuplicateAction,
+    pub retreat_dir: Option<String>,
+    pub conflict_policy: Option<CollisionPolicy>,
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub hash: String,
+    pub file_size: u64,
+    pub files: Vec<String>,
+    pub wasted_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateDetectResponse {
+    pub duplicate_groups: Vec<DuplicateGroup>,
+    pub total_files: usize,
+    pub duplicate_files: usize,
+    pub wasted_bytes: u64,
+    pub unreadable: usize,
+    pub warnings: Vec<String>,
+}
+
+// ===== Organize By Date =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeByDateRequest {
+    pub input_paths: Vec<String>,
+    pub include_subfolders: bool,
+    pub max_files: Option<usize>,
+    pub output_root: String,
+    pub date_format: String,
+    pub source: RenameSource,
+    pub conflict_policy: CollisionPolicy,
+    pub use_ffprobe: Option<bool>,
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeByDatePreviewItem {
+    pub source_path: String,
+    pub destination_path: Option<String>,
+    pub resolved_date: Option<String>,
+    pub status: PreviewStatus,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeByDatePreviewResponse {
+    pub output_root: String,
+    pub items: Vec<OrganizeByDatePreviewItem>,
+    pub total: usize,
+    pub ready: usize,
+    pub skipped: usize,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeByDateExecuteDetail {
+    pub source_path: String,
+    pub destination_path: Option<String>,
+    pub status: ExecuteStatus,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeByDateExecuteResponse {
+    pub output_root: String,
+    #[serde(flatten)]
+    pub summary: OperationSummary,
+    pub details: Vec<OrganizeByDateExecuteDetail>,
+}
+
+// ===== EXIF Read =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExifReadResult {
+    pub path: String,
+    pub fields: HashMap<u16, String>,
+    pub error: Option<String>,
+}
+
+// ===== File Info =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub modified_at: String,
+    pub created_at: Option<String>,
+    pub image_width: Option<u32>,
+    pub image_height: Option<u32>,
+    pub is_directory: bool,
+}
+
+/// Per-path entry of `batch_get_file_info`; exactly one of `info` / `error` is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileInfoResult {
+    pub path: String,
+    pub info: Option<FileInfo>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathValidation {
+    pub path: String,
+    pub exists: bool,
+    pub is_file: bool,
+    pub is_directory: bool,
+    pub is_readable: bool,
+    pub is_writable: bool,
+    pub size_bytes: Option<u64>,
+}
+
+// ===== Reorient =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorientPreviewRequest {
+    pub input_paths: Vec<String>,
+    pub include_subfolders: bool,
+    pub max_files: Option<usize>,
+    pub conflict_policy: CollisionPolicy,
+    pub output_dir: Option<String>,
+    /// JPEG re-encode quality (default 95).
+    pub quality: Option<u8>,
+    /// Prefer a lossless transform for 90°/180°/270° rotations. No DCT-domain transform is
+    /// bundled yet, so every file is still re-encoded and reported with `lossless: false`.
+    pub lossless_if_possible: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorientPreviewItem {
+    pub source_path: String,
+    pub destination_path: String,
+    pub orientation: Option<u16>,
+    pub status: PreviewStatus,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorientPreviewResponse {
+    pub items: Vec<ReorientPreviewItem>,
+    pub total: usize,
+    pub ready: usize,
+    pub skipped: usize,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorientExecuteDetail {
+    pub source_path: String,
+    pub destination_path: String,
+    pub original_orientation: u16,
+    pub applied_transform: String,
+    pub status: ExecuteStatus,
+    pub reason: Option<String>,
+    /// True when the rotation was applied without re-encoding; `reason` explains a fallback.
+    pub lossless: bool,
+    /// Edge pixels dropped by a lossless transform on non-multiple-of-8 sizes.
+    pub pixels_trimmed: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorientExecuteResponse {
+    #[serde(flatten)]
+    pub summary: OperationSummary,
+    pub details: Vec<ReorientExecuteDetail>,
+}
+
+// ===== Watch Folder =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchOperation {
+    Rename,
+    Compress,
+    MetadataStrip,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchFolderConfig {
+    pub folder_path: String,
+    pub include_subfolders: bool,
+    pub extensions: Vec<String>,
+    pub operation: WatchOperation,
+    pub rename_request: Option<RenamePreviewRequest>,
+    pub compress_request: Option<CompressPreviewRequest>,
+    pub metadata_strip_request: Option<MetadataStripPreviewRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchFolderEvent {
+    pub watch_id: String,
+    pub file_path: String,
+    pub operation: WatchOperation,
+    pub status: ExecuteStatus,
+    pub reason: Option<String>,
+}
+
+// ===== Result Export =====
+
+/// Flattened execute detail shared by every operation for CSV/JSON export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationResultRow {
+    pub source_path: String,
+    pub destination_path: Option<String>,
+    pub status: ExecuteStatus,
+    pub reason: Option<String>,
+}
+
+/// Preview items saved to disk for review before executing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewManifest {
+    pub manifest_version: u32,
+    pub manifest_type: String,
+    pub generated_at: String,
+    pub items: Vec<serde_json::Value>,
+}
+
+/// A compress request saved so the same batch can be replayed later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressBatchManifest {
+    /// App version that wrote the manifest.
+    pub cfm_version: String,
+    pub generated_at: String,
+    pub request: CompressPreviewRequest,
+}
+
+// ===== Operation History =====
+
+/// One completed execute call, stored as a line of `operation_history.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: String,
+    pub operation: String,
+    pub timestamp: String,
+    /// Files handled before the run ended; lower than the total for cancelled runs.
+    #[serde(default)]
+    pub processed: usize,
     pub succeeded: usize,
     pub failed: usize,
     pub skipped: usize,
+    #[serde(default)]
+    pub canceled: bool,
+    #[serde(default)]
+    pub cancel_reason: Option<String>,
+    /// Wall-clock time from start to completion or cancellation.
+    #[serde(default)]
+    pub elapsed_ms: Option<u64>,
+    pub parameters: serde_json::Value,
 }
 
 impl Default for AppSettings {
@@ -549,9 +1155,24 @@ impl Default for AppSettings {
             rename_templates: vec![RenameTemplate {
                 name: "日付通番".to_string(),
                 template: "{capture_date:YYYYMMDD}_{capture_time:HHmmss}_{seq:3}".to_string(),
+                group: None,
             }],
             output_directories: HashMap::new(),
+            output_suffixes: HashMap::new(),
             theme: ThemeMode::System,
+            compress_max_concurrent_decodes: None,
+            metadata_scan_threads: None,
+            invalid_output_directories: Vec::new(),
         }
     }
 }
+
+// ===== Errors =====
+
+/// Error payload every command returns as a JSON string; `code` mirrors `AppError::code`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorResponse {
+    pub code: String,
+    pub message: String,
+}
@@ -0,0 +1,456 @@
+use crate::error::AppError;
+use crate::file_collect::{collect_rename_targets, depth_limit};
+use crate::fs_atomic::{atomic_copy_replace, dry_run_check};
+use crate::model::{
+    CollisionPolicy, ExecuteStatus, OperationProgressEvent, OperationSummary,
+    OrganizeByDateExecuteDetail, OrganizeByDateExecuteResponse, OrganizeByDatePreviewItem,
+    OrganizeByDatePreviewResponse, OrganizeByDateRequest, PreviewStatus, RenameSource,
+};
+use crate::path_norm::destination_key;
+use crate::rename::{convert_datetime_format, prefetch_ffprobe_for_files, resolve_timestamp};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+struct PlannedOrganize {
+    source: PathBuf,
+    destination: Option<PathBuf>,
+    resolved_date: Option<String>,
+    status: PreviewStatus,
+    reason: Option<String>,
+}
+
+pub fn preview<FProgress>(
+    request: &OrganizeByDateRequest,
+    mut report_progress: FProgress,
+) -> Result<OrganizeByDatePreviewResponse, AppError>
+where
+    FProgress: FnMut(OperationProgressEvent),
+{
+    let (output_root, plan, warnings) = build_plan(request, &mut report_progress)?;
+    let mut ready = 0usize;
+    let mut skipped = 0usize;
+    let items = plan
+        .iter()
+        .map(|item| {
+            match item.status {
+                PreviewStatus::Ready => ready += 1,
+                PreviewStatus::Skipped => skipped += 1,
+            }
+            OrganizeByDatePreviewItem {
+                source_path: item.source.to_string_lossy().to_string(),
+                destination_path: item
+                    .destination
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().to_string()),
+                resolved_date: item.resolved_date.clone(),
+                status: item.status.clone(),
+                reason: item.reason.clone(),
+            }
+        })
+        .collect();
+
+    Ok(OrganizeByDatePreviewResponse {
+        output_root: output_root.to_string_lossy().to_string(),
+        items,
+        total: ready + skipped,
+        ready,
+        skipped,
+        warnings,
+    })
+}
+
+pub fn execute<FCancel, FProgress>(
+    request: &OrganizeByDateRequest,
+    is_cancelled: FCancel,
+    mut report_progress: FProgress,
+) -> Result<OrganizeByDateExecuteResponse, AppError>
+where
+    FCancel: Fn() -> bool,
+    FProgress: FnMut(OperationProgressEvent),
+{
+    let (output_root, plan, warnings) = build_plan(request, &mut report_progress)?;
+    if is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
+    let dry_run = request.dry_run.unwrap_or(false);
+
+    let total = plan.len();
+    let mut details = Vec::with_capacity(total);
+    let mut succeeded = 0usize;
+    let mut failed = 0usize;
+    let mut skipped = 0usize;
+    let mut processed = 0usize;
+    let mut canceled = false;
+
+    let cancel_requested = Arc::new(AtomicBool::new(false));
+    let worker_cancel = Arc::clone(&cancel_requested);
+    let (tx, rx) = mpsc::channel::<OrganizeByDateExecuteDetail>();
+
+    let worker = std::thread::spawn(move || {
+        plan.into_par_iter().for_each_with(tx, |sender, item| {
+            let detail = execute_one_organize(&item, worker_cancel.load(Ordering::SeqCst), dry_run);
+            let _ = sender.send(detail);
+        });
+    });
+
+    while processed < total {
+        if !canceled && is_cancelled() {
+            canceled = true;
+            cancel_requested.store(true, Ordering::SeqCst);
+        }
+
+        match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(detail) => {
+                processed += 1;
+                match detail.status {
+                    ExecuteStatus::Succeeded => succeeded += 1,
+                    ExecuteStatus::Failed => failed += 1,
+                    ExecuteStatus::Skipped => skipped += 1,
+                }
+                let current_path = Some(detail.source_path.clone());
+                details.push(detail);
+                report_progress(OperationProgressEvent {
+                    operation: "organizeByDate".to_string(),
+                    processed,
+                    total,
+                    succeeded,
+                    failed,
+                    skipped,
+                    current_path,
+                    done: false,
+                    canceled,
+                    eta_seconds: None,
+                    bytes_processed: 0,
+                    total_bytes: 0,
+                });
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    let _ = worker.join();
+
+    report_progress(OperationProgressEvent {
+        operation: "organizeByDate".to_string(),
+        processed,
+        total,
+        succeeded,
+        failed,
+        skipped,
+        current_path: None,
+        done: true,
+        canceled,
+        eta_seconds: None,
+        bytes_processed: 0,
+        total_bytes: 0,
+    });
+
+    Ok(OrganizeByDateExecuteResponse {
+        output_root: output_root.to_string_lossy().to_string(),
+        summary: OperationSummary::new(
+            "organizeByDate",
+            succeeded + failed + skipped,
+            succeeded,
+            failed,
+            skipped,
+            None,
+            canceled,
+        )
+        .with_warnings(warnings),
+        details,
+    })
+}
+
+fn execute_one_organize(
+    item: &PlannedOrganize,
+    canceled: bool,
+    dry_run: bool,
+) -> OrganizeByDateExecuteDetail {
+    let destination_path = item
+        .destination
+        .as_ref()
+        .map(|path| path.to_string_lossy().to_string());
+    if canceled || matches!(item.status, PreviewStatus::Skipped) {
+        return OrganizeByDateExecuteDetail {
+            source_path: item.source.to_string_lossy().to_string(),
+            destination_path,
+            status: ExecuteStatus::Skipped,
+            reason: if canceled {
+                Some("キャンセルされました".to_string())
+            } else {
+                item.reason.clone()
+            },
+        };
+    }
+
+    let Some(destination) = item.destination.as_ref() else {
+        return OrganizeByDateExecuteDetail {
+            source_path: item.source.to_string_lossy().to_string(),
+            destination_path: None,
+            status: ExecuteStatus::Skipped,
+            reason: Some("出力先が未定です".to_string()),
+        };
+    };
+
+    let result = if dry_run {
+        dry_run_check(&item.source)
+    } else if let Some(Err(error)) = destination.parent().map(fs::create_dir_all) {
+        Err(format!("出力先フォルダの作成に失敗しました: {}", error))
+    } else {
+        atomic_copy_replace(&item.source, destination).map(|()| None)
+    };
+
+    match result {
+        Ok(note) => OrganizeByDateExecuteDetail {
+            source_path: item.source.to_string_lossy().to_string(),
+            destination_path,
+            status: ExecuteStatus::Succeeded,
+            reason: note,
+        },
+        Err(error) => OrganizeByDateExecuteDetail {
+            source_path: item.source.to_string_lossy().to_string(),
+            destination_path,
+            status: ExecuteStatus::Failed,
+            reason: Some(error),
+        },
+    }
+}
+
+fn build_plan<FProgress>(
+    request: &OrganizeByDateRequest,
+    report_progress: &mut FProgress,
+) -> Result<(PathBuf, Vec<PlannedOrganize>, Vec<String>), AppError>
+where
+    FProgress: FnMut(OperationProgressEvent),
+{
+    let output_root = request.output_root.trim();
+    if output_root.is_empty() {
+        return Err(AppError::InvalidRequest(
+            "出力先フォルダを指定してください".to_string(),
+        ));
+    }
+    let output_root = PathBuf::from(output_root);
+    let segments = parse_date_format(&request.date_format)?;
+
+    let collect = collect_rename_targets(
+        &request.input_paths,
+        depth_limit(request.include_subfolders, None),
+        request.max_files,
+    )
+    .map_err(AppError::InvalidRequest)?;
+    if collect.files.is_empty() {
+        return Err(AppError::InvalidRequest(
+            "対象ファイルが見つかりません。".to_string(),
+        ));
+    }
+
+    let ffprobe_cache = if request.use_ffprobe.unwrap_or(false)
+        && matches!(request.source, RenameSource::CaptureThenModified)
+    {
+        prefetch_ffprobe_for_files(&collect.files, "organizeByDate", report_progress)
+    } else {
+        HashMap::new()
+    };
+
+    let mut used_destination_keys: HashSet<String> = HashSet::new();
+    let mut plan = Vec::with_capacity(collect.files.len());
+    for file in &collect.files {
+        let Some((timestamp, timestamp_source)) =
+            resolve_timestamp(file, &request.source, &ffprobe_cache)
+        else {
+            plan.push(PlannedOrganize {
+                source: file.clone(),
+                destination: None,
+                resolved_date: None,
+                status: PreviewStatus::Skipped,
+                reason: Some("タイムスタンプを取得できません".to_string()),
+            });
+            continue;
+        };
+
+        let Some(file_name) = file.file_name() else {
+            plan.push(PlannedOrganize {
+                source: file.clone(),
+                destination: None,
+                resolved_date: None,
+                status: PreviewStatus::Skipped,
+                reason: Some("無効なファイル名です".to_string()),
+            });
+            continue;
+        };
+
+        let base_destination = output_root
+            .join(date_path(&timestamp, &segments))
+            .join(file_name);
+        let (status, collision_reason, destination) = resolve_destination_for_policy(
+            &base_destination,
+            &mut used_destination_keys,
+            &request.conflict_policy,
+        );
+        let reason = match collision_reason {
+            Some(collision) => Some(format!("{} / {}", timestamp_source, collision)),
+            None => Some(timestamp_source.to_string()),
+        };
+
+        plan.push(PlannedOrganize {
+            source: file.clone(),
+            destination: Some(destination),
+            resolved_date: Some(timestamp.format("%Y-%m-%d %H:%M:%S").to_string()),
+            status,
+            reason,
+        });
+    }
+
+    // When overwrite policy is used and multiple sources map to the same
+    // destination, keep only the last writer (by sorted order) as Ready.
+    if matches!(request.conflict_policy, CollisionPolicy::Overwrite) {
+        let mut last_ready: HashMap<String, usize> = HashMap::new();
+        for (i, item) in plan.iter().enumerate() {
+            if let (PreviewStatus::Ready, Some(dest)) = (&item.status, &item.destination) {
+                last_ready.insert(destination_key(dest), i);
+            }
+        }
+        for (i, item) in plan.iter_mut().enumerate() {
+            if !matches!(item.status, PreviewStatus::Ready) {
+                continue;
+            }
+            if let Some(dest) = &item.destination {
+                if let Some(&last) = last_ready.get(&destination_key(dest)) {
+                    if i < last {
+                        item.status = PreviewStatus::Skipped;
+                        item.reason =
+                            Some("同一出力先の後続ファイルに置き換えられました".to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok((output_root, plan, collect.warnings()))
+}
+
+/// Splits `YYYY/MM/DD` style formats into per-directory strftime patterns.
+pub(crate) fn parse_date_format(value: &str) -> Result<Vec<String>, AppError> {
+    let segments: Vec<String> = value
+        .split(['/', '\\'])
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(convert_datetime_format)
+        .collect();
+    if segments.is_empty() {
+        return Err(AppError::InvalidRequest(
+            "日付フォルダの形式を指定してください".to_string(),
+        ));
+    }
+    if segments
+        .iter()
+        .any(|segment| StrftimeItems::new(segment).any(|item| matches!(item, Item::Error)))
+    {
+        return Err(AppError::InvalidRequest(format!(
+            "日付フォルダの形式が不正です: {}",
+            value
+        )));
+    }
+    Ok(segments)
+}
+
+pub(crate) fn date_path(timestamp: &DateTime<Local>, segments: &[String]) -> PathBuf {
+    let invalid_chars = ['<', '>', ':', '"', '|', '?', '*'];
+    segments
+        .iter()
+        .map(|segment| {
+            timestamp
+                .format(segment)
+                .to_string()
+                .chars()
+                .map(|ch| if invalid_chars.contains(&ch) { '_' } else { ch })
+                .collect::<String>()
+        })
+        .collect()
+}
+
+fn resolve_destination_for_policy(
+    base: &Path,
+    used_keys: &mut HashSet<String>,
+    policy: &CollisionPolicy,
+) -> (PreviewStatus, Option<String>, PathBuf) {
+    let key = destination_key(base);
+    let collision = used_keys.contains(&key) || base.exists();
+    match policy {
+        CollisionPolicy::Overwrite => {
+            used_keys.insert(key);
+            (
+                PreviewStatus::Ready,
+                if collision {
+                    Some("競合ポリシーにより上書きされます".to_string())
+                } else {
+                    None
+                },
+                base.to_path_buf(),
+            )
+        }
+        CollisionPolicy::Skip => {
+            if collision {
+                (
+                    PreviewStatus::Skipped,
+                    Some("ファイル名の競合によりスキップされました".to_string()),
+                    base.to_path_buf(),
+                )
+            } else {
+                used_keys.insert(key);
+                (PreviewStatus::Ready, None, base.to_path_buf())
+            }
+        }
+        CollisionPolicy::Sequence | CollisionPolicy::PrefixParent => {
+            let unique = uniquify_destination(base, used_keys);
+            (
+                PreviewStatus::Ready,
+                if unique != base {
+                    Some("競合のため連番サフィックスを付与しました".to_string())
+                } else {
+                    None
+                },
+                unique,
+            )
+        }
+    }
+}
+
+fn uniquify_destination(base: &Path, used_keys: &mut HashSet<String>) -> PathBuf {
+    let mut candidate = base.to_path_buf();
+    let mut suffix = 1usize;
+    loop {
+        let key = destination_key(&candidate);
+        if !used_keys.contains(&key) && !candidate.exists() {
+            used_keys.insert(key);
+            return candidate;
+        }
+        let stem = base
+            .file_stem()
+            .and_then(|value| value.to_str())
+            .unwrap_or("file");
+        let ext = base
+            .extension()
+            .and_then(|value| value.to_str())
+            .unwrap_or("");
+        let file_name = if ext.is_empty() {
+            format!("{}_{}", stem, suffix)
+        } else {
+            format!("{}_{}.{}", stem, suffix, ext)
+        };
+        candidate = base.parent().map_or_else(
+            || PathBuf::from(&file_name),
+            |parent| parent.join(&file_name),
+        );
+        suffix += 1;
+    }
+}
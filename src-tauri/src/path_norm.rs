@@ -1,6 +1,12 @@
+use crate::model::UnicodeNorm;
 use std::path::{Component, Path, PathBuf, Prefix};
+use unicode_normalization::UnicodeNormalization;
 
-/// `canonicalize()` wrapper that strips the Windows `\\?\` prefix.
+/// Legacy Win32 `MAX_PATH` (includes the terminating NUL).
+#[cfg(windows)]
+const WINDOWS_MAX_PATH: usize = 260;
+
+/// `canonicalize()` wrapper that strips the Windows `\\?\` prefix for paths short enough to work without it.
 pub fn safe_canonicalize(path: &Path) -> std::io::Result<PathBuf> {
     let canonical = path.canonicalize()?;
     Ok(strip_verbatim(canonical))
@@ -10,10 +16,11 @@ pub fn safe_canonicalize(path: &Path) -> std::io::Result<PathBuf> {
 fn strip_verbatim(path: PathBuf) -> PathBuf {
     let s = path.to_string_lossy();
     if let Some(stripped) = s.strip_prefix(r"\\?\") {
-        PathBuf::from(stripped)
-    } else {
-        path
+        if stripped.encode_utf16().count() < WINDOWS_MAX_PATH {
+            return PathBuf::from(stripped);
+        }
     }
+    path
 }
 
 #[cfg(not(windows))]
@@ -21,6 +28,86 @@ fn strip_verbatim(path: PathBuf) -> PathBuf {
     path
 }
 
+/// Prepends `\\?\` to absolute paths that exceed `MAX_PATH` so Win32 file APIs accept them.
+#[cfg(windows)]
+pub fn to_windows_extended(path: &Path) -> PathBuf {
+    let s = path.to_string_lossy();
+    if s.starts_with(r"\\?\")
+        || !path.is_absolute()
+        || s.encode_utf16().count() < WINDOWS_MAX_PATH
+    {
+        return path.to_path_buf();
+    }
+    let backslashed = s.replace('/', "\\");
+    if let Some(unc) = backslashed.strip_prefix(r"\\") {
+        PathBuf::from(format!(r"\\?\UNC\{}", unc))
+    } else {
+        PathBuf::from(format!(r"\\?\{}", backslashed))
+    }
+}
+
+#[cfg(not(windows))]
+pub fn to_windows_extended(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// Applies the requested Unicode normalization form to a file name.
+pub fn normalize_unicode(value: &str, form: Option<&UnicodeNorm>) -> String {
+    match form {
+        Some(UnicodeNorm::Nfc) => value.nfc().collect(),
+        Some(UnicodeNorm::Nfd) => value.nfd().collect(),
+        Some(UnicodeNorm::None) | None => value.to_string(),
+    }
+}
+
+/// Case- and normalization-insensitive key used for destination collision checks.
+pub fn destination_key(path: &Path) -> String {
+    path.to_string_lossy().nfc().collect::<String>().to_ascii_lowercase()
+}
+
+/// Checks a suffix used for auto-generated output folder names.
+pub fn validate_output_suffix(suffix: &str) -> Result<(), String> {
+    if suffix.trim().is_empty() {
+        return Err("出力フォルダのサフィックスを入力してください".to_string());
+    }
+    if suffix.contains(['/', '\\']) {
+        return Err("出力フォルダのサフィックスにパス区切り文字は使用できません".to_string());
+    }
+    Ok(())
+}
+
+/// Returns true for device names Windows refuses to create (`CON`, `NUL`, `COM1`, ...).
+pub fn is_reserved_windows_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or("").trim_end();
+    if stem.eq_ignore_ascii_case("CON")
+        || stem.eq_ignore_ascii_case("PRN")
+        || stem.eq_ignore_ascii_case("AUX")
+        || stem.eq_ignore_ascii_case("NUL")
+    {
+        return true;
+    }
+    match (stem.get(..3), stem.get(3..)) {
+        (Some(prefix), Some(digit)) => {
+            (prefix.eq_ignore_ascii_case("COM") || prefix.eq_ignore_ascii_case("LPT"))
+                && digit.len() == 1
+                && digit.as_bytes()[0].is_ascii_digit()
+        }
+        _ => false,
+    }
+}
+
+/// Number of components, counting a drive/UNC prefix and the root as one each.
+pub fn path_depth(path: &Path) -> usize {
+    path.components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .count()
+}
+
+/// Levels below `root`; `Some(0)` for `root` itself, `None` when `path` is not under it.
+pub fn path_depth_below(path: &Path, root: &Path) -> Option<usize> {
+    path.strip_prefix(root).ok().map(path_depth)
+}
+
 pub fn relative_or_portable_absolute(path: &Path, root: Option<&Path>) -> PathBuf {
     if let Some(root) = root {
         if let Ok(relative) = path.strip_prefix(root) {
@@ -57,3 +144,57 @@ fn push_prefix(out: &mut PathBuf, prefix: Prefix<'_>) {
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_below_root_itself_is_zero() {
+        let root = Path::new("/photos/2024");
+        assert_eq!(path_depth_below(root, root), Some(0));
+        assert_eq!(
+            path_depth_below(Path::new("/photos/2024/a.jpg"), root),
+            Some(1)
+        );
+        assert_eq!(
+            path_depth_below(Path::new("/photos/2024/x/a.jpg"), root),
+            Some(2)
+        );
+        assert_eq!(
+            path_depth_below(Path::new("/photos/2023/a.jpg"), root),
+            None
+        );
+    }
+
+    #[test]
+    fn depth_ignores_current_dir_components() {
+        assert_eq!(path_depth(Path::new("./a/./b")), 2);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn depth_counts_drive_root_once() {
+        assert_eq!(path_depth(Path::new(r"C:\")), 2);
+        assert_eq!(path_depth(Path::new(r"C:\photos\a.jpg")), 4);
+        let root = Path::new(r"C:\");
+        assert_eq!(path_depth_below(root, root), Some(0));
+        assert_eq!(path_depth_below(Path::new(r"C:\a.jpg"), root), Some(1));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn depth_counts_unc_prefix_once() {
+        assert_eq!(path_depth(Path::new(r"\\server\share\photos")), 3);
+        let root = Path::new(r"\\server\share");
+        assert_eq!(path_depth_below(root, root), Some(0));
+        assert_eq!(
+            path_depth_below(Path::new(r"\\server\share\photos\a.jpg"), root),
+            Some(2)
+        );
+        assert_eq!(
+            path_depth_below(Path::new(r"\\other\share\a.jpg"), root),
+            None
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use crate::error::AppError;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+const SORTABLE_FIELDS: &[&str] = &[
+    "sourcePath",
+    "destinationPath",
+    "status",
+    "reason",
+    "sourceSize",
+];
+
+/// Sort serialized preview items by one of their camelCase fields.
+/// Items missing the field (or holding null) always come last.
+pub fn sort_preview_items(
+    mut items: Vec<Value>,
+    field: &str,
+    ascending: bool,
+) -> Result<Vec<Value>, AppError> {
+    if !SORTABLE_FIELDS.contains(&field) {
+        return Err(AppError::InvalidRequest(format!(
+            "並べ替えに対応していない項目です: {}",
+            field
+        )));
+    }
+
+    items.sort_by(|a, b| match (sort_value(a, field), sort_value(b, field)) {
+        (Some(a), Some(b)) => {
+            let ordering = compare_values(field, a, b);
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    Ok(items)
+}
+
+fn sort_value<'a>(item: &'a Value, field: &str) -> Option<&'a Value> {
+    item.get(field).filter(|value| !value.is_null())
+}
+
+fn compare_values(field: &str, a: &Value, b: &Value) -> Ordering {
+    if field == "status" {
+        return status_rank(a).cmp(&status_rank(b));
+    }
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => {
+            let a = a.as_f64().unwrap_or(0.0);
+            let b = b.as_f64().unwrap_or(0.0);
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(a), Value::String(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+        _ => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+/// Errors sort first so that problems surface at the top in ascending order.
+fn status_rank(value: &Value) -> u8 {
+    match value.as_str() {
+        Some("failed") => 0,
+        Some("skipped") => 1,
+        Some("ready") => 2,
+        Some("succeeded") => 3,
+        _ => 4,
+    }
+}
@@ -0,0 +1,35 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+const WINDOW: usize = 10;
+
+/// Estimates remaining time from a rolling window of per-item durations.
+pub struct EtaTracker {
+    last: Instant,
+    samples: VecDeque<Duration>,
+}
+
+impl EtaTracker {
+    pub fn new() -> Self {
+        Self {
+            last: Instant::now(),
+            samples: VecDeque::with_capacity(WINDOW),
+        }
+    }
+
+    /// Record one finished item and return the ETA in seconds for `remaining` items.
+    /// Returns `None` until at least two items have been timed.
+    pub fn record(&mut self, remaining: usize) -> Option<u64> {
+        let now = Instant::now();
+        self.samples.push_back(now.duration_since(self.last));
+        self.last = now;
+        if self.samples.len() > WINDOW {
+            self.samples.pop_front();
+        }
+        if self.samples.len() < 2 {
+            return None;
+        }
+        let average = self.samples.iter().sum::<Duration>() / self.samples.len() as u32;
+        Some((average.as_secs_f64() * remaining as f64).ceil() as u64)
+    }
+}
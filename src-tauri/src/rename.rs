@@ -1,15 +1,22 @@
 use crate::error::AppError;
-use crate::file_collect::collect_rename_targets;
+use crate::file_collect::{collect_rename_targets, count_by_extension, depth_limit};
 use crate::fs_atomic::atomic_copy_replace;
 use crate::fs_atomic::atomic_move_replace;
+use crate::fs_atomic::dry_run_check;
+use crate::duplicate_detect::hash_file;
 use crate::model::{
-    CollisionPolicy, ExecuteStatus, OperationProgressEvent, PreviewStatus, RenameExecuteDetail,
-    RenameExecuteResponse, RenamePreviewItem, RenamePreviewRequest, RenamePreviewResponse,
-    RenameSource, RenameTemplateTag,
+    CollisionPolicy, ExecuteStatus, HashAlgorithm, OperationProgressEvent, OperationSummary,
+    PreviewStatus, RenameExecuteDetail, RenameExecuteResponse, RenamePreviewItem,
+    RenamePreviewRequest, RenamePreviewResponse, RenameSource, RenameTemplateTag, SequenceSortBy,
+    TemplateWarning, UnicodeNorm,
 };
-use crate::path_norm::relative_or_portable_absolute;
-use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
-use exif::{In, Reader, Tag, Value};
+use crate::path_norm::{
+    destination_key, is_reserved_windows_name, normalize_unicode, path_depth_below,
+    relative_or_portable_absolute, safe_canonicalize, to_windows_extended,
+};
+use crate::progress_eta::EtaTracker;
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone};
+use exif::{Context, In, Reader, Tag, Value};
 use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use regex::Regex;
@@ -28,8 +35,14 @@ struct PlannedRename {
     destination: Option<PathBuf>,
     status: PreviewStatus,
     reason: Option<String>,
+    anomalous_datetime: bool,
+    timestamp_source: Option<&'static str>,
 }
 
+/// Cameras with a dead clock battery commonly write this instead of a real date.
+const ZERO_EXIF_DATETIME: &str = "0000:00:00 00:00:00";
+const MIN_PLAUSIBLE_YEAR: i32 = 1990;
+
 static ISO_DATE_TIME_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
         r"(?i)\d{4}[-/]\d{2}[-/]\d{2}[T\s]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?",
@@ -68,11 +81,23 @@ pub fn template_tags() -> Vec<RenameTemplateTag> {
             label: "撮影日付".to_string(),
             description: "撮影日時の日付部分を挿入".to_string(),
         },
+        RenameTemplateTag {
+            token: "{capture_date:week}".to_string(),
+            label: "撮影週番号".to_string(),
+            description: "撮影日の ISO 週番号（01〜53）を挿入（exec_date でも可。year / month / day も指定可能）"
+                .to_string(),
+        },
         RenameTemplateTag {
             token: "{capture_time:HHmmss}".to_string(),
             label: "撮影時刻".to_string(),
             description: "撮影日時の時刻部分を挿入".to_string(),
         },
+        RenameTemplateTag {
+            token: "{capture_tz}".to_string(),
+            label: "撮影タイムゾーン".to_string(),
+            description: "EXIF の OffsetTimeOriginal を挿入（+09:00 は p0900。無い場合はこの PC のオフセット）"
+                .to_string(),
+        },
         RenameTemplateTag {
             token: "{exec_date:YYYYMMDD}".to_string(),
             label: "実行日付".to_string(),
@@ -86,7 +111,24 @@ pub fn template_tags() -> Vec<RenameTemplateTag> {
         RenameTemplateTag {
             token: "{seq:3}".to_string(),
             label: "通し番号".to_string(),
-            description: "ファイル順にゼロ埋め通し番号を挿入".to_string(),
+            description: "ファイル順にゼロ埋め通し番号を挿入（{seq:3:100} で開始番号を指定）".to_string(),
+        },
+        RenameTemplateTag {
+            token: "{seq_alpha:1}".to_string(),
+            label: "英字連番".to_string(),
+            description: "通し番号を英字で挿入（1→A、26→Z、27→AA。桁数に満たない分は 0 で埋める）"
+                .to_string(),
+        },
+        RenameTemplateTag {
+            token: "{parent_path:2}".to_string(),
+            label: "親フォルダ階層".to_string(),
+            description: "直近 N 階層の親フォルダ名を上位から順に _ で連結して挿入（例: 2024_06_Tokyo）".to_string(),
+        },
+        RenameTemplateTag {
+            token: "{subfolder_depth}".to_string(),
+            label: "フォルダ階層".to_string(),
+            description: "入力フォルダからの階層の深さを挿入（直下のファイルは 0、1階層下は 1）"
+                .to_string(),
         },
         RenameTemplateTag {
             token: "{original}".to_string(),
@@ -96,7 +138,18 @@ pub fn template_tags() -> Vec<RenameTemplateTag> {
         RenameTemplateTag {
             token: "{ext}".to_string(),
             label: "拡張子".to_string(),
-            description: "拡張子を挿入".to_string(),
+            description: "拡張子を小文字で挿入（大文字小文字を保持する設定では元の表記のまま）"
+                .to_string(),
+        },
+        RenameTemplateTag {
+            token: "{file_hash:md5}".to_string(),
+            label: "ファイルハッシュ".to_string(),
+            description: "ファイル内容のハッシュ値を挿入（md5 / sha256）".to_string(),
+        },
+        RenameTemplateTag {
+            token: "{camera_serial}".to_string(),
+            label: "カメラシリアル番号".to_string(),
+            description: "EXIF の BodySerialNumber を挿入（無い場合はスキップ）".to_string(),
         },
     ]
 }
@@ -110,9 +163,17 @@ where
 {
     let preview_timestamp = Local::now();
     let ffprobe_cache = prefetch_ffprobe_datetimes(request, &mut report_progress)?;
-    let plan = build_plan(request, Some(&preview_timestamp), &ffprobe_cache)?;
+    let (plan, warnings) = build_plan(request, Some(&preview_timestamp), &ffprobe_cache)?;
     let mut ready = 0usize;
     let mut skipped = 0usize;
+    let anomalous_datetime_count = plan.iter().filter(|item| item.anomalous_datetime).count();
+    let mut timestamp_source_counts: HashMap<String, usize> = HashMap::new();
+    for item in &plan {
+        *timestamp_source_counts
+            .entry(item.timestamp_source.unwrap_or("なし").to_string())
+            .or_default() += 1;
+    }
+    let sources: Vec<PathBuf> = plan.iter().map(|item| item.source.clone()).collect();
 
     let items = plan
         .iter()
@@ -137,7 +198,11 @@ where
         total: ready + skipped,
         ready,
         skipped,
+        anomalous_datetime_count,
+        timestamp_source_counts,
+        source_by_extension: count_by_extension(&sources),
         items,
+        warnings,
     })
 }
 
@@ -152,21 +217,40 @@ where
 {
     let execution_timestamp = Local::now();
     let ffprobe_cache = prefetch_ffprobe_datetimes(request, &mut report_progress)?;
-    let plan = build_plan(request, Some(&execution_timestamp), &ffprobe_cache)?;
+    let (plan, warnings) = build_plan(request, Some(&execution_timestamp), &ffprobe_cache)?;
+    if is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
     let total = plan.len();
     let copy_output = request.duplicate_output.unwrap_or(false) && request.output_dir.is_some();
+    let dry_run = request.dry_run.unwrap_or(false);
     let mut details = Vec::with_capacity(total);
     let mut succeeded = 0usize;
     let mut failed = 0usize;
     let mut skipped = 0usize;
     let mut processed = 0usize;
     let mut canceled = false;
+    let mut eta = EtaTracker::new();
+    // Sizes are read up front; rename moves the source away before progress is reported.
+    let source_sizes: HashMap<String, u64> = plan
+        .iter()
+        .filter(|item| matches!(item.status, PreviewStatus::Ready))
+        .map(|item| {
+            let size = fs::metadata(to_windows_extended(&item.source))
+                .map(|metadata| metadata.len())
+                .unwrap_or(0);
+            (item.source.to_string_lossy().to_string(), size)
+        })
+        .collect();
+    let total_bytes: u64 = source_sizes.values().sum();
+    let mut bytes_processed = 0u64;
+    let copy_fallbacks = Arc::new(AtomicUsize::new(0));
 
     // When moving into the output folder, a destination can overlap with a
     // source path. Parallel execution could then destroy a source file before
     // another worker reads it (e.g. 2.jpg→1.jpg and 3.jpg→2.jpg).
     // Fall back to sequential execution when this overlap is detected.
-    let needs_sequential = if copy_output {
+    let needs_sequential = if copy_output || dry_run {
         false
     } else {
         let source_keys: HashSet<String> = plan
@@ -185,10 +269,14 @@ where
             if !canceled && is_cancelled() {
                 canceled = true;
             }
-            let detail = execute_one_rename(item, canceled, copy_output);
+            let detail = execute_one_rename(item, canceled, copy_output, dry_run, &copy_fallbacks);
             processed += 1;
             match detail.status {
-                ExecuteStatus::Succeeded => succeeded += 1,
+                ExecuteStatus::Succeeded => {
+                    succeeded += 1;
+                    bytes_processed +=
+                        source_sizes.get(&detail.source_path).copied().unwrap_or(0);
+                }
                 ExecuteStatus::Failed => failed += 1,
                 ExecuteStatus::Skipped => skipped += 1,
             }
@@ -204,6 +292,9 @@ where
                 current_path,
                 done: false,
                 canceled,
+                eta_seconds: eta.record(total.saturating_sub(processed)),
+                bytes_processed,
+                total_bytes,
             });
         }
     } else {
@@ -211,13 +302,19 @@ where
         let worker_cancel = Arc::clone(&cancel_requested);
         let (tx, rx) = mpsc::channel::<RenameExecuteDetail>();
         let worker_plan = plan.clone();
+        let worker_copy_fallbacks = Arc::clone(&copy_fallbacks);
 
         let worker = std::thread::spawn(move || {
             worker_plan
                 .into_par_iter()
                 .for_each_with(tx, |sender, item| {
-                    let detail =
-                        execute_one_rename(&item, worker_cancel.load(Ordering::SeqCst), copy_output);
+                    let detail = execute_one_rename(
+                        &item,
+                        worker_cancel.load(Ordering::SeqCst),
+                        copy_output,
+                        dry_run,
+                        &worker_copy_fallbacks,
+                    );
                     let _ = sender.send(detail);
                 });
         });
@@ -232,7 +329,11 @@ where
                 Ok(detail) => {
                     processed += 1;
                     match detail.status {
-                        ExecuteStatus::Succeeded => succeeded += 1,
+                        ExecuteStatus::Succeeded => {
+                    succeeded += 1;
+                    bytes_processed +=
+                        source_sizes.get(&detail.source_path).copied().unwrap_or(0);
+                }
                         ExecuteStatus::Failed => failed += 1,
                         ExecuteStatus::Skipped => skipped += 1,
                     }
@@ -248,6 +349,9 @@ where
                         current_path,
                         done: false,
                         canceled,
+                        eta_seconds: eta.record(total.saturating_sub(processed)),
+                        bytes_processed,
+                        total_bytes,
                     });
                 }
                 Err(mpsc::RecvTimeoutError::Timeout) => continue,
@@ -268,18 +372,34 @@ where
         current_path: None,
         done: true,
         canceled,
+        eta_seconds: None,
+        bytes_processed,
+        total_bytes,
     });
 
     Ok(RenameExecuteResponse {
-        processed: succeeded + failed + skipped,
-        succeeded,
-        failed,
-        skipped,
+        summary: OperationSummary::new(
+            "rename",
+            succeeded + failed + skipped,
+            succeeded,
+            failed,
+            skipped,
+            None,
+            canceled,
+        )
+        .with_warnings(warnings),
+        copy_fallback_count: copy_fallbacks.load(Ordering::SeqCst),
         details,
     })
 }
 
-fn execute_one_rename(item: &PlannedRename, canceled: bool, copy_output: bool) -> RenameExecuteDetail {
+fn execute_one_rename(
+    item: &PlannedRename,
+    canceled: bool,
+    copy_output: bool,
+    dry_run: bool,
+    copy_fallbacks: &AtomicUsize,
+) -> RenameExecuteDetail {
     if canceled || matches!(item.status, PreviewStatus::Skipped) {
         return RenameExecuteDetail {
             source_path: item.source.to_string_lossy().to_string(),
@@ -314,6 +434,23 @@ fn execute_one_rename(item: &PlannedRename, canceled: bool, copy_output: bool) -
         };
     }
 
+    if dry_run {
+        return match dry_run_check(&item.source) {
+            Ok(note) => RenameExecuteDetail {
+                source_path: item.source.to_string_lossy().to_string(),
+                destination_path: Some(destination.to_string_lossy().to_string()),
+                status: ExecuteStatus::Succeeded,
+                reason: note,
+            },
+            Err(error) => RenameExecuteDetail {
+                source_path: item.source.to_string_lossy().to_string(),
+                destination_path: Some(destination.to_string_lossy().to_string()),
+                status: ExecuteStatus::Failed,
+                reason: Some(error),
+            },
+        };
+    }
+
     if let Some(parent) = destination.parent() {
         if let Err(error) = fs::create_dir_all(parent) {
             return RenameExecuteDetail {
@@ -329,7 +466,12 @@ fn execute_one_rename(item: &PlannedRename, canceled: bool, copy_output: bool) -
         atomic_copy_replace(&item.source, &destination)
             .map(|()| Some("元ファイルを残して複製しました".to_string()))
     } else {
-        atomic_move_replace(&item.source, &destination)
+        atomic_move_replace(&item.source, &destination).map(|note| {
+            if note.is_copy_fallback() {
+                copy_fallbacks.fetch_add(1, Ordering::SeqCst);
+            }
+            note.into_reason()
+        })
     };
 
     match result {
@@ -373,16 +515,40 @@ where
     if !request.use_ffprobe.unwrap_or(false) || !*FFPROBE_AVAILABLE {
         return Ok(HashMap::new());
     }
-    if !matches!(request.source, RenameSource::CaptureThenModified) {
+    let sorts_by_capture = matches!(
+        request.sequence_sort_by,
+        Some(SequenceSortBy::CaptureDateTime)
+    );
+    if !matches!(request.source, RenameSource::CaptureThenModified) && !sorts_by_capture {
         return Ok(HashMap::new());
     }
 
-    let collect = collect_rename_targets(&request.input_paths, request.include_subfolders)
-        .map_err(AppError::InvalidRequest)?;
+    let collect = collect_rename_targets(
+        &request.input_paths,
+        depth_limit(request.include_subfolders, request.max_depth),
+        request.max_files,
+    )
+    .map_err(AppError::InvalidRequest)?;
+
+    Ok(prefetch_ffprobe_for_files(&collect.files, "rename", report_progress))
+}
+
+/// Runs ffprobe in parallel for the video files in `files` and caches the results.
+pub fn prefetch_ffprobe_for_files<FProgress>(
+    files: &[PathBuf],
+    operation: &str,
+    report_progress: &mut FProgress,
+) -> HashMap<PathBuf, Option<DateTime<Local>>>
+where
+    FProgress: FnMut(OperationProgressEvent),
+{
+    if !*FFPROBE_AVAILABLE {
+        return HashMap::new();
+    }
 
-    let targets: Vec<PathBuf> = collect.files.iter().filter(|f| needs_ffprobe(f)).cloned().collect();
+    let targets: Vec<PathBuf> = files.iter().filter(|f| needs_ffprobe(f)).cloned().collect();
     if targets.is_empty() {
-        return Ok(HashMap::new());
+        return HashMap::new();
     }
 
     let total = targets.len();
@@ -390,7 +556,7 @@ where
 
     // Report start of ffprobe phase
     report_progress(OperationProgressEvent {
-        operation: "rename".to_string(),
+        operation: operation.to_string(),
         processed: 0,
         total,
         succeeded: 0,
@@ -399,6 +565,9 @@ where
         current_path: Some("メタデータ取得中...".to_string()),
         done: false,
         canceled: false,
+        eta_seconds: None,
+        bytes_processed: 0,
+        total_bytes: 0,
     });
 
     let worker_processed = Arc::clone(&processed);
@@ -423,7 +592,7 @@ where
                 cache.insert(path, dt);
                 count += 1;
                 report_progress(OperationProgressEvent {
-                    operation: "rename".to_string(),
+                    operation: operation.to_string(),
                     processed: count,
                     total,
                     succeeded: count,
@@ -432,6 +601,9 @@ where
                     current_path: Some("メタデータ取得中...".to_string()),
                     done: false,
                     canceled: false,
+                    eta_seconds: None,
+                    bytes_processed: 0,
+                    total_bytes: 0,
                 });
             }
             Err(mpsc::RecvTimeoutError::Timeout) => continue,
@@ -443,7 +615,7 @@ where
 
     // Clear progress so it doesn't linger
     report_progress(OperationProgressEvent {
-        operation: "rename".to_string(),
+        operation: operation.to_string(),
         processed: 0,
         total: 0,
         succeeded: 0,
@@ -452,23 +624,33 @@ where
         current_path: None,
         done: true,
         canceled: false,
+        eta_seconds: None,
+        bytes_processed: 0,
+        total_bytes: 0,
     });
 
-    Ok(cache)
+    cache
 }
 
 fn build_plan(
     request: &RenamePreviewRequest,
     execution_timestamp: Option<&DateTime<Local>>,
     ffprobe_cache: &HashMap<PathBuf, Option<DateTime<Local>>>,
-) -> Result<Vec<PlannedRename>, AppError> {
+) -> Result<(Vec<PlannedRename>, Vec<String>), AppError> {
     if request.template.trim().is_empty() {
         return Err(AppError::InvalidRequest(
             "テンプレートを入力してください。".to_string(),
         ));
     }
 
-    let collect = collect_rename_targets(&request.input_paths, request.include_subfolders)
+    let collect = collect_rename_targets(
+        &request.input_paths,
+        depth_limit(request.include_subfolders, request.max_depth),
+        request.max_files,
+    )
+    .map_err(AppError::InvalidRequest)?;
+    collect
+        .ensure_accessible(request.fail_on_permission_denied.unwrap_or(false))
         .map_err(AppError::InvalidRequest)?;
     if collect.files.is_empty() {
         let msg = if collect.skipped_by_extension > 0 {
@@ -491,28 +673,80 @@ fn build_plan(
         ));
     }
 
+    // `/` in the template separates subfolder components; only the file name may use {ext}.
+    if let Some((folder_part, _)) = request.template.rsplit_once('/') {
+        if folder_part.contains("{ext}") {
+            return Err(AppError::InvalidRequest(
+                "{ext} はフォルダ部分には使用できません".to_string(),
+            ));
+        }
+    }
+
     let output_dir = request.output_dir.as_ref().map(PathBuf::from);
     let template_uses_ext = request.template.contains("{ext}");
+    let preserve_extension_case = request.preserve_extension_case.unwrap_or(false);
     let requires_capture_datetime = requires_capture_datetime_placeholder(&request.template);
+    let warn_on_anomalous_datetime = request.warn_on_anomalous_datetime.unwrap_or(false);
+    let now = Local::now();
     let conflict_policy = request
         .conflict_policy
         .clone()
         .unwrap_or(CollisionPolicy::Sequence);
 
+    let warnings = collect.warnings();
+    let input_roots = selected_input_roots(&request.input_paths);
     let mut used_destination_keys: HashSet<String> = HashSet::new();
-    let mut planned = Vec::with_capacity(collect.files.len());
+    let files = sort_for_sequence(
+        collect.files,
+        request.sequence_sort_by.as_ref(),
+        ffprobe_cache,
+    );
+    let hash_algorithm =
+        template_hash_algorithm(&request.template).map_err(AppError::InvalidRequest)?;
+    let hash_cache: HashMap<PathBuf, String> = match &hash_algorithm {
+        Some(algorithm) => files
+            .par_iter()
+            .filter_map(|file| {
+                hash_file(file, algorithm)
+                    .ok()
+                    .map(|hash| (file.clone(), hash))
+            })
+            .collect(),
+        None => HashMap::new(),
+    };
+    let tz_cache: HashMap<PathBuf, String> = if template_uses_capture_tz(&request.template) {
+        files
+            .par_iter()
+            .filter_map(|file| read_exif_offset_time(file).map(|offset| (file.clone(), offset)))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    let serial_cache: HashMap<PathBuf, String> = if request.template.contains("{camera_serial}") {
+        files
+            .par_iter()
+            .filter_map(|file| {
+                read_exif_ifd_ascii_tag(file, TAG_BODY_SERIAL_NUMBER)
+                    .map(|serial| (file.clone(), serial))
+            })
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    let mut planned = Vec::with_capacity(files.len());
 
-    for (index, file) in collect.files.iter().enumerate() {
+    for (index, file) in files.iter().enumerate() {
         let original_stem = file
             .file_stem()
             .and_then(|name| name.to_str())
             .unwrap_or("file")
             .to_string();
-        let original_ext = file
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("")
-            .to_ascii_lowercase();
+        let raw_ext = file.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        let original_ext = if preserve_extension_case {
+            raw_ext.to_string()
+        } else {
+            raw_ext.to_ascii_lowercase()
+        };
 
         let timestamp_result =
             resolve_timestamp(file, &request.source, ffprobe_cache);
@@ -522,6 +756,8 @@ fn build_plan(
                 destination: None,
                 status: PreviewStatus::Skipped,
                 reason: Some("タイムスタンプを取得できません".to_string()),
+                anomalous_datetime: false,
+                timestamp_source: None,
             });
             continue;
         }
@@ -537,8 +773,13 @@ fn build_plan(
                 capture_timestamp: timestamp,
                 execution_timestamp,
                 sequence: index + 1,
+                source: file,
+                subfolder_depth: subfolder_depth(file, &input_roots),
                 original: &original_stem,
                 ext: &original_ext,
+                file_hash: hash_cache.get(file).map(String::as_str),
+                capture_tz: tz_cache.get(file).map(String::as_str),
+                camera_serial: serial_cache.get(file).map(String::as_str),
             },
         );
 
@@ -550,30 +791,64 @@ fn build_plan(
                     destination: None,
                     status: PreviewStatus::Skipped,
                     reason: Some(error),
+                    anomalous_datetime: false,
+                    timestamp_source,
                 });
                 continue;
             }
         };
 
-        let mut safe_name = sanitize_file_name(&rendered_name);
+        let (folder_part, name_part) = rendered_name
+            .rsplit_once('/')
+            .unwrap_or(("", rendered_name.as_str()));
+        let subfolders: Vec<String> = folder_part
+            .split('/')
+            .map(|component| sanitize_file_name(component, request.unicode_normalize.as_ref()))
+            .filter(|component| !component.is_empty())
+            .collect();
+        if subfolders.iter().any(|component| is_reserved_windows_name(component)) {
+            planned.push(PlannedRename {
+                source: file.clone(),
+                destination: None,
+                status: PreviewStatus::Skipped,
+                reason: Some("Windowsの予約ファイル名です".to_string()),
+                anomalous_datetime: false,
+                timestamp_source,
+            });
+            continue;
+        }
+
+        let mut safe_name = sanitize_file_name(name_part, request.unicode_normalize.as_ref());
         if safe_name.is_empty() {
-            safe_name = original_stem.clone();
+            safe_name = normalize_unicode(&original_stem, request.unicode_normalize.as_ref());
         }
         if !template_uses_ext && !original_ext.is_empty() {
             safe_name.push('.');
             safe_name.push_str(&original_ext);
         }
+        if is_reserved_windows_name(&safe_name) {
+            planned.push(PlannedRename {
+                source: file.clone(),
+                destination: None,
+                status: PreviewStatus::Skipped,
+                reason: Some("Windowsの予約ファイル名です".to_string()),
+                anomalous_datetime: false,
+                timestamp_source,
+            });
+            continue;
+        }
 
-        let base_destination = if let Some(out_dir) = output_dir.as_ref() {
+        let destination_dir = if let Some(out_dir) = output_dir.as_ref() {
             let relative = relative_or_portable_absolute(file, collect.input_root.as_deref());
             let relative_parent = relative.parent().map_or_else(PathBuf::new, PathBuf::from);
-            out_dir.join(relative_parent).join(safe_name)
+            out_dir.join(relative_parent)
         } else {
-            file.parent().map_or_else(
-                || PathBuf::from(&safe_name),
-                |parent| parent.join(&safe_name),
-            )
+            file.parent().map_or_else(PathBuf::new, PathBuf::from)
         };
+        let base_destination = subfolders
+            .iter()
+            .fold(destination_dir, |dir, component| dir.join(component))
+            .join(&safe_name);
 
         let (status, collision_reason, destination) = resolve_destination_for_policy(
             &base_destination,
@@ -591,12 +866,25 @@ fn build_plan(
         } else {
             collision_reason
         };
+        let anomaly = if warn_on_anomalous_datetime {
+            detect_datetime_anomaly(file, &request.source, timestamp, &now)
+        } else {
+            None
+        };
+        let anomalous_datetime = anomaly.is_some();
+        let reason = match (anomaly, reason) {
+            (Some(anomaly), Some(reason)) => Some(format!("異常な日時: {} / {}", anomaly, reason)),
+            (Some(anomaly), None) => Some(format!("異常な日時: {}", anomaly)),
+            (None, reason) => reason,
+        };
 
         planned.push(PlannedRename {
             source: file.clone(),
             destination: Some(destination),
             status,
             reason,
+            anomalous_datetime,
+            timestamp_source,
         });
     }
 
@@ -632,10 +920,43 @@ fn build_plan(
         }
     }
 
-    Ok(planned)
+    Ok((planned, warnings))
+}
+
+/// Orders files for `{seq}` numbering. Capture order uses capture time only, never the
+/// modified-time fallback. Files without a timestamp go last, keeping their file-name
+/// order (the sort is stable).
+fn sort_for_sequence(
+    files: Vec<PathBuf>,
+    sort_by: Option<&SequenceSortBy>,
+    ffprobe_cache: &HashMap<PathBuf, Option<DateTime<Local>>>,
+) -> Vec<PathBuf> {
+    let capture_only = match sort_by {
+        None | Some(SequenceSortBy::FileName) => return files,
+        Some(SequenceSortBy::CaptureDateTime) => true,
+        Some(SequenceSortBy::FileModified) => false,
+    };
+    let mut timestamped: Vec<(PathBuf, Option<DateTime<Local>>)> = files
+        .into_par_iter()
+        .map(|file| {
+            let timestamp = if capture_only {
+                read_capture_datetime(&file, ffprobe_cache).map(|(dt, _)| dt)
+            } else {
+                read_modified_datetime(&file)
+            };
+            (file, timestamp)
+        })
+        .collect();
+    timestamped.sort_by(|(_, a), (_, b)| match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    timestamped.into_iter().map(|(file, _)| file).collect()
 }
 
-fn resolve_timestamp(
+pub fn resolve_timestamp(
     path: &Path,
     source: &RenameSource,
     ffprobe_cache: &HashMap<PathBuf, Option<DateTime<Local>>>,
@@ -651,7 +972,7 @@ fn resolve_timestamp(
 }
 
 fn read_modified_datetime(path: &Path) -> Option<DateTime<Local>> {
-    let metadata = fs::metadata(path).ok()?;
+    let metadata = fs::metadata(to_windows_extended(path)).ok()?;
     let modified = metadata.modified().ok()?;
     Some(DateTime::<Local>::from(modified))
 }
@@ -711,7 +1032,11 @@ fn read_capture_datetime(
 }
 
 fn read_image_capture_datetime(path: &Path) -> Option<DateTime<Local>> {
-    let file = fs::File::open(path).ok()?;
+    parse_exif_datetime(&read_image_exif_datetime_raw(path)?)
+}
+
+fn read_image_exif_datetime_raw(path: &Path) -> Option<String> {
+    let file = fs::File::open(to_windows_extended(path)).ok()?;
     let mut reader = BufReader::new(file);
     let exif = Reader::new().read_from_container(&mut reader).ok()?;
     let field = exif
@@ -728,7 +1053,61 @@ fn read_image_capture_datetime(path: &Path) -> Option<DateTime<Local>> {
     .unwrap_or_else(|| {
         field.display_value().with_unit(&exif).to_string()
     });
-    parse_exif_datetime(&date_value)
+    Some(date_value)
+}
+
+const TAG_OFFSET_TIME_ORIGINAL: u16 = 0x9011;
+const TAG_BODY_SERIAL_NUMBER: u16 = 0xA431;
+
+fn read_exif_offset_time(path: &Path) -> Option<String> {
+    read_exif_ifd_ascii_tag(path, TAG_OFFSET_TIME_ORIGINAL)
+}
+
+/// ASCII value of `tag` from the primary image's Exif IFD, trimmed of NULs and spaces.
+fn read_exif_ifd_ascii_tag(path: &Path, tag: u16) -> Option<String> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    if !IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+    let file = fs::File::open(to_windows_extended(path)).ok()?;
+    let mut reader = BufReader::new(file);
+    let exif = Reader::new().read_from_container(&mut reader).ok()?;
+    let field = exif.get_field(Tag(Context::Exif, tag), In::PRIMARY)?;
+    match &field.value {
+        Value::Ascii(vec) if !vec.is_empty() => String::from_utf8(vec[0].clone())
+            .ok()
+            .map(|value| value.trim_end_matches('\0').trim().to_string())
+            .filter(|value| !value.is_empty()),
+        _ => None,
+    }
+}
+
+/// Flags timestamps that most likely come from a camera with an unset clock.
+fn detect_datetime_anomaly(
+    path: &Path,
+    source: &RenameSource,
+    timestamp: Option<&DateTime<Local>>,
+    now: &DateTime<Local>,
+) -> Option<String> {
+    if matches!(source, RenameSource::CurrentTime) {
+        return None;
+    }
+    // An all-zero EXIF value fails to parse, so the timestamp itself may be a fallback.
+    let is_image = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+    if is_image && matches!(source, RenameSource::CaptureThenModified) {
+        let raw = read_image_exif_datetime_raw(path);
+        if raw.as_deref().map(|value| value.trim().trim_matches('"')) == Some(ZERO_EXIF_DATETIME) {
+            return Some(ZERO_EXIF_DATETIME.to_string());
+        }
+    }
+    let timestamp = timestamp?;
+    if timestamp.year() < MIN_PLAUSIBLE_YEAR || *timestamp > *now + chrono::Duration::days(365) {
+        return Some(timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+    }
+    None
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -738,7 +1117,7 @@ struct AtomRange {
 }
 
 fn read_iso_bmff_creation_datetime(path: &Path) -> Option<DateTime<Local>> {
-    let mut file = fs::File::open(path).ok()?;
+    let mut file = fs::File::open(to_windows_extended(path)).ok()?;
     let file_len = file.metadata().ok()?.len();
     let moov = find_atom(&mut file, 0, file_len, *b"moov")?;
     let mvhd = find_atom(&mut file, moov.data_start, moov.data_end, *b"mvhd")?;
@@ -842,7 +1221,7 @@ fn read_mxf_sidecar_datetime(path: &Path) -> Option<DateTime<Local>> {
         if !candidate.exists() || !candidate.is_file() {
             continue;
         }
-        if let Ok(text) = fs::read_to_string(candidate) {
+        if let Ok(text) = fs::read_to_string(to_windows_extended(&candidate)) {
             if let Some(dt) = parse_datetime_from_xml_text(&text) {
                 return Some(dt);
             }
@@ -968,12 +1347,79 @@ fn requires_capture_datetime_placeholder(template: &str) -> bool {
     template.contains("{capture_date") || template.contains("{capture_time")
 }
 
+/// Bijective base-26: 1→A, 26→Z, 27→AA, 53→BA. Left-padded with `A` up to `width`.
+fn seq_to_alpha(n: usize, width: usize) -> String {
+    let mut letters = Vec::new();
+    let mut rest = n;
+    while rest > 0 {
+        rest -= 1;
+        letters.push(b'A' + (rest % 26) as u8);
+        rest /= 26;
+    }
+    // 'A' is digit value 1, so padding with it would make 1 and 27 both "AA" at width 2.
+    // '0' is not a base-26 digit and sorts before every letter.
+    while letters.len() < width {
+        letters.push(b'0');
+    }
+    letters.iter().rev().map(|&b| b as char).collect()
+}
+
+/// `start - 1 + sequence`, rejected instead of wrapping when the start is 0 or too large.
+fn offset_sequence(start: usize, sequence: usize, token: &str) -> Result<usize, String> {
+    start
+        .checked_sub(1)
+        .and_then(|base| base.checked_add(sequence))
+        .ok_or_else(|| format!("{} の開始番号が範囲外です", token))
+}
+
 struct TemplateContext<'a> {
     capture_timestamp: Option<&'a DateTime<Local>>,
     execution_timestamp: Option<&'a DateTime<Local>>,
     sequence: usize,
+    source: &'a Path,
+    subfolder_depth: usize,
     original: &'a str,
     ext: &'a str,
+    file_hash: Option<&'a str>,
+    capture_tz: Option<&'a str>,
+    camera_serial: Option<&'a str>,
+}
+
+/// Placeholder keys that make each rendered name distinct.
+const UNIQUENESS_KEYS: &[&str] = &["original", "seq", "seq_alpha", "file_hash"];
+
+/// Keys of every `{...}` placeholder in the template, in order.
+fn template_keys(template: &str) -> Result<Vec<String>, String> {
+    let mut keys = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| "テンプレートに閉じられていない `{` があります".to_string())?;
+        let token = &rest[start + 1..start + end];
+        keys.push(token.split(':').next().unwrap_or(token).to_string());
+        rest = &rest[start + end + 1..];
+    }
+    Ok(keys)
+}
+
+/// Lints a template for names that are likely to collide across files.
+pub fn template_warnings(template: &str) -> Result<Vec<TemplateWarning>, String> {
+    let keys = template_keys(template)?;
+    let mut warnings = Vec::new();
+    if keys.is_empty() {
+        warnings.push(TemplateWarning {
+            code: "static_template".to_string(),
+            message: "プレースホルダーがないため、すべてのファイルが同じ名前になります".to_string(),
+        });
+    } else if !keys.iter().any(|key| UNIQUENESS_KEYS.contains(&key.as_str())) {
+        warnings.push(TemplateWarning {
+            code: "low_uniqueness".to_string(),
+            message: "{original} / {seq} / {file_hash} がないため、名前が重複しやすくなります"
+                .to_string(),
+        });
+    }
+    Ok(warnings)
 }
 
 fn render_template(template: &str, context: TemplateContext<'_>) -> Result<String, String> {
@@ -1018,6 +1464,8 @@ fn resolve_token(token: &str, context: &TemplateContext<'_>) -> Result<String, S
             let format = convert_datetime_format(arg.unwrap_or("YYYYMMDD"));
             Ok(timestamp.format(&format).to_string())
         }
+        "capture_time" if arg == Some("timezone") => Ok(format_capture_tz(context.capture_tz)),
+        "capture_tz" => Ok(format_capture_tz(context.capture_tz)),
         "capture_time" => {
             let timestamp = context
                 .capture_timestamp
@@ -1040,38 +1488,194 @@ fn resolve_token(token: &str, context: &TemplateContext<'_>) -> Result<String, S
             Ok(timestamp.format(&format).to_string())
         }
         "seq" => {
-            let digits = arg.unwrap_or("1");
+            // `{seq:digits}` or `{seq:digits:start}`
+            let spec = arg.unwrap_or("1");
+            let (digits, start) = spec
+                .split_once(':')
+                .map_or((spec, None), |(d, s)| (d, Some(s)));
             let digits: usize = digits
                 .parse()
                 .map_err(|_| "seq の桁数は正の整数で指定してください".to_string())?;
             if digits == 0 {
                 return Err("seq の桁数は1以上にしてください".to_string());
             }
-            Ok(format!("{:0width$}", context.sequence, width = digits))
+            let start: usize = match start {
+                Some(value) => value
+                    .parse()
+                    .map_err(|_| "seq の開始番号は正の整数で指定してください".to_string())?,
+                None => 1,
+            };
+            if start == 0 {
+                return Err("seq の開始番号は1以上にしてください".to_string());
+            }
+            Ok(format!(
+                "{:0width$}",
+                offset_sequence(start, context.sequence, "seq")?,
+                width = digits
+            ))
+        }
+        "seq_alpha" => {
+            // `{seq_alpha:width}` or `{seq_alpha:width:start}`, mirroring `{seq}`
+            let spec = arg.unwrap_or("1");
+            let (width, start) = spec
+                .split_once(':')
+                .map_or((spec, None), |(w, s)| (w, Some(s)));
+            let width: usize = width
+                .parse()
+                .map_err(|_| "seq_alpha の桁数は正の整数で指定してください".to_string())?;
+            let start: usize = match start {
+                Some(value) => value
+                    .parse()
+                    .map_err(|_| "seq_alpha の開始番号は正の整数で指定してください".to_string())?,
+                None => 1,
+            };
+            if start == 0 {
+                return Err("seq_alpha の開始番号は1以上にしてください".to_string());
+            }
+            Ok(seq_to_alpha(
+                offset_sequence(start, context.sequence, "seq_alpha")?,
+                width,
+            ))
+        }
+        "parent_path" => {
+            let levels: usize = arg
+                .unwrap_or("1")
+                .parse()
+                .map_err(|_| "parent_path の階層数は正の整数で指定してください".to_string())?;
+            if levels == 0 {
+                return Err("parent_path の階層数は1以上にしてください".to_string());
+            }
+            Ok(parent_path_components(context.source, levels))
         }
+        "subfolder_depth" if arg.is_none() => Ok(context.subfolder_depth.to_string()),
         "original" => Ok(context.original.to_string()),
         "ext" => Ok(context.ext.to_string()),
+        "file_hash" => {
+            parse_hash_algorithm(arg)?;
+            context
+                .file_hash
+                .map(str::to_string)
+                .ok_or_else(|| "ファイルハッシュを計算できません".to_string())
+        }
+        "camera_serial" => context
+            .camera_serial
+            .map(str::to_string)
+            .ok_or_else(|| "カメラのシリアル番号を取得できません".to_string()),
         _ => Err(format!("未対応のプレースホルダー: {{{}}}", token)),
     }
 }
 
-fn convert_datetime_format(value: &str) -> String {
-    value
-        .replace("YYYY", "%Y")
-        .replace("MM", "%m")
-        .replace("DD", "%d")
-        .replace("HH", "%H")
-        .replace("mm", "%M")
-        .replace("ss", "%S")
+/// Last `levels` parent folder names, outermost first, joined with `_`. Uses every
+/// available level when the path is shallower.
+fn parent_path_components(path: &Path, levels: usize) -> String {
+    let mut names: Vec<String> = path
+        .ancestors()
+        .skip(1)
+        .filter_map(|ancestor| ancestor.file_name().and_then(|name| name.to_str()))
+        .take(levels)
+        .map(|name| sanitize_file_name(name, None))
+        .collect();
+    names.reverse();
+    names.join("_")
+}
+
+/// Folder levels between `root` and the file; 0 when it sits directly in `root`.
+/// Folders the user picked, canonicalized like the collected files. A file input counts
+/// as its parent folder. `collect.input_root` is not used: it is the common parent of the
+/// files, which sits deeper when every file shares one subfolder.
+fn selected_input_roots(input_paths: &[String]) -> Vec<PathBuf> {
+    input_paths
+        .iter()
+        .filter_map(|raw| safe_canonicalize(Path::new(raw.trim())).ok())
+        .filter_map(|path| {
+            if path.is_dir() {
+                Some(path)
+            } else {
+                path.parent().map(Path::to_path_buf)
+            }
+        })
+        .collect()
+}
+
+/// Levels between the file's folder and the nearest selected input folder containing it.
+fn subfolder_depth(path: &Path, roots: &[PathBuf]) -> usize {
+    path.parent()
+        .and_then(|parent| {
+            roots
+                .iter()
+                .filter_map(|root| path_depth_below(parent, root))
+                .min()
+        })
+        .unwrap_or(0)
+}
+
+/// `+09:00` becomes `p0900`; falls back to the local offset when EXIF has none.
+fn format_capture_tz(offset: Option<&str>) -> String {
+    let offset = offset
+        .map(str::to_string)
+        .unwrap_or_else(|| Local::now().offset().to_string());
+    offset.trim().replace('+', "p").replace(':', "")
+}
+
+fn template_uses_capture_tz(template: &str) -> bool {
+    template.contains("{capture_tz}") || template.contains("{capture_time:timezone}")
+}
+
+fn parse_hash_algorithm(arg: Option<&str>) -> Result<HashAlgorithm, String> {
+    match arg.map(|value| value.trim().to_ascii_lowercase()).as_deref() {
+        None | Some("md5") => Ok(HashAlgorithm::Md5),
+        Some("sha256") => Ok(HashAlgorithm::Sha256),
+        Some(other) => Err(format!("未対応のハッシュ形式です: {}", other)),
+    }
+}
+
+/// Algorithm used by the `{file_hash}` tokens of a template, or `None` when absent.
+fn template_hash_algorithm(template: &str) -> Result<Option<HashAlgorithm>, String> {
+    let mut found: Option<HashAlgorithm> = None;
+    for (start, _) in template.match_indices("{file_hash") {
+        let token = template[start + 1..]
+            .split('}')
+            .next()
+            .unwrap_or_default();
+        let arg = token.strip_prefix("file_hash").and_then(|rest| rest.strip_prefix(':'));
+        let algorithm = parse_hash_algorithm(arg)?;
+        if let Some(existing) = &found {
+            if std::mem::discriminant(existing) != std::mem::discriminant(&algorithm) {
+                return Err(
+                    "テンプレート内の {file_hash} の形式は統一してください".to_string(),
+                );
+            }
+        }
+        found = Some(algorithm);
+    }
+    Ok(found)
 }
 
-fn sanitize_file_name(value: &str) -> String {
+/// Named keywords (`week`, `year`, `month`, `day`) map directly; anything else is
+/// treated as a `YYYYMMDD`-style pattern.
+pub fn convert_datetime_format(value: &str) -> String {
+    match value {
+        "week" => "%V".to_string(),
+        "year" => "%Y".to_string(),
+        "month" => "%m".to_string(),
+        "day" => "%d".to_string(),
+        _ => value
+            .replace("YYYY", "%Y")
+            .replace("MM", "%m")
+            .replace("DD", "%d")
+            .replace("HH", "%H")
+            .replace("mm", "%M")
+            .replace("ss", "%S"),
+    }
+}
+
+pub(crate) fn sanitize_file_name(value: &str, unicode_normalize: Option<&UnicodeNorm>) -> String {
     let invalid_chars = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
     let sanitized: String = value
         .chars()
         .map(|ch| if invalid_chars.contains(&ch) { '_' } else { ch })
         .collect();
-    sanitized.trim().trim_matches('.').to_string()
+    normalize_unicode(sanitized.trim().trim_matches('.'), unicode_normalize)
 }
 
 fn uniquify_destination(base: &Path, source: &Path, used_keys: &mut HashSet<String>) -> PathBuf {
@@ -1105,10 +1709,6 @@ fn uniquify_destination(base: &Path, source: &Path, used_keys: &mut HashSet<Stri
     }
 }
 
-fn destination_key(path: &Path) -> String {
-    path.to_string_lossy().to_ascii_lowercase()
-}
-
 fn resolve_destination_for_policy(
     base: &Path,
     source: &Path,
@@ -1142,7 +1742,7 @@ fn resolve_destination_for_policy(
                 (PreviewStatus::Ready, None, base.to_path_buf())
             }
         }
-        CollisionPolicy::Sequence => {
+        CollisionPolicy::Sequence | CollisionPolicy::PrefixParent => {
             let unique = uniquify_destination(base, source, used_keys);
             (
                 PreviewStatus::Ready,
@@ -1156,3 +1756,99 @@ fn resolve_destination_for_policy(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seq_to_alpha_matches_request_examples() {
+        assert_eq!(seq_to_alpha(1, 1), "A");
+        assert_eq!(seq_to_alpha(26, 1), "Z");
+        assert_eq!(seq_to_alpha(27, 1), "AA");
+        assert_eq!(seq_to_alpha(52, 1), "AZ");
+        assert_eq!(seq_to_alpha(53, 1), "BA");
+    }
+
+    #[test]
+    fn seq_to_alpha_padding_keeps_names_distinct() {
+        assert_eq!(seq_to_alpha(1, 2), "0A");
+        assert_eq!(seq_to_alpha(27, 2), "AA");
+        assert_ne!(seq_to_alpha(1, 2), seq_to_alpha(27, 2));
+    }
+
+    #[test]
+    fn offset_sequence_rejects_out_of_range_start() {
+        assert_eq!(offset_sequence(100, 1, "seq"), Ok(100));
+        assert!(offset_sequence(0, 1, "seq").is_err());
+        assert!(offset_sequence(usize::MAX, 2, "seq").is_err());
+    }
+
+    #[test]
+    fn subfolder_depth_counts_from_selected_folder() {
+        let root = std::env::temp_dir().join(format!("cfm-subfolder-depth-{}", std::process::id()));
+        let nested = root.join("a");
+        fs::create_dir_all(&nested).unwrap();
+        for name in ["x.jpg", "y.jpg"] {
+            fs::write(nested.join(name), b"").unwrap();
+        }
+        let input_paths = vec![root.to_string_lossy().to_string()];
+        let collect = collect_rename_targets(&input_paths, None, None).unwrap();
+        let roots = selected_input_roots(&input_paths);
+        let depths: Vec<usize> = collect
+            .files
+            .iter()
+            .map(|file| subfolder_depth(file, &roots))
+            .collect();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(depths, vec![1, 1]);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn renames_file_beyond_max_path() {
+        let root = std::env::temp_dir().join(format!("cfm-long-path-{}", std::process::id()));
+        let mut dir = root.clone();
+        while dir.to_string_lossy().len() < 270 {
+            dir.push("a".repeat(40));
+        }
+        fs::create_dir_all(to_windows_extended(&dir)).unwrap();
+        let source = dir.join("photo.jpg");
+        fs::write(to_windows_extended(&source), b"not really a jpeg").unwrap();
+        assert!(source.to_string_lossy().len() > 270);
+
+        let request = RenamePreviewRequest {
+            input_paths: vec![dir.to_string_lossy().to_string()],
+            include_subfolders: false,
+            max_depth: None,
+            max_files: None,
+            template: "renamed_{exec_date}".to_string(),
+            source: RenameSource::CurrentTime,
+            output_dir: None,
+            duplicate_output: None,
+            conflict_policy: None,
+            use_ffprobe: None,
+            unicode_normalize: None,
+            dry_run: None,
+            sequence_sort_by: None,
+            preserve_extension_case: None,
+            warn_on_anomalous_datetime: None,
+            fail_on_permission_denied: None,
+        };
+        let response = execute(&request, || false, |_| {}).unwrap();
+        fs::remove_dir_all(to_windows_extended(&root)).unwrap();
+
+        assert_eq!(response.details.len(), 1);
+        let detail = &response.details[0];
+        assert!(
+            matches!(detail.status, ExecuteStatus::Succeeded),
+            "{:?}",
+            detail.reason
+        );
+        let destination = detail.destination_path.as_deref().unwrap();
+        assert!(Path::new(destination)
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("renamed_")));
+    }
+}
@@ -0,0 +1,532 @@
+use crate::error::AppError;
+use crate::file_collect::{collect_targets_with_extensions, JPEG_ALLOWED_EXTENSIONS};
+use crate::fs_atomic::atomic_write_replace;
+use crate::model::{
+    CollisionPolicy, ExecuteStatus, OperationProgressEvent, OperationSummary, PreviewStatus,
+    ReorientExecuteDetail, ReorientExecuteResponse, ReorientPreviewItem, ReorientPreviewRequest,
+    ReorientPreviewResponse,
+};
+use crate::path_norm::{destination_key, relative_or_portable_absolute, to_windows_extended};
+use exif::{In, Reader, Tag};
+use image::codecs::jpeg::JpegEncoder;
+use image::metadata::Orientation;
+use image::ImageReader;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufReader, Cursor};
+use std::path::{Path, PathBuf};
+
+/// Default re-encode quality; kept high because the whole image is recompressed.
+const REORIENT_JPEG_QUALITY: u8 = 95;
+const LOSSLESS_FALLBACK_NOTE: &str = "可逆回転に未対応のため再エンコードしました";
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_EXIF_IFD_POINTER: u16 = 0x8769;
+const TAG_PIXEL_X_DIMENSION: u16 = 0xA002;
+const TAG_PIXEL_Y_DIMENSION: u16 = 0xA003;
+
+#[derive(Debug, Clone)]
+struct PlannedReorient {
+    source: PathBuf,
+    destination: PathBuf,
+    orientation: Option<u16>,
+    status: PreviewStatus,
+    reason: Option<String>,
+}
+
+pub fn preview(request: &ReorientPreviewRequest) -> Result<ReorientPreviewResponse, AppError> {
+    let (plan, warnings) = build_plan(request)?;
+    let ready = plan
+        .iter()
+        .filter(|item| matches!(item.status, PreviewStatus::Ready))
+        .count();
+    let items = plan
+        .into_iter()
+        .map(|item| ReorientPreviewItem {
+            source_path: item.source.to_string_lossy().to_string(),
+            destination_path: item.destination.to_string_lossy().to_string(),
+            orientation: item.orientation,
+            status: item.status,
+            reason: item.reason,
+        })
+        .collect::<Vec<_>>();
+    Ok(ReorientPreviewResponse {
+        total: items.len(),
+        ready,
+        skipped: items.len() - ready,
+        items,
+        warnings,
+    })
+}
+
+pub fn execute<FCancel, FProgress>(
+    request: &ReorientPreviewRequest,
+    is_cancelled: FCancel,
+    mut report_progress: FProgress,
+) -> Result<ReorientExecuteResponse, AppError>
+where
+    FCancel: Fn() -> bool,
+    FProgress: FnMut(OperationProgressEvent),
+{
+    let (plan, warnings) = build_plan(request)?;
+    if is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
+    let quality = request
+        .quality
+        .unwrap_or(REORIENT_JPEG_QUALITY)
+        .clamp(1, 100);
+    let lossless_requested = request.lossless_if_possible.unwrap_or(false);
+
+    let total = plan.len();
+    let mut details = Vec::with_capacity(total);
+    let mut succeeded = 0usize;
+    let mut failed = 0usize;
+    let mut skipped = 0usize;
+    let mut processed = 0usize;
+    let mut canceled = false;
+
+    for item in plan {
+        if !canceled && is_cancelled() {
+            canceled = true;
+        }
+
+        let source_path = item.source.to_string_lossy().to_string();
+        let destination_path = item.destination.to_string_lossy().to_string();
+        let orientation = item.orientation.unwrap_or(1);
+        let applied_transform = transform_name(orientation).to_string();
+
+        let (status, reason) = if canceled {
+            skipped += 1;
+            (
+                ExecuteStatus::Skipped,
+                Some("キャンセルされました".to_string()),
+            )
+        } else if !matches!(item.status, PreviewStatus::Ready) {
+            skipped += 1;
+            (ExecuteStatus::Skipped, item.reason)
+        } else {
+            match reorient_file(&item.source, &item.destination, orientation, quality) {
+                Ok(()) => {
+                    succeeded += 1;
+                    // No DCT-domain transform is bundled, so cardinal rotations fall back too.
+                    let reason = if lossless_requested {
+                        Some(match item.reason {
+                            Some(reason) => format!("{} / {}", reason, LOSSLESS_FALLBACK_NOTE),
+                            None => LOSSLESS_FALLBACK_NOTE.to_string(),
+                        })
+                    } else {
+                        item.reason
+                    };
+                    (ExecuteStatus::Succeeded, reason)
+                }
+                Err(e) => {
+                    failed += 1;
+                    (ExecuteStatus::Failed, Some(e))
+                }
+            }
+        };
+
+        processed += 1;
+        details.push(ReorientExecuteDetail {
+            source_path: source_path.clone(),
+            destination_path,
+            original_orientation: orientation,
+            applied_transform,
+            status,
+            reason,
+            lossless: false,
+            pixels_trimmed: None,
+        });
+        report_progress(OperationProgressEvent {
+            operation: "reorient".to_string(),
+            processed,
+            total,
+            succeeded,
+            failed,
+            skipped,
+            current_path: Some(source_path),
+            done: false,
+            canceled,
+            eta_seconds: None,
+            bytes_processed: 0,
+            total_bytes: 0,
+        });
+    }
+
+    report_progress(OperationProgressEvent {
+        operation: "reorient".to_string(),
+        processed,
+        total,
+        succeeded,
+        failed,
+        skipped,
+        current_path: None,
+        done: true,
+        canceled,
+        eta_seconds: None,
+        bytes_processed: 0,
+        total_bytes: 0,
+    });
+
+    Ok(ReorientExecuteResponse {
+        summary: OperationSummary::new(
+            "reorient",
+            processed,
+            succeeded,
+            failed,
+            skipped,
+            None,
+            canceled,
+        )
+        .with_warnings(warnings),
+        details,
+    })
+}
+
+fn build_plan(
+    request: &ReorientPreviewRequest,
+) -> Result<(Vec<PlannedReorient>, Vec<String>), AppError> {
+    let collect = collect_targets_with_extensions(
+        &request.input_paths,
+        request.include_subfolders,
+        JPEG_ALLOWED_EXTENSIONS,
+        request.max_files,
+    )
+    .map_err(AppError::InvalidRequest)?;
+    if collect.files.is_empty() {
+        return Err(AppError::InvalidRequest(
+            "対象ファイルが見つかりません。".to_string(),
+        ));
+    }
+
+    let output_dir = request
+        .output_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+    let mut used_destinations: HashSet<String> = HashSet::new();
+    let mut plan = Vec::with_capacity(collect.files.len());
+
+    for source in &collect.files {
+        let orientation = read_orientation(source);
+        // 0 is "undefined" in the spec, so it is reported like any other unknown value.
+        let needs_reorient = matches!(orientation, Some(2..=8));
+        let base_destination = match &output_dir {
+            Some(dir) => dir.join(relative_or_portable_absolute(
+                source,
+                collect.input_root.as_deref(),
+            )),
+            None => source.clone(),
+        };
+
+        if !needs_reorient {
+            plan.push(PlannedReorient {
+                source: source.clone(),
+                destination: base_destination,
+                orientation,
+                status: PreviewStatus::Skipped,
+                reason: Some(match orientation {
+                    Some(1) => "回転補正は不要です".to_string(),
+                    Some(_) => "不明な回転情報です".to_string(),
+                    None => "EXIF回転情報がありません".to_string(),
+                }),
+            });
+            continue;
+        }
+
+        let (status, reason, destination) = if output_dir.is_some() {
+            resolve_destination_for_policy(
+                &base_destination,
+                &mut used_destinations,
+                &request.conflict_policy,
+            )
+        } else {
+            (PreviewStatus::Ready, None, base_destination)
+        };
+        plan.push(PlannedReorient {
+            source: source.clone(),
+            destination,
+            orientation,
+            status,
+            reason,
+        });
+    }
+
+    Ok((plan, collect.warnings()))
+}
+
+pub(crate) fn read_orientation(path: &Path) -> Option<u16> {
+    let file = fs::File::open(to_windows_extended(path)).ok()?;
+    let mut reader = BufReader::new(file);
+    let exif = Reader::new().read_from_container(&mut reader).ok()?;
+    let field = exif.get_field(Tag::Orientation, In::PRIMARY)?;
+    field.value.get_uint(0).map(|value| value as u16)
+}
+
+fn transform_name(orientation: u16) -> &'static str {
+    match orientation {
+        2 => "flipHorizontal",
+        3 => "rotate180",
+        4 => "flipVertical",
+        5 => "transpose",
+        6 => "rotate90",
+        7 => "transverse",
+        8 => "rotate270",
+        _ => "none",
+    }
+}
+
+/// Decode, apply the EXIF orientation to the pixels and re-encode.
+/// The original APP1–APP15 segments (Exif, XMP, ICC, ...) are carried over; the Exif
+/// block gets Orientation 1, the new pixel dimensions and no IFD1 thumbnail.
+fn reorient_file(
+    source: &Path,
+    destination: &Path,
+    orientation: u16,
+    quality: u8,
+) -> Result<(), String> {
+    let original = fs::read(to_windows_extended(source))
+        .map_err(|e| format!("ファイルの読み込みに失敗しました: {}", e))?;
+    let transform = Orientation::from_exif(orientation as u8)
+        .ok_or_else(|| "不明な回転情報です".to_string())?;
+
+    let mut image = ImageReader::new(Cursor::new(&original))
+        .with_guessed_format()
+        .map_err(|e| format!("画像ファイルを開けません: {}", e))?
+        .decode()
+        .map_err(|e| format!("画像のデコードに失敗しました: {}", e))?;
+    image.apply_orientation(transform);
+
+    let mut encoded = Vec::new();
+    JpegEncoder::new_with_quality(&mut encoded, quality)
+        .encode_image(&image)
+        .map_err(|e| format!("JPEGエンコードに失敗しました: {}", e))?;
+
+    let mut app_segments = extract_app_segments(&original);
+    for segment in &mut app_segments {
+        if segment.len() >= 10 && segment[1] == 0xE1 && &segment[4..10] == b"Exif\0\0" {
+            update_reoriented_exif(segment, image.width(), image.height());
+        }
+    }
+    let output = inject_app_segments(&encoded, &app_segments);
+
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(to_windows_extended(parent))
+            .map_err(|e| format!("出力先フォルダの作成に失敗しました: {}", e))?;
+    }
+    atomic_write_replace(destination, &output).map_err(|e| e.to_string())
+}
+
+/// APP1–APP15 segments in file order; APP0 comes from the encoder.
+fn extract_app_segments(bytes: &[u8]) -> Vec<Vec<u8>> {
+    if bytes.len() < 4 || bytes[0] != 0xFF || bytes[1] != 0xD8 {
+        return Vec::new();
+    }
+    let mut result = Vec::new();
+    let mut i = 2usize;
+    while i + 4 <= bytes.len() {
+        if bytes[i] != 0xFF {
+            break;
+        }
+        let marker = bytes[i + 1];
+        if marker == 0xDA || marker == 0xD9 {
+            break;
+        }
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            i += 2;
+            continue;
+        }
+        let len = u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]) as usize;
+        if len < 2 || i + 2 + len > bytes.len() {
+            break;
+        }
+        if (0xE1..=0xEF).contains(&marker) {
+            result.push(bytes[i..i + 2 + len].to_vec());
+        }
+        i += 2 + len;
+    }
+    result
+}
+
+fn inject_app_segments(encoded: &[u8], app_segments: &[Vec<u8>]) -> Vec<u8> {
+    if app_segments.is_empty() || encoded.len() < 2 || encoded[0] != 0xFF || encoded[1] != 0xD8 {
+        return encoded.to_vec();
+    }
+    let mut out =
+        Vec::with_capacity(encoded.len() + app_segments.iter().map(Vec::len).sum::<usize>());
+    out.extend_from_slice(&encoded[0..2]);
+    for segment in app_segments {
+        out.extend_from_slice(segment);
+    }
+    out.extend_from_slice(&encoded[2..]);
+    out
+}
+
+/// Rewrites an APP1 Exif segment in place: IFD0 Orientation becomes 1, the Exif IFD's
+/// PixelXDimension/PixelYDimension get the rotated size and the IFD1 thumbnail is
+/// unlinked, since it still shows the old orientation.
+fn update_reoriented_exif(segment: &mut [u8], width: u32, height: u32) {
+    // marker (2) + length (2) + "Exif\0\0" (6)
+    let tiff_start = 10usize;
+    if segment.len() < tiff_start + 8 {
+        return;
+    }
+    let little_endian = match &segment[tiff_start..tiff_start + 2] {
+        b"II" => true,
+        b"MM" => false,
+        _ => return,
+    };
+    let read_u16 = |data: &[u8], at: usize| {
+        let bytes = [data[at], data[at + 1]];
+        if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        }
+    };
+    let read_u32 = |data: &[u8], at: usize| {
+        let bytes = [data[at], data[at + 1], data[at + 2], data[at + 3]];
+        if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        }
+    };
+    let u16_bytes = |value: u16| {
+        if little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    };
+    let u32_bytes = |value: u32| {
+        if little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    };
+    let ifd0 = tiff_start + read_u32(segment, tiff_start + 4) as usize;
+    if ifd0 + 2 > segment.len() {
+        return;
+    }
+
+    let entry_count = read_u16(segment, ifd0) as usize;
+    let mut exif_ifd = None;
+    for index in 0..entry_count {
+        let entry = ifd0 + 2 + index * 12;
+        if entry + 12 > segment.len() {
+            return;
+        }
+        match read_u16(segment, entry) {
+            // SHORT (type 3) values are stored inline in the first two value bytes.
+            TAG_ORIENTATION if read_u16(segment, entry + 2) == 3 => {
+                segment[entry + 8..entry + 10].copy_from_slice(&u16_bytes(1));
+            }
+            TAG_EXIF_IFD_POINTER => {
+                exif_ifd = Some(tiff_start + read_u32(segment, entry + 8) as usize);
+            }
+            _ => {}
+        }
+    }
+    let next_ifd = ifd0 + 2 + entry_count * 12;
+    if next_ifd + 4 <= segment.len() {
+        segment[next_ifd..next_ifd + 4].copy_from_slice(&u32_bytes(0));
+    }
+
+    let Some(exif_ifd) = exif_ifd.filter(|offset| offset + 2 <= segment.len()) else {
+        return;
+    };
+    let entry_count = read_u16(segment, exif_ifd) as usize;
+    for index in 0..entry_count {
+        let entry = exif_ifd + 2 + index * 12;
+        if entry + 12 > segment.len() {
+            return;
+        }
+        let value = match read_u16(segment, entry) {
+            TAG_PIXEL_X_DIMENSION => width,
+            TAG_PIXEL_Y_DIMENSION => height,
+            _ => continue,
+        };
+        match read_u16(segment, entry + 2) {
+            3 => segment[entry + 8..entry + 10].copy_from_slice(&u16_bytes(value as u16)),
+            4 => segment[entry + 8..entry + 12].copy_from_slice(&u32_bytes(value)),
+            _ => {}
+        }
+    }
+}
+
+fn resolve_destination_for_policy(
+    base: &Path,
+    used_keys: &mut HashSet<String>,
+    policy: &CollisionPolicy,
+) -> (PreviewStatus, Option<String>, PathBuf) {
+    let key = destination_key(base);
+    let collision = used_keys.contains(&key) || base.exists();
+    match policy {
+        CollisionPolicy::Overwrite => {
+            used_keys.insert(key);
+            (
+                PreviewStatus::Ready,
+                if collision {
+                    Some("競合ポリシーにより上書きされます".to_string())
+                } else {
+                    None
+                },
+                base.to_path_buf(),
+            )
+        }
+        CollisionPolicy::Skip => {
+            if collision {
+                (
+                    PreviewStatus::Skipped,
+                    Some("出力先の競合によりスキップされました".to_string()),
+                    base.to_path_buf(),
+                )
+            } else {
+                used_keys.insert(key);
+                (PreviewStatus::Ready, None, base.to_path_buf())
+            }
+        }
+        CollisionPolicy::Sequence | CollisionPolicy::PrefixParent => {
+            let unique = uniquify_destination(base, used_keys);
+            (
+                PreviewStatus::Ready,
+                if unique != base {
+                    Some("競合のため連番サフィックスを付与しました".to_string())
+                } else {
+                    None
+                },
+                unique,
+            )
+        }
+    }
+}
+
+fn uniquify_destination(base: &Path, used_keys: &mut HashSet<String>) -> PathBuf {
+    let stem = base
+        .file_stem()
+        .and_then(|value| value.to_str())
+        .unwrap_or("file");
+    let ext = base
+        .extension()
+        .and_then(|value| value.to_str())
+        .unwrap_or("");
+    let mut candidate = base.to_path_buf();
+    let mut suffix = 1usize;
+    loop {
+        let key = destination_key(&candidate);
+        if !used_keys.contains(&key) && !candidate.exists() {
+            used_keys.insert(key);
+            return candidate;
+        }
+        let file_name = if ext.is_empty() {
+            format!("{}_{}", stem, suffix)
+        } else {
+            format!("{}_{}.{}", stem, suffix, ext)
+        };
+        candidate = base.with_file_name(file_name);
+        suffix += 1;
+    }
+}
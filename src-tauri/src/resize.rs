@@ -1,8 +1,9 @@
 use crate::error::AppError;
 use crate::file_collect::collect_targets_with_extensions;
 use crate::model::{
-    CollisionPolicy, OperationProgressEvent, PreviewStatus, ResizeCollectInfoResponse,
-    ResizeExecuteResponse, ResizePreviewItem, ResizePreviewRequest, ResizePreviewResponse,
+    CollisionPolicy, OperationProgressEvent, OperationSummary, PreviewStatus,
+    ResizeCollectInfoResponse, ResizeExecuteResponse, ResizePreviewItem, ResizePreviewRequest,
+    ResizePreviewResponse,
 };
 use crate::path_norm::relative_or_portable_absolute;
 use crate::worker_bridge::{self, BatchProgress, ResizeBatchItemMsg};
@@ -32,9 +33,13 @@ pub fn collect_info(
     input_paths: &[String],
     include_subfolders: bool,
 ) -> Result<ResizeCollectInfoResponse, AppError> {
-    let collect =
-        collect_targets_with_extensions(input_paths, include_subfolders, RESIZE_ALLOWED_EXTENSIONS)
-            .map_err(AppError::InvalidRequest)?;
+    let collect = collect_targets_with_extensions(
+        input_paths,
+        include_subfolders,
+        RESIZE_ALLOWED_EXTENSIONS,
+        None,
+    )
+    .map_err(AppError::InvalidRequest)?;
     let total_size: u64 = collect
         .files
         .iter()
@@ -48,7 +53,7 @@ pub fn collect_info(
 
 pub fn preview(request: &ResizePreviewRequest) -> Result<ResizePreviewResponse, AppError> {
     let plan = build_plan(request)?;
-    Ok(response_from_plan(&plan.items, &plan.output_dir))
+    Ok(response_from_plan(&plan))
 }
 
 pub fn execute<FCancel, FProgress>(
@@ -121,10 +126,12 @@ where
                 current_path: Some(progress.source),
                 done: false,
                 canceled,
+                eta_seconds: None,
+                bytes_processed: 0,
+                total_bytes: 0,
             });
         },
-    )
-    .map_err(|e| AppError::Io(e))?;
+    )?;
 
     succeeded = result.succeeded;
     failed = result.failed;
@@ -140,19 +147,30 @@ where
         current_path: None,
         done: true,
         canceled,
+        eta_seconds: None,
+        bytes_processed: 0,
+        total_bytes: 0,
     });
 
     Ok(ResizeExecuteResponse {
         output_dir: plan.output_dir.to_string_lossy().to_string(),
-        succeeded,
-        failed,
-        skipped,
+        summary: OperationSummary::new(
+            "resize",
+            succeeded + failed + skipped,
+            succeeded,
+            failed,
+            skipped,
+            None,
+            canceled,
+        )
+        .with_warnings(plan.warnings),
     })
 }
 
 struct ResizePlan {
     output_dir: PathBuf,
     items: Vec<PlannedResize>,
+    warnings: Vec<String>,
 }
 
 fn build_plan(request: &ResizePreviewRequest) -> Result<ResizePlan, AppError> {
@@ -160,6 +178,7 @@ fn build_plan(request: &ResizePreviewRequest) -> Result<ResizePlan, AppError> {
         &request.input_paths,
         request.include_subfolders,
         RESIZE_ALLOWED_EXTENSIONS,
+        request.max_files,
     )
     .map_err(AppError::InvalidRequest)?;
 
@@ -254,7 +273,11 @@ fn build_plan(request: &ResizePreviewRequest) -> Result<ResizePlan, AppError> {
         });
     }
 
-    Ok(ResizePlan { output_dir, items })
+    Ok(ResizePlan {
+        output_dir,
+        items,
+        warnings: collect.warnings(),
+    })
 }
 
 fn read_dimensions(path: &Path) -> Result<(u32, u32), AppError> {
@@ -268,10 +291,11 @@ fn read_dimensions(path: &Path) -> Result<(u32, u32), AppError> {
     Ok((w, h))
 }
 
-fn response_from_plan(items: &[PlannedResize], _output_dir: &Path) -> ResizePreviewResponse {
+fn response_from_plan(plan: &ResizePlan) -> ResizePreviewResponse {
     let mut ready = 0usize;
     let mut skipped = 0usize;
-    let result_items = items
+    let result_items = plan
+        .items
         .iter()
         .map(|item| {
             match item.status {
@@ -297,6 +321,7 @@ fn response_from_plan(items: &[PlannedResize], _output_dir: &Path) -> ResizePrev
         total: ready + skipped,
         ready,
         skipped,
+        warnings: plan.warnings.clone(),
     }
 }
 
@@ -364,7 +389,7 @@ fn resolve_destination_for_policy(
                 (PreviewStatus::Ready, None, base_destination.to_path_buf())
             }
         }
-        CollisionPolicy::Sequence => {
+        CollisionPolicy::Sequence | CollisionPolicy::PrefixParent => {
             let destination = uniquify_destination(base_destination, used_destinations);
             (
                 PreviewStatus::Ready,
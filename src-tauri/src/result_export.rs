@@ -0,0 +1,139 @@
+use crate::error::AppError;
+use crate::fs_atomic::atomic_write_replace;
+use crate::model::{
+    CompressBatchManifest, CompressPreviewRequest, ExecuteStatus, OperationResultRow,
+    PreviewManifest,
+};
+use chrono::Local;
+use std::fs;
+use std::path::PathBuf;
+
+const CSV_HEADER: [&str; 4] = ["source_path", "destination_path", "status", "reason"];
+
+/// Bump when the manifest layout changes incompatibly.
+pub const PREVIEW_MANIFEST_VERSION: u32 = 1;
+
+pub fn export_csv(rows: &[OperationResultRow], output_path: &str) -> Result<(), AppError> {
+    let path = resolve_output_path(output_path)?;
+    // BOM so that Excel opens the file as UTF-8.
+    let mut body = String::from("\u{FEFF}");
+    push_csv_record(&mut body, &CSV_HEADER);
+    for row in rows {
+        push_csv_record(
+            &mut body,
+            &[
+                row.source_path.as_str(),
+                row.destination_path.as_deref().unwrap_or(""),
+                status_label(&row.status),
+                row.reason.as_deref().unwrap_or(""),
+            ],
+        );
+    }
+    atomic_write_replace(&path, body.as_bytes())
+}
+
+pub fn export_json(rows: &[OperationResultRow], output_path: &str) -> Result<(), AppError> {
+    let path = resolve_output_path(output_path)?;
+    let body = serde_json::to_string_pretty(rows).map_err(|e| AppError::Io(e.to_string()))?;
+    atomic_write_replace(&path, body.as_bytes())
+}
+
+pub fn export_preview_manifest(
+    manifest_type: &str,
+    items: Vec<serde_json::Value>,
+    output_path: &str,
+) -> Result<(), AppError> {
+    let path = resolve_output_path(output_path)?;
+    let manifest = PreviewManifest {
+        manifest_version: PREVIEW_MANIFEST_VERSION,
+        manifest_type: manifest_type.to_string(),
+        generated_at: Local::now().to_rfc3339(),
+        items,
+    };
+    let body =
+        serde_json::to_string_pretty(&manifest).map_err(|e| AppError::Io(e.to_string()))?;
+    atomic_write_replace(&path, body.as_bytes())
+}
+
+pub fn import_preview_manifest(input_path: &str) -> Result<serde_json::Value, AppError> {
+    let path = PathBuf::from(input_path.trim());
+    if path.as_os_str().is_empty() {
+        return Err(AppError::InvalidRequest(
+            "入力パスが指定されていません".to_string(),
+        ));
+    }
+    let body = fs::read_to_string(&path)?;
+    let manifest: PreviewManifest = serde_json::from_str(&body).map_err(|e| {
+        AppError::InvalidRequest(format!("マニフェストの形式が正しくありません: {}", e))
+    })?;
+    if manifest.manifest_version > PREVIEW_MANIFEST_VERSION {
+        return Err(AppError::InvalidRequest(format!(
+            "未対応のマニフェストバージョンです: {}",
+            manifest.manifest_version
+        )));
+    }
+    serde_json::to_value(manifest).map_err(|e| AppError::Io(e.to_string()))
+}
+
+pub fn export_compress_batch_manifest(
+    request: &CompressPreviewRequest,
+    output_path: &str,
+) -> Result<(), AppError> {
+    let path = resolve_output_path(output_path)?;
+    let manifest = CompressBatchManifest {
+        cfm_version: env!("CARGO_PKG_VERSION").to_string(),
+        generated_at: Local::now().to_rfc3339(),
+        request: request.clone(),
+    };
+    let body =
+        serde_json::to_string_pretty(&manifest).map_err(|e| AppError::Io(e.to_string()))?;
+    atomic_write_replace(&path, body.as_bytes())
+}
+
+pub fn import_compress_batch_manifest(input_path: &str) -> Result<CompressBatchManifest, AppError> {
+    let path = PathBuf::from(input_path.trim());
+    if path.as_os_str().is_empty() {
+        return Err(AppError::InvalidRequest(
+            "入力パスが指定されていません".to_string(),
+        ));
+    }
+    let body = fs::read_to_string(&path)?;
+    serde_json::from_str(&body).map_err(|e| {
+        AppError::InvalidRequest(format!("マニフェストの形式が正しくありません: {}", e))
+    })
+}
+
+fn resolve_output_path(output_path: &str) -> Result<PathBuf, AppError> {
+    let path = PathBuf::from(output_path.trim());
+    if path.as_os_str().is_empty() {
+        return Err(AppError::InvalidRequest(
+            "出力パスが指定されていません".to_string(),
+        ));
+    }
+    Ok(path)
+}
+
+fn status_label(status: &ExecuteStatus) -> &'static str {
+    match status {
+        ExecuteStatus::Succeeded => "succeeded",
+        ExecuteStatus::Failed => "failed",
+        ExecuteStatus::Skipped => "skipped",
+    }
+}
+
+/// Append one RFC 4180 record terminated by CRLF.
+fn push_csv_record(out: &mut String, fields: &[&str]) {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\r', '\n']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+}
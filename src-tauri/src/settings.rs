@@ -1,10 +1,13 @@
 use crate::error::AppError;
 use crate::model::{
-    AppSettings, DeleteMode, DeletePattern, ImportConflictPreview, RenameTemplate, ThemeMode,
+    AppSettings, DeleteMode, DeletePattern, ImportConflictPreview, ImportMergePreview,
+    OutputDirDiff, PatternDiff, RenameTemplate, TemplateDiff, ThemeMode,
 };
-use std::collections::{HashMap, HashSet};
+use crate::path_norm::validate_output_suffix;
+use chrono::Local;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tauri::{AppHandle, Manager};
 
@@ -17,7 +20,27 @@ pub fn load_settings(app: &AppHandle) -> Result<AppSettings, AppError> {
     }
 
     let content = fs::read_to_string(&path).map_err(|e| AppError::Settings(e.to_string()))?;
-    serde_json::from_str(&content).map_err(|e| AppError::Settings(e.to_string()))
+    let mut settings: AppSettings =
+        serde_json::from_str(&content).map_err(|e| AppError::Settings(e.to_string()))?;
+    let mut invalid: Vec<String> = check_output_directory_validity(&settings.output_directories)
+        .into_iter()
+        .filter_map(|(key, valid)| (!valid).then_some(key))
+        .collect();
+    invalid.sort();
+    settings.invalid_output_directories = invalid;
+    Ok(settings)
+}
+
+/// Whether each saved output directory currently exists (e.g. external drive plugged in).
+pub fn check_output_directory_validity(dirs: &HashMap<String, String>) -> HashMap<String, bool> {
+    dirs.iter()
+        .map(|(key, dir)| (key.clone(), Path::new(dir).is_dir()))
+        .collect()
+}
+
+/// Keys of saved output directories that no longer exist. They are kept in settings.
+pub fn invalid_output_directories(app: &AppHandle) -> Result<Vec<String>, AppError> {
+    Ok(load_settings(app)?.invalid_output_directories)
 }
 
 pub fn save_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), AppError> {
@@ -31,6 +54,102 @@ pub fn save_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), AppE
     fs::write(path, body).map_err(|e| AppError::Settings(e.to_string()))
 }
 
+/// Unique non-empty rename template groups, sorted.
+pub fn list_rename_template_groups(app: &AppHandle) -> Result<Vec<String>, AppError> {
+    let settings = load_settings(app)?;
+    let groups: BTreeSet<String> = settings
+        .rename_templates
+        .iter()
+        .filter_map(|template| template.group.as_deref())
+        .map(str::trim)
+        .filter(|group| !group.is_empty())
+        .map(str::to_string)
+        .collect();
+    Ok(groups.into_iter().collect())
+}
+
+/// Keys of `AppSettings.output_directories`.
+pub const OUTPUT_DIRECTORY_KEYS: &[&str] = &[
+    "rename",
+    "compress",
+    "flatten",
+    "delete_retreat",
+    "exif_offset",
+    "metadata_strip",
+];
+
+/// Saved default output folder for an operation, if one is set.
+pub fn default_output_dir(app: &AppHandle, operation: &str) -> Result<Option<String>, AppError> {
+    if !OUTPUT_DIRECTORY_KEYS.contains(&operation) {
+        return Err(AppError::InvalidRequest(format!(
+            "不明な操作名です: {}",
+            operation
+        )));
+    }
+    let settings = load_settings(app)?;
+    Ok(settings
+        .output_directories
+        .get(operation)
+        .map(|dir| dir.trim())
+        .filter(|dir| !dir.is_empty())
+        .map(str::to_string))
+}
+
+/// Saved suffix for the operation's auto-generated output folder, if any.
+pub fn default_output_suffix(app: &AppHandle, operation: &str) -> Result<Option<String>, AppError> {
+    let settings = load_settings(app)?;
+    Ok(settings
+        .output_suffixes
+        .get(operation)
+        .map(|suffix| suffix.trim())
+        .filter(|suffix| !suffix.is_empty())
+        .map(str::to_string))
+}
+
+/// Backs up the current file, then writes and returns the defaults.
+pub fn reset_settings(app: &AppHandle) -> Result<AppSettings, AppError> {
+    backup_settings_file(app)?;
+    let defaults = AppSettings::default();
+    save_settings(app, &defaults)?;
+    Ok(defaults)
+}
+
+/// Resets one section to its default while keeping the others.
+pub fn reset_settings_section(app: &AppHandle, section: &str) -> Result<AppSettings, AppError> {
+    let mut settings = load_settings(app)?;
+    let defaults = AppSettings::default();
+    match section {
+        "delete_patterns" => settings.delete_patterns = defaults.delete_patterns,
+        "rename_templates" => settings.rename_templates = defaults.rename_templates,
+        "output_directories" => settings.output_directories = defaults.output_directories,
+        "output_suffixes" => settings.output_suffixes = defaults.output_suffixes,
+        "theme" => settings.theme = defaults.theme,
+        _ => {
+            return Err(AppError::Settings(format!(
+                "不明な設定セクションです: {}",
+                section
+            )));
+        }
+    }
+    backup_settings_file(app)?;
+    save_settings(app, &settings)?;
+    Ok(settings)
+}
+
+/// Copies the raw settings file aside so even an unparsable file can be recovered.
+fn backup_settings_file(app: &AppHandle) -> Result<(), AppError> {
+    let path = settings_file_path(app)?;
+    if !path.exists() {
+        return Ok(());
+    }
+    let backup = path.with_file_name(format!(
+        "settings_backup_{}.json",
+        Local::now().format("%Y%m%d%H%M%S")
+    ));
+    fs::copy(&path, backup).map_err(|e| AppError::Settings(e.to_string()))?;
+    Ok(())
+}
+
 pub fn settings_file_path(app: &AppHandle) -> Result<PathBuf, AppError> {
     let mut dir = app
         .path()
@@ -58,14 +177,7 @@ pub fn import_settings_from_path(
     mode: &str,
     conflict_policy: &str,
 ) -> Result<AppSettings, AppError> {
-    let path = PathBuf::from(input_path.trim());
-    if path.as_os_str().is_empty() {
-        return Err(AppError::Settings("入力パスが指定されていません".to_string()));
-    }
-    let body = fs::read_to_string(path).map_err(|e| AppError::Settings(e.to_string()))?;
-    let imported: AppSettings =
-        serde_json::from_str(&body).map_err(|e| AppError::Settings(e.to_string()))?;
-    validate_settings(&imported)?;
+    let imported = read_import_file(input_path)?;
 
     let next = match mode {
         "overwrite" => imported,
@@ -84,14 +196,7 @@ pub fn preview_import_conflicts(
     app: &AppHandle,
     input_path: &str,
 ) -> Result<ImportConflictPreview, AppError> {
-    let path = PathBuf::from(input_path.trim());
-    if path.as_os_str().is_empty() {
-        return Err(AppError::Settings("入力パスが指定されていません".to_string()));
-    }
-    let body = fs::read_to_string(path).map_err(|e| AppError::Settings(e.to_string()))?;
-    let imported: AppSettings =
-        serde_json::from_str(&body).map_err(|e| AppError::Settings(e.to_string()))?;
-    validate_settings(&imported)?;
+    let imported = read_import_file(input_path)?;
 
     let existing = load_settings(app)?;
     let existing_pattern_names: HashSet<String> = existing
@@ -144,6 +249,96 @@ pub fn preview_import_conflicts(
     })
 }
 
+/// Value-level before/after for every entry that exists in both the current settings
+/// and the import file.
+pub fn preview_import_merge(
+    app: &AppHandle,
+    input_path: &str,
+) -> Result<ImportMergePreview, AppError> {
+    let imported = read_import_file(input_path)?;
+    let existing = load_settings(app)?;
+
+    let mut pattern_diffs: Vec<PatternDiff> = imported
+        .delete_patterns
+        .iter()
+        .filter_map(|imported| {
+            let existing = existing
+                .delete_patterns
+                .iter()
+                .find(|pattern| pattern.name.eq_ignore_ascii_case(&imported.name))?;
+            Some(PatternDiff {
+                name: imported.name.clone(),
+                extensions_changed: normalized_extension_set(&existing.extensions)
+                    != normalized_extension_set(&imported.extensions),
+                mode_changed: std::mem::discriminant(&existing.mode)
+                    != std::mem::discriminant(&imported.mode),
+                existing: existing.clone(),
+                imported: imported.clone(),
+            })
+        })
+        .collect();
+    pattern_diffs.sort_by_key(|diff| diff.name.to_ascii_lowercase());
+
+    let mut template_diffs: Vec<TemplateDiff> = imported
+        .rename_templates
+        .iter()
+        .filter_map(|imported| {
+            let existing = existing
+                .rename_templates
+                .iter()
+                .find(|template| template.name.eq_ignore_ascii_case(&imported.name))?;
+            Some(TemplateDiff {
+                name: imported.name.clone(),
+                template_changed: existing.template != imported.template,
+                group_changed: existing.group != imported.group,
+                existing: existing.clone(),
+                imported: imported.clone(),
+            })
+        })
+        .collect();
+    template_diffs.sort_by_key(|diff| diff.name.to_ascii_lowercase());
+
+    let mut output_dir_diffs: Vec<OutputDirDiff> = imported
+        .output_directories
+        .iter()
+        .filter_map(|(key, imported_value)| {
+            let existing_value = existing.output_directories.get(key)?;
+            Some(OutputDirDiff {
+                key: key.clone(),
+                existing_value: existing_value.clone(),
+                imported_value: imported_value.clone(),
+            })
+        })
+        .collect();
+    output_dir_diffs.sort_by(|a, b| a.key.cmp(&b.key));
+
+    Ok(ImportMergePreview {
+        pattern_diffs,
+        template_diffs,
+        output_dir_diffs,
+    })
+}
+
+fn read_import_file(input_path: &str) -> Result<AppSettings, AppError> {
+    let path = PathBuf::from(input_path.trim());
+    if path.as_os_str().is_empty() {
+        return Err(AppError::Settings("入力パスが指定されていません".to_string()));
+    }
+    let body = fs::read_to_string(path).map_err(|e| AppError::Settings(e.to_string()))?;
+    let imported: AppSettings =
+        serde_json::from_str(&body).map_err(|e| AppError::Settings(e.to_string()))?;
+    validate_settings(&imported)?;
+    Ok(imported)
+}
+
+fn normalized_extension_set(extensions: &[String]) -> BTreeSet<String> {
+    extensions
+        .iter()
+        .map(|ext| ext.trim().trim_start_matches('.').to_ascii_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect()
+}
+
 pub fn open_settings_folder(app: &AppHandle) -> Result<(), AppError> {
     let path = settings_file_path(app)?;
     let folder = path
@@ -209,6 +404,28 @@ fn validate_settings(settings: &AppSettings) -> Result<(), AppError> {
             }
         }
     }
+    for suffix in settings.output_suffixes.values() {
+        validate_output_suffix(suffix).map_err(AppError::Settings)?;
+    }
+    for template in &settings.rename_templates {
+        if let Some(group) = &template.group {
+            if group.trim().chars().count() > 40 {
+                return Err(AppError::Settings(
+                    "テンプレートのグループ名は40文字以内で指定してください".to_string(),
+                ));
+            }
+        }
+    }
+    if settings.compress_max_concurrent_decodes == Some(0) {
+        return Err(AppError::Settings(
+            "同時デコード数は1以上で指定してください".to_string(),
+        ));
+    }
+    if settings.metadata_scan_threads == Some(0) {
+        return Err(AppError::Settings(
+            "メタデータ解析のスレッド数は1以上で指定してください".to_string(),
+        ));
+    }
     Ok(())
 }
 
@@ -262,10 +479,18 @@ fn merge_settings(
         }
     }
 
-    let output_directories = merge_output_dirs(
+    let output_directories = merge_keyed_values(
         &existing.output_directories,
         &imported.output_directories,
         conflict_policy,
+        "出力フォルダキー",
+    )?;
+
+    let output_suffixes = merge_keyed_values(
+        &existing.output_suffixes,
+        &imported.output_suffixes,
+        conflict_policy,
+        "出力サフィックスキー",
     )?;
 
     let theme = match conflict_policy {
@@ -291,7 +516,15 @@ fn merge_settings(
         delete_patterns,
         rename_templates,
         output_directories,
+        output_suffixes,
         theme,
+        compress_max_concurrent_decodes: imported
+            .compress_max_concurrent_decodes
+            .or(existing.compress_max_concurrent_decodes),
+        metadata_scan_threads: imported
+            .metadata_scan_threads
+            .or(existing.metadata_scan_threads),
+        invalid_output_directories: Vec::new(),
     })
 }
 
@@ -307,10 +540,12 @@ fn find_template_index(values: &[RenameTemplate], name: &str) -> Option<usize> {
         .position(|item| item.name.to_ascii_lowercase() == name.to_ascii_lowercase())
 }
 
-fn merge_output_dirs(
+/// `key_label` names the map in the conflict message.
+fn merge_keyed_values(
     existing: &HashMap<String, String>,
     imported: &HashMap<String, String>,
     conflict_policy: &str,
+    key_label: &str,
 ) -> Result<HashMap<String, String>, AppError> {
     let mut merged = existing.clone();
     for (key, value) in imported {
@@ -322,8 +557,8 @@ fn merge_output_dirs(
                 }
                 "cancel" => {
                     return Err(AppError::Settings(format!(
-                        "出力フォルダキー `{}` が競合しています",
-                        key
+                        "{} `{}` が競合しています",
+                        key_label, key
                     )));
                 }
                 _ => {
@@ -0,0 +1,260 @@
+use crate::compress;
+use crate::error::AppError;
+use crate::metadata_strip;
+use crate::model::{ExecuteStatus, WatchFolderConfig, WatchFolderEvent, WatchOperation};
+use crate::path_norm::safe_canonicalize;
+use crate::rename;
+use crate::{record_history, HistoryRun};
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Quiet period before a new file is processed, so in-progress writes can finish.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// Dropping a watcher closes its event channel, which ends the worker thread.
+static WATCHERS: Lazy<Mutex<HashMap<String, RecommendedWatcher>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static NEXT_WATCH_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Settings defaults are expected to be filled in by the caller, as for `execute_compress`.
+pub fn start(app: &AppHandle, mut config: WatchFolderConfig) -> Result<String, AppError> {
+    let folder = PathBuf::from(config.folder_path.trim());
+    if !folder.is_dir() {
+        return Err(AppError::InvalidRequest(
+            "監視フォルダが見つかりません".to_string(),
+        ));
+    }
+    // Every run sees a single file, so an unset output folder would be auto-generated
+    // per file. Resolve it once against the watched folder instead.
+    if let Some(request) = config.compress_request.as_mut() {
+        if request
+            .output_dir
+            .as_deref()
+            .is_none_or(|dir| dir.trim().is_empty())
+        {
+            let root = safe_canonicalize(&folder)?;
+            let output_dir = compress::resolve_output_dir(
+                Some(&root),
+                None,
+                request.output_suffix.as_deref().unwrap_or("_compressed_"),
+            )?;
+            request.output_dir = Some(output_dir.to_string_lossy().to_string());
+        }
+    }
+    let has_preset = match config.operation {
+        WatchOperation::Rename => config.rename_request.is_some(),
+        WatchOperation::Compress => config.compress_request.is_some(),
+        WatchOperation::MetadataStrip => config.metadata_strip_request.is_some(),
+    };
+    if !has_preset {
+        return Err(AppError::InvalidRequest(
+            "監視時に実行する操作の設定がありません".to_string(),
+        ));
+    }
+
+    let (tx, rx) = mpsc::channel::<PathBuf>();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+        let Ok(event) = result else {
+            return;
+        };
+        if matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_) | ModifyKind::Data(_))
+        ) {
+            for path in event.paths {
+                let _ = tx.send(path);
+            }
+        }
+    })
+    .map_err(|e| AppError::Io(format!("フォルダ監視の開始に失敗しました: {}", e)))?;
+    let mode = if config.include_subfolders {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    watcher
+        .watch(&folder, mode)
+        .map_err(|e| AppError::Io(format!("フォルダ監視の開始に失敗しました: {}", e)))?;
+
+    let watch_id = NEXT_WATCH_ID.fetch_add(1, Ordering::Relaxed).to_string();
+    let thread_app = app.clone();
+    let thread_id = watch_id.clone();
+    std::thread::spawn(move || run_debounce_loop(&thread_app, &thread_id, &config, rx));
+
+    WATCHERS
+        .lock()
+        .map_err(|e| AppError::Io(e.to_string()))?
+        .insert(watch_id.clone(), watcher);
+    Ok(watch_id)
+}
+
+pub fn stop(id: &str) -> Result<(), AppError> {
+    let removed = WATCHERS
+        .lock()
+        .map_err(|e| AppError::Io(e.to_string()))?
+        .remove(id);
+    match removed {
+        Some(_) => Ok(()),
+        None => Err(AppError::InvalidRequest(format!(
+            "監視IDが見つかりません: {}",
+            id
+        ))),
+    }
+}
+
+pub fn stop_all() {
+    if let Ok(mut watchers) = WATCHERS.lock() {
+        watchers.clear();
+    }
+}
+
+fn run_debounce_loop(
+    app: &AppHandle,
+    watch_id: &str,
+    config: &WatchFolderConfig,
+    rx: mpsc::Receiver<PathBuf>,
+) {
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    // Outputs written by this watcher, so their own events are not picked up again.
+    // Entries expire after one debounce window so later real files at the same path run.
+    let mut produced: HashMap<PathBuf, Instant> = HashMap::new();
+
+    loop {
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(path) => {
+                if !produced.contains_key(&path) && matches_extension(&path, &config.extensions) {
+                    pending.insert(path, Instant::now());
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        let now = Instant::now();
+        produced.retain(|_, written| now.duration_since(*written) < DEBOUNCE);
+        let ready: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, seen)| now.duration_since(**seen) >= DEBOUNCE)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in ready {
+            pending.remove(&path);
+            if !path.is_file() {
+                continue;
+            }
+            let (status, reason, outputs) = run_operation(app, config, &path);
+            let written = Instant::now();
+            produced.extend(outputs.into_iter().map(|output| (output, written)));
+            let _ = app.emit(
+                "watch-folder-event",
+                WatchFolderEvent {
+                    watch_id: watch_id.to_string(),
+                    file_path: path.to_string_lossy().to_string(),
+                    operation: config.operation.clone(),
+                    status,
+                    reason,
+                },
+            );
+        }
+    }
+}
+
+fn matches_extension(path: &Path, extensions: &[String]) -> bool {
+    if extensions.is_empty() {
+        return true;
+    }
+    let Some(ext) = path.extension().and_then(|value| value.to_str()) else {
+        return false;
+    };
+    extensions
+        .iter()
+        .any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(ext))
+}
+
+/// Run the configured preset against a single file and record it in history.
+/// Returns the resulting status, reason and any output paths it produced.
+fn run_operation(
+    app: &AppHandle,
+    config: &WatchFolderConfig,
+    path: &Path,
+) -> (ExecuteStatus, Option<String>, Vec<PathBuf>) {
+    let input_paths = vec![path.to_string_lossy().to_string()];
+    let result = match config.operation {
+        WatchOperation::Rename => config.rename_request.clone().map(|mut request| {
+            request.input_paths = input_paths;
+            let run = HistoryRun::start();
+            let result = rename::execute(&request, || false, |_| {});
+            record_history(app, "rename", &request, &run, result, |response| {
+                &mut response.summary
+            })
+            .map(|response| {
+                response
+                    .details
+                    .into_iter()
+                    .map(|detail| (detail.status, detail.reason, detail.destination_path))
+                    .collect::<Vec<_>>()
+            })
+        }),
+        WatchOperation::Compress => config.compress_request.clone().map(|mut request| {
+            request.input_paths = input_paths;
+            let run = HistoryRun::start();
+            let result = compress::execute(app, &request, || false, |_| {});
+            record_history(app, "compress", &request, &run, result, |response| {
+                &mut response.summary
+            })
+            .map(|response| {
+                response
+                    .details
+                    .into_iter()
+                    .map(|detail| (detail.status, detail.reason, Some(detail.destination_path)))
+                    .collect::<Vec<_>>()
+            })
+        }),
+        WatchOperation::MetadataStrip => {
+            config.metadata_strip_request.clone().map(|mut request| {
+                request.input_paths = input_paths;
+                let run = HistoryRun::start();
+                let result = metadata_strip::execute(&request, || false, |_| {});
+                record_history(app, "metadata_strip", &request, &run, result, |response| {
+                    &mut response.summary
+                })
+                .map(|response| {
+                    response
+                        .details
+                        .into_iter()
+                        .map(|detail| (detail.status, detail.reason, Some(detail.source_path)))
+                        .collect::<Vec<_>>()
+                })
+            })
+        }
+    };
+
+    match result {
+        Some(Ok(details)) => match details.into_iter().next() {
+            Some((status, reason, output)) => (
+                status,
+                reason,
+                output.map(PathBuf::from).into_iter().collect(),
+            ),
+            None => (
+                ExecuteStatus::Skipped,
+                Some("処理対象になりませんでした".to_string()),
+                Vec::new(),
+            ),
+        },
+        Some(Err(e)) => (ExecuteStatus::Failed, Some(e.to_string()), Vec::new()),
+        None => (
+            ExecuteStatus::Failed,
+            Some("監視時に実行する操作の設定がありません".to_string()),
+            Vec::new(),
+        ),
+    }
+}
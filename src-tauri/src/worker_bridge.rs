@@ -1,6 +1,6 @@
+use crate::error::AppError;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::{HashMap, VecDeque};
 use std::sync::{mpsc, Arc, Mutex};
 use tauri::AppHandle;
 use tauri_plugin_shell::process::CommandChild;
@@ -32,6 +32,9 @@ pub enum WorkerRequest {
         resize_percent: f32,
         quality: u8,
         preserve_exif: bool,
+        verify_output: bool,
+        direct_write_threshold_bytes: Option<u64>,
+        max_concurrent_decodes: Option<usize>,
     },
     ResizeBatch {
         id: String,
@@ -57,6 +60,7 @@ pub struct CompressBatchItemMsg {
     pub source: String,
     pub destination: String,
     pub skip: bool,
+    pub timeout_ms: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -66,6 +70,20 @@ pub struct ResizeBatchItemMsg {
     pub skip: bool,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct SuggestIterationMsg {
+    pub resize_percent: f32,
+    pub estimated_ratio: f64,
+    pub estimated_bytes: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SampleEstimateEntryMsg {
+    pub source: String,
+    pub source_size: u64,
+    pub compressed_size: u64,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum WorkerResponse {
@@ -73,15 +91,25 @@ pub enum WorkerResponse {
         id: String,
         current: usize,
         total: usize,
+        #[serde(default)]
+        sample: Option<SampleEstimateEntryMsg>,
+    },
+    SampleEstimateDetail {
+        id: String,
+        samples: Vec<SampleEstimateEntryMsg>,
     },
     SampleEstimateResult {
         id: String,
         compression_ratio: f64,
+        #[serde(default)]
+        confidence: f64,
     },
     SuggestParamsResult {
         id: String,
         resize_percent: f32,
         quality: u8,
+        #[serde(default)]
+        iterations: Vec<SuggestIterationMsg>,
     },
     CompressFileDone {
         id: String,
@@ -90,6 +118,8 @@ pub enum WorkerResponse {
         status: String,
         output_size: Option<u64>,
         reason: Option<String>,
+        #[serde(default)]
+        write_mode: Option<String>,
     },
     CompressBatchDone {
         id: String,
@@ -121,6 +151,7 @@ impl WorkerResponse {
     pub fn id(&self) -> &str {
         match self {
             WorkerResponse::Progress { id, .. } => id,
+            WorkerResponse::SampleEstimateDetail { id, .. } => id,
             WorkerResponse::SampleEstimateResult { id, .. } => id,
             WorkerResponse::SuggestParamsResult { id, .. } => id,
             WorkerResponse::CompressFileDone { id, .. } => id,
@@ -148,7 +179,45 @@ impl WorkerResponse {
 struct WorkerInner {
     child: CommandChild,
     pending: Arc<Mutex<HashMap<String, mpsc::Sender<WorkerResponse>>>>,
-    next_id: AtomicU64,
+    // Last few IPC lines in each direction, reported when the worker dies.
+    recent_received: Arc<Mutex<VecDeque<String>>>,
+    recent_sent: Mutex<VecDeque<String>>,
+}
+
+const RECENT_IPC_LIMIT: usize = 20;
+const RECENT_IPC_LINE_CHARS: usize = 200;
+
+fn push_recent(buffer: &Mutex<VecDeque<String>>, line: &str) {
+    let Ok(mut buffer) = buffer.lock() else {
+        return;
+    };
+    if buffer.len() == RECENT_IPC_LIMIT {
+        buffer.pop_front();
+    }
+    buffer.push_back(line.chars().take(RECENT_IPC_LINE_CHARS).collect());
+}
+
+const WORKER_CRASHED_MESSAGE: &str = "ワーカープロセスが予期せず終了しました";
+
+/// Drop the dead worker so the next call respawns it, and describe the crash
+/// together with the last IPC traffic for bug reports.
+fn reset_crashed_worker() -> AppError {
+    let inner = BRIDGE.lock().ok().and_then(|mut guard| guard.take());
+    let Some(inner) = inner else {
+        return AppError::WorkerCrashed(WORKER_CRASHED_MESSAGE.to_string());
+    };
+    let join = |buffer: &Mutex<VecDeque<String>>| {
+        buffer
+            .lock()
+            .map(|lines| lines.iter().cloned().collect::<Vec<_>>().join(" | "))
+            .unwrap_or_default()
+    };
+    AppError::WorkerCrashed(format!(
+        "{}。最後のメッセージ: [{}] 最後の送信: [{}]",
+        WORKER_CRASHED_MESSAGE,
+        join(&inner.recent_received),
+        join(&inner.recent_sent)
+    ))
 }
 
 static BRIDGE: Mutex<Option<WorkerInner>> = Mutex::new(None);
@@ -169,6 +238,9 @@ fn ensure_worker(app: &AppHandle) -> Result<(), String> {
     let pending: Arc<Mutex<HashMap<String, mpsc::Sender<WorkerResponse>>>> =
         Arc::new(Mutex::new(HashMap::new()));
     let pending_clone = Arc::clone(&pending);
+    let recent_received: Arc<Mutex<VecDeque<String>>> =
+        Arc::new(Mutex::new(VecDeque::with_capacity(RECENT_IPC_LIMIT)));
+    let recent_received_clone = Arc::clone(&recent_received);
 
     // Background thread to read stdout and route responses
     std::thread::spawn(move || {
@@ -181,6 +253,7 @@ fn ensure_worker(app: &AppHandle) -> Result<(), String> {
                     if line.is_empty() {
                         continue;
                     }
+                    push_recent(&recent_received_clone, line);
                     if let Ok(resp) = serde_json::from_str::<WorkerResponse>(line) {
                         let id = resp.id().to_string();
                         let is_terminal = resp.is_terminal();
@@ -195,7 +268,24 @@ fn ensure_worker(app: &AppHandle) -> Result<(), String> {
                         }
                     }
                 }
-                Some(CommandEvent::Terminated(_)) | None => {
+                Some(CommandEvent::Stderr(line_bytes)) => {
+                    let line = String::from_utf8_lossy(&line_bytes);
+                    push_recent(&recent_received_clone, &format!("stderr: {}", line.trim()));
+                }
+                Some(CommandEvent::Terminated(payload)) => {
+                    push_recent(
+                        &recent_received_clone,
+                        &format!(
+                            "terminated: code={:?} signal={:?}",
+                            payload.code, payload.signal
+                        ),
+                    );
+                    // Worker crashed or exited — clear all pending
+                    let mut map = pending_clone.lock().unwrap();
+                    map.clear();
+                    break;
+                }
+                None => {
                     // Worker crashed or exited — clear all pending
                     let mut map = pending_clone.lock().unwrap();
                     map.clear();
@@ -209,7 +299,8 @@ fn ensure_worker(app: &AppHandle) -> Result<(), String> {
     *guard = Some(WorkerInner {
         child,
         pending,
-        next_id: AtomicU64::new(1),
+        recent_received,
+        recent_sent: Mutex::new(VecDeque::with_capacity(RECENT_IPC_LIMIT)),
     });
 
     Ok(())
@@ -230,9 +321,13 @@ fn send_request(
     let (tx, rx) = mpsc::channel();
     {
         let mut pending = inner.pending.lock().unwrap();
+        if pending.contains_key(id) {
+            return Err(format!("リクエストIDが重複しています: {}", id));
+        }
         pending.insert(id.to_string(), tx);
     }
 
+    push_recent(&inner.recent_sent, &json);
     inner
         .child
         .write((json + "\n").as_bytes())
@@ -252,17 +347,26 @@ fn request_id(req: &WorkerRequest) -> &str {
     }
 }
 
-fn next_id() -> Result<String, String> {
-    let guard = BRIDGE.lock().map_err(|e| e.to_string())?;
-    let inner = guard
-        .as_ref()
-        .ok_or_else(|| "ワーカーが起動していません".to_string())?;
-    let id = inner.next_id.fetch_add(1, Ordering::Relaxed);
-    Ok(id.to_string())
+/// Random rather than a counter so a restarted worker never sees a reused ID.
+fn next_id() -> String {
+    uuid::Uuid::new_v4().to_string()
 }
 
 // ── Public API ──
 
+pub struct SampleEstimate {
+    pub compression_ratio: f64,
+    /// 0.0–1.0, derived from the spread of per-sample ratios.
+    pub confidence: f64,
+    pub samples: Vec<SampleEstimateEntryMsg>,
+}
+
+pub struct SuggestedParams {
+    pub resize_percent: f32,
+    pub quality: u8,
+    pub iterations: Vec<SuggestIterationMsg>,
+}
+
 pub fn sample_estimate(
     app: &AppHandle,
     files: Vec<String>,
@@ -270,47 +374,97 @@ pub fn sample_estimate(
     quality: u8,
     max_samples: usize,
     is_cancelled: impl Fn() -> bool,
-    on_progress: impl Fn(usize, usize),
-) -> Result<f64, String> {
-    ensure_worker(app)?;
-    let id = next_id()?;
+    on_progress: impl Fn(usize, usize, Option<f64>),
+) -> Result<SampleEstimate, AppError> {
+    ensure_worker(app).map_err(AppError::Io)?;
+    let id = next_id();
     let rx = send_request(&WorkerRequest::SampleEstimate {
         id: id.clone(),
         files,
         resize_percent,
         quality,
         max_samples,
-    })?;
+    })
+    .map_err(AppError::Io)?;
 
+    let mut samples = Vec::new();
+    let mut received: Vec<SampleEstimateEntryMsg> = Vec::new();
     loop {
         if is_cancelled() {
             let _ = send_request(&WorkerRequest::Cancel { id: id.clone() });
-            return Err("キャンセルされました".to_string());
+            // Keep what was sampled so far so the estimate matches the last partial ratio.
+            return match partial_ratio(&received) {
+                Some(compression_ratio) => Ok(SampleEstimate {
+                    compression_ratio,
+                    confidence: ratio_confidence(&received),
+                    samples: received,
+                }),
+                None => Err(AppError::Cancelled),
+            };
         }
         match rx.recv_timeout(std::time::Duration::from_millis(100)) {
-            Ok(WorkerResponse::Progress { current, total, .. }) => {
-                on_progress(current, total);
+            Ok(WorkerResponse::Progress {
+                current,
+                total,
+                sample,
+                ..
+            }) => {
+                received.extend(sample);
+                on_progress(current, total, partial_ratio(&received));
+            }
+            Ok(WorkerResponse::SampleEstimateDetail { samples: detail, .. }) => {
+                samples = detail;
             }
             Ok(WorkerResponse::SampleEstimateResult {
-                compression_ratio, ..
+                compression_ratio,
+                confidence,
+                ..
             }) => {
-                return Ok(compression_ratio);
+                return Ok(SampleEstimate {
+                    compression_ratio,
+                    confidence,
+                    samples,
+                });
             }
             Ok(WorkerResponse::Error { message, .. }) => {
-                return Err(message);
+                return Err(AppError::Io(message));
             }
             Ok(_) => {}
             Err(mpsc::RecvTimeoutError::Timeout) => continue,
             Err(mpsc::RecvTimeoutError::Disconnected) => {
-                // Worker died — clear bridge so next call respawns
-                let mut guard = BRIDGE.lock().map_err(|e| e.to_string())?;
-                *guard = None;
-                return Err("ワーカープロセスが予期せず終了しました".to_string());
+                return Err(reset_crashed_worker());
             }
         }
     }
 }
 
+/// Compressed / source bytes over the samples received so far.
+fn partial_ratio(samples: &[SampleEstimateEntryMsg]) -> Option<f64> {
+    let (source, compressed) = samples.iter().fold((0u64, 0u64), |(s, c), entry| {
+        (s + entry.source_size, c + entry.compressed_size)
+    });
+    (source > 0).then(|| compressed as f64 / source as f64)
+}
+
+/// Mirrors the worker's confidence: `1 - cv` of the per-sample ratios.
+fn ratio_confidence(samples: &[SampleEstimateEntryMsg]) -> f64 {
+    let ratios: Vec<f64> = samples
+        .iter()
+        .filter(|entry| entry.source_size > 0)
+        .map(|entry| entry.compressed_size as f64 / entry.source_size as f64)
+        .collect();
+    if ratios.len() < 2 {
+        return 1.0;
+    }
+    let n = ratios.len() as f64;
+    let mean = ratios.iter().sum::<f64>() / n;
+    if mean <= 0.0 {
+        return 0.0;
+    }
+    let variance = ratios.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n;
+    1.0 - (variance.sqrt() / mean).min(1.0)
+}
+
 pub fn suggest_params(
     app: &AppHandle,
     files: Vec<String>,
@@ -318,9 +472,10 @@ pub fn suggest_params(
     target_bytes: u64,
     quality_seed: u8,
     max_samples: usize,
-) -> Result<(f32, u8), String> {
-    ensure_worker(app)?;
-    let id = next_id()?;
+    is_cancelled: impl Fn() -> bool,
+) -> Result<SuggestedParams, AppError> {
+    ensure_worker(app).map_err(AppError::Io)?;
+    let id = next_id();
     let rx = send_request(&WorkerRequest::SuggestParams {
         id: id.clone(),
         files,
@@ -328,27 +483,35 @@ pub fn suggest_params(
         target_bytes,
         quality_seed,
         max_samples,
-    })?;
+    })
+    .map_err(AppError::Io)?;
 
     loop {
+        if is_cancelled() {
+            let _ = send_request(&WorkerRequest::Cancel { id: id.clone() });
+            return Err(AppError::Cancelled);
+        }
         match rx.recv_timeout(std::time::Duration::from_millis(100)) {
             Ok(WorkerResponse::Progress { .. }) => {}
             Ok(WorkerResponse::SuggestParamsResult {
                 resize_percent,
                 quality,
+                iterations,
                 ..
             }) => {
-                return Ok((resize_percent, quality));
+                return Ok(SuggestedParams {
+                    resize_percent,
+                    quality,
+                    iterations,
+                });
             }
             Ok(WorkerResponse::Error { message, .. }) => {
-                return Err(message);
+                return Err(AppError::Io(message));
             }
             Ok(_) => {}
             Err(mpsc::RecvTimeoutError::Timeout) => continue,
             Err(mpsc::RecvTimeoutError::Disconnected) => {
-                let mut guard = BRIDGE.lock().map_err(|e| e.to_string())?;
-                *guard = None;
-                return Err("ワーカープロセスが予期せず終了しました".to_string());
+                return Err(reset_crashed_worker());
             }
         }
     }
@@ -360,6 +523,7 @@ pub struct BatchProgress {
     pub status: String,
     pub output_size: Option<u64>,
     pub reason: Option<String>,
+    pub write_mode: Option<String>,
 }
 
 pub struct BatchResult {
@@ -368,24 +532,32 @@ pub struct BatchResult {
     pub skipped: usize,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn compress_batch(
     app: &AppHandle,
     items: Vec<CompressBatchItemMsg>,
     resize_percent: f32,
     quality: u8,
     preserve_exif: bool,
+    verify_output: bool,
+    direct_write_threshold_bytes: Option<u64>,
+    max_concurrent_decodes: Option<usize>,
     is_cancelled: impl Fn() -> bool,
     on_file_done: impl FnMut(BatchProgress),
-) -> Result<BatchResult, String> {
-    ensure_worker(app)?;
-    let id = next_id()?;
+) -> Result<BatchResult, AppError> {
+    ensure_worker(app).map_err(AppError::Io)?;
+    let id = next_id();
     let rx = send_request(&WorkerRequest::CompressBatch {
         id: id.clone(),
         items,
         resize_percent,
         quality,
         preserve_exif,
-    })?;
+        verify_output,
+        direct_write_threshold_bytes,
+        max_concurrent_decodes,
+    })
+    .map_err(AppError::Io)?;
 
     let mut on_file_done = on_file_done;
 
@@ -400,6 +572,7 @@ pub fn compress_batch(
                 status,
                 output_size,
                 reason,
+                write_mode,
                 ..
             }) => {
                 on_file_done(BatchProgress {
@@ -408,6 +581,7 @@ pub fn compress_batch(
                     status,
                     output_size,
                     reason,
+                    write_mode,
                 });
             }
             Ok(WorkerResponse::CompressBatchDone {
@@ -423,14 +597,12 @@ pub fn compress_batch(
                 });
             }
             Ok(WorkerResponse::Error { message, .. }) => {
-                return Err(message);
+                return Err(AppError::Io(message));
             }
             Ok(_) => {}
             Err(mpsc::RecvTimeoutError::Timeout) => continue,
             Err(mpsc::RecvTimeoutError::Disconnected) => {
-                let mut guard = BRIDGE.lock().map_err(|e| e.to_string())?;
-                *guard = None;
-                return Err("ワーカープロセスが予期せず終了しました".to_string());
+                return Err(reset_crashed_worker());
             }
         }
     }
@@ -449,9 +621,9 @@ pub fn resize_batch(
     preserve_exif: bool,
     is_cancelled: impl Fn() -> bool,
     on_file_done: impl FnMut(BatchProgress),
-) -> Result<BatchResult, String> {
-    ensure_worker(app)?;
-    let id = next_id()?;
+) -> Result<BatchResult, AppError> {
+    ensure_worker(app).map_err(AppError::Io)?;
+    let id = next_id();
     let rx = send_request(&WorkerRequest::ResizeBatch {
         id: id.clone(),
         items,
@@ -462,7 +634,8 @@ pub fn resize_batch(
         sharpen,
         quality,
         preserve_exif,
-    })?;
+    })
+    .map_err(AppError::Io)?;
 
     let mut on_file_done = on_file_done;
 
@@ -485,6 +658,7 @@ pub fn resize_batch(
                     status,
                     output_size,
                     reason,
+                    write_mode: None,
                 });
             }
             Ok(WorkerResponse::ResizeBatchDone {
@@ -500,14 +674,12 @@ pub fn resize_batch(
                 });
             }
             Ok(WorkerResponse::Error { message, .. }) => {
-                return Err(message);
+                return Err(AppError::Io(message));
             }
             Ok(_) => {}
             Err(mpsc::RecvTimeoutError::Timeout) => continue,
             Err(mpsc::RecvTimeoutError::Disconnected) => {
-                let mut guard = BRIDGE.lock().map_err(|e| e.to_string())?;
-                *guard = None;
-                return Err("ワーカープロセスが予期せず終了しました".to_string());
+                return Err(reset_crashed_worker());
             }
         }
     }
@@ -7,6 +7,7 @@ import {
   cancelOperation,
   compressCollectInfo,
   compressEstimate,
+  describeError,
   executeCompress,
   executeDelete,
   executeExifOffset,
@@ -183,6 +184,7 @@ const DEFAULT_SETTINGS: AppSettings = {
   deletePatterns: [],
   renameTemplates: [{ name: "日付通番", template: "{capture_date:YYYYMMDD}_{capture_time:HHmmss}_{seq:3}" }],
   outputDirectories: {},
+  outputSuffixes: {},
   theme: "system"
 };
 
@@ -427,7 +429,7 @@ export function App() {
   const [renameSource, setRenameSource] = useState<RenameSource>("captureThenModified");
   const [renameOutputDir, setRenameOutputDir] = useState("");
   const [renameDuplicateOutput, setRenameDuplicateOutput] = useState(false);
-  const [renameConflictPolicy, setRenameConflictPolicy] = useState<"overwrite" | "sequence" | "skip">("sequence");
+  const [renameConflictPolicy, setRenameConflictPolicy] = useState<"overwrite" | "sequence" | "skip" | "prefixParent">("sequence");
   const [ffprobeAvailable, setFfprobeAvailable] = useState(false);
   const [useFfprobe, setUseFfprobe] = useState(false);
   const [renamePreview, setRenamePreview] = useState<RenamePreviewResponse | null>(null);
@@ -443,7 +445,7 @@ export function App() {
   const [deleteExtensions, setDeleteExtensions] = useState("");
   const [deleteMode, setDeleteMode] = useState<DeletePattern["mode"]>("trash");
   const [deleteRetreatDir, setDeleteRetreatDir] = useState("");
-  const [deleteConflictPolicy, setDeleteConflictPolicy] = useState<"overwrite" | "sequence" | "skip">("sequence");
+  const [deleteConflictPolicy, setDeleteConflictPolicy] = useState<"overwrite" | "sequence" | "skip" | "prefixParent">("sequence");
   const [deletePreview, setDeletePreview] = useState<DeletePreviewResponse | null>(null);
   const [deleteExec, setDeleteExec] = useState<DeleteExecuteResponse | null>(null);
   const [patternSelected, setPatternSelected] = useState("");
@@ -460,7 +462,7 @@ export function App() {
   const [compressTolerancePercent, setCompressTolerancePercent] = useState(10);
   const [compressPreserveExif, setCompressPreserveExif] = useState(true);
   const [compressOutputDir, setCompressOutputDir] = useState("");
-  const [compressConflictPolicy, setCompressConflictPolicy] = useState<"overwrite" | "sequence" | "skip">("sequence");
+  const [compressConflictPolicy, setCompressConflictPolicy] = useState<"overwrite" | "sequence" | "skip" | "prefixParent">("sequence");
   const [compressSourceInfo, setCompressSourceInfo] = useState<CompressCollectInfoResponse | null>(null);
   const [compressEstimateResult, setCompressEstimateResult] = useState<CompressEstimateResponse | null>(null);
   const [estimateProgress, setEstimateProgress] = useState<EstimateProgressEvent | null>(null);
@@ -470,7 +472,7 @@ export function App() {
 
   const [flattenInputDir, setFlattenInputDir] = useState("");
   const [flattenOutputDir, setFlattenOutputDir] = useState("");
-  const [flattenConflictPolicy, setFlattenConflictPolicy] = useState<"overwrite" | "sequence" | "skip">("sequence");
+  const [flattenConflictPolicy, setFlattenConflictPolicy] = useState<"overwrite" | "sequence" | "skip" | "prefixParent">("sequence");
   const [flattenPreview, setFlattenPreview] = useState<FlattenPreviewResponse | null>(null);
   const [flattenExec, setFlattenExec] = useState<FlattenExecuteResponse | null>(null);
 
@@ -503,7 +505,7 @@ export function App() {
   const [resizeQuality, setResizeQuality] = useState(90);
   const [resizePreserveExif, setResizePreserveExif] = useState(true);
   const [resizeOutputDir, setResizeOutputDir] = useState("");
-  const [resizeConflictPolicy, setResizeConflictPolicy] = useState<"overwrite" | "sequence" | "skip">("sequence");
+  const [resizeConflictPolicy, setResizeConflictPolicy] = useState<"overwrite" | "sequence" | "skip" | "prefixParent">("sequence");
   const [resizePreview, setResizePreview] = useState<ResizePreviewResponse | null>(null);
   const [resizeExec, setResizeExec] = useState<ResizeExecuteResponse | null>(null);
 
@@ -639,7 +641,7 @@ export function App() {
         setFlattenOutputDir(loadedSettings.outputDirectories.flatten ?? "");
         settingsLoadedRef.current = true;
       } catch (loadError) {
-        addToast("error", String(loadError));
+        addToast("error", describeError(loadError));
       }
     })();
 
@@ -692,7 +694,7 @@ export function App() {
       settingsRef.current = next;
       setSettings(next);
       void saveSettings(next).catch((saveError) => {
-        addToast("error", String(saveError));
+        addToast("error", describeError(saveError));
       });
     }, 400);
     return () => {
@@ -801,7 +803,7 @@ export function App() {
     try {
       await action();
     } catch (runError) {
-      addToast("error", String(runError));
+      addToast("error", describeError(runError));
     } finally {
       setIsBusy(false);
       setBusyLabel(null);
@@ -1436,7 +1438,7 @@ export function App() {
                   </div>
                   <div className="form-group">
                     <label className="form-label">競合時の処理</label>
-                    <select value={renameConflictPolicy} onChange={(event) => setRenameConflictPolicy(event.target.value as "overwrite" | "sequence" | "skip")}>
+                    <select value={renameConflictPolicy} onChange={(event) => setRenameConflictPolicy(event.target.value as "overwrite" | "sequence" | "skip" | "prefixParent")}>
                       <option value="overwrite">上書き</option>
                       <option value="sequence">連番付与</option>
                       <option value="skip">スキップ</option>
@@ -1699,10 +1701,11 @@ export function App() {
                   </div>
                   <div className="form-group">
                     <label className="form-label">競合時の処理</label>
-                    <select value={deleteConflictPolicy} disabled={deleteMode === "trash"} onChange={(event) => setDeleteConflictPolicy(event.target.value as "overwrite" | "sequence" | "skip")}>
+                    <select value={deleteConflictPolicy} disabled={deleteMode === "trash"} onChange={(event) => setDeleteConflictPolicy(event.target.value as "overwrite" | "sequence" | "skip" | "prefixParent")}>
                       <option value="overwrite">上書き</option>
                       <option value="sequence">連番付与</option>
                       <option value="skip">スキップ</option>
+                      <option value="prefixParent">親フォルダ名を付与</option>
                     </select>
                   </div>
                 </div>
@@ -1963,10 +1966,11 @@ export function App() {
                 </div>
                 <div className="form-group">
                   <label className="form-label">競合時の処理</label>
-                  <select value={compressConflictPolicy} onChange={(event) => setCompressConflictPolicy(event.target.value as "overwrite" | "sequence" | "skip")}>
+                  <select value={compressConflictPolicy} onChange={(event) => setCompressConflictPolicy(event.target.value as "overwrite" | "sequence" | "skip" | "prefixParent")}>
                     <option value="overwrite">上書き</option>
                     <option value="sequence">連番付与</option>
                     <option value="skip">スキップ</option>
+                    <option value="prefixParent">親フォルダ名を付与</option>
                   </select>
                 </div>
                 <div className="form-group">
@@ -2067,7 +2071,7 @@ export function App() {
                 <div className="result-summary">
                   <span className="result-summary-label">プレビュー</span>
                   <span className="badge badge-info">{compressPreview.ready}/{compressPreview.total} 件</span>
-                  {compressPreview.warnings > 0 ? <span className="badge badge-skip">警告 {compressPreview.warnings}</span> : null}
+                  {compressPreview.toleranceWarnings > 0 ? <span className="badge badge-skip">警告 {compressPreview.toleranceWarnings}</span> : null}
                   <span className="text-muted" style={{ fontSize: 12 }}>
                     リサイズ {compressPreview.effectiveResizePercent.toFixed(1)}% / 品質 {compressPreview.effectiveQuality}
                   </span>
@@ -2311,7 +2315,7 @@ export function App() {
 
                 <div className="form-group">
                   <label className="form-label">競合時の処理</label>
-                  <select value={resizeConflictPolicy} onChange={(event) => setResizeConflictPolicy(event.target.value as "overwrite" | "sequence" | "skip")}>
+                  <select value={resizeConflictPolicy} onChange={(event) => setResizeConflictPolicy(event.target.value as "overwrite" | "sequence" | "skip" | "prefixParent")}>
                     <option value="overwrite">上書き</option>
                     <option value="sequence">連番付与</option>
                     <option value="skip">スキップ</option>
@@ -2470,10 +2474,11 @@ export function App() {
                 </div>
                 <div className="form-group">
                   <label className="form-label">競合時の処理</label>
-                  <select value={flattenConflictPolicy} onChange={(event) => setFlattenConflictPolicy(event.target.value as "overwrite" | "sequence" | "skip")}>
+                  <select value={flattenConflictPolicy} onChange={(event) => setFlattenConflictPolicy(event.target.value as "overwrite" | "sequence" | "skip" | "prefixParent")}>
                     <option value="overwrite">上書き</option>
                     <option value="sequence">連番付与</option>
                     <option value="skip">スキップ</option>
+                    <option value="prefixParent">親フォルダ名を付与</option>
                   </select>
                 </div>
                 </div>
@@ -2815,13 +2820,16 @@ export function App() {
                         setMetadataStripCategories({ gps: true, cameraLens: true, software: false, authorCopyright: false, comments: true, thumbnail: true, iptc: false, xmp: false, shootingSettings: false, captureDateTime: false });
                       } else if (preset === "delivery") {
                         setMetadataStripCategories({ gps: false, cameraLens: true, software: true, authorCopyright: false, comments: true, thumbnail: false, iptc: false, xmp: false, shootingSettings: false, captureDateTime: false });
+                      } else if (preset === "privacyMinimal") {
+                        setMetadataStripCategories({ gps: true, cameraLens: false, software: false, authorCopyright: false, comments: true, thumbnail: false, iptc: false, xmp: false, shootingSettings: false, captureDateTime: false });
                       } else if (preset === "fullClean") {
-                        setMetadataStripCategories({ gps: true, cameraLens: true, software: true, authorCopyright: true, comments: true, thumbnail: true, iptc: true, xmp: true, shootingSettings: true, captureDateTime: true });
+                        setMetadataStripCategories({ gps: true, cameraLens: true, software: true, authorCopyright: true, comments: true, thumbnail: true, iptc: true, xmp: true, iccProfile: true, shootingSettings: true, captureDateTime: true });
                       }
                     }}
                   >
                     <option value="snsPublish">SNS公開用</option>
                     <option value="delivery">納品用</option>
+                    <option value="privacyMinimal">プライバシー最小</option>
                     <option value="fullClean">完全クリーン</option>
                     <option value="custom">カスタム</option>
                   </select>
@@ -2832,6 +2840,7 @@ export function App() {
                     {(
                       [
                         ["gps", "GPS/位置情報"],
+                        ["gpsBlur", "GPS精度を下げる(約1km)"],
                         ["cameraLens", "カメラ/レンズ情報"],
                         ["software", "作成ソフト/編集履歴"],
                         ["authorCopyright", "作者/著作権"],
@@ -2839,6 +2848,7 @@ export function App() {
                         ["thumbnail", "サムネイル(IFD1)"],
                         ["iptc", "IPTC(APP13)"],
                         ["xmp", "XMP(APP1)"],
+                        ["iccProfile", "ICCプロファイル(APP2)"],
                         ["shootingSettings", "撮影時設定"],
                         ["captureDateTime", "撮影日時"]
                       ] as [keyof MetadataStripCategories, string][]
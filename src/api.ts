@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   AppSettings,
+  CommandError,
   CompressCollectInfoResponse,
   CompressEstimateResponse,
   CompressExecuteResponse,
@@ -9,24 +10,43 @@ import type {
   DeleteExecuteResponse,
   DeletePreviewRequest,
   DeletePreviewResponse,
+  DuplicateDetectRequest,
+  DuplicateDetectResponse,
   ExifOffsetExecuteResponse,
   ExifOffsetPreviewRequest,
   ExifOffsetPreviewResponse,
+  ExifReadResult,
+  FileInfo,
+  FileInfoResult,
   FlattenExecuteResponse,
   FlattenPreviewRequest,
   FlattenPreviewResponse,
+  HistoryEntry,
   ImportConflictPreview,
+  ImportMergePreview,
   MetadataStripExecuteResponse,
+  MetadataStripPresetInfo,
   MetadataStripPreviewRequest,
   MetadataStripPreviewResponse,
+  OperationResultRow,
+  OrganizeByDateExecuteResponse,
+  OrganizeByDatePreviewResponse,
+  OrganizeByDateRequest,
+  PathValidation,
+  PreviewManifest,
   RenameExecuteResponse,
   RenamePreviewRequest,
   RenamePreviewResponse,
   RenameTemplateTag,
+  ReorientExecuteResponse,
+  ReorientPreviewRequest,
+  ReorientPreviewResponse,
   ResizeCollectInfoResponse,
   ResizeExecuteResponse,
   ResizePreviewRequest,
-  ResizePreviewResponse
+  ResizePreviewResponse,
+  TemplateWarning,
+  WatchFolderConfig
 } from "./types";
 
 export async function previewRename(
@@ -53,6 +73,18 @@ export async function executeDelete(
   return invoke<DeleteExecuteResponse>("execute_delete", { request: payload });
 }
 
+export async function previewDuplicateDetect(
+  payload: DuplicateDetectRequest
+): Promise<DuplicateDetectResponse> {
+  return invoke<DuplicateDetectResponse>("preview_duplicate_detect", { request: payload });
+}
+
+export async function executeDuplicateDetect(
+  payload: DuplicateDetectRequest
+): Promise<DeleteExecuteResponse> {
+  return invoke<DeleteExecuteResponse>("execute_duplicate_detect", { request: payload });
+}
+
 export async function previewFlatten(
   payload: FlattenPreviewRequest
 ): Promise<FlattenPreviewResponse> {
@@ -65,6 +97,22 @@ export async function executeFlatten(
   return invoke<FlattenExecuteResponse>("execute_flatten", { request: payload });
 }
 
+export async function executeFlattenUndo(manifestPath: string): Promise<FlattenExecuteResponse> {
+  return invoke<FlattenExecuteResponse>("execute_flatten_undo", { manifestPath });
+}
+
+export async function previewOrganizeByDate(
+  payload: OrganizeByDateRequest
+): Promise<OrganizeByDatePreviewResponse> {
+  return invoke<OrganizeByDatePreviewResponse>("preview_organize_by_date", { request: payload });
+}
+
+export async function executeOrganizeByDate(
+  payload: OrganizeByDateRequest
+): Promise<OrganizeByDateExecuteResponse> {
+  return invoke<OrganizeByDateExecuteResponse>("execute_organize_by_date", { request: payload });
+}
+
 export async function compressCollectInfo(
   inputPaths: string[],
   includeSubfolders: boolean
@@ -98,6 +146,19 @@ export async function executeCompress(
   return invoke<CompressExecuteResponse>("execute_compress", { request: payload });
 }
 
+export async function exportCompressBatchManifest(
+  payload: CompressPreviewRequest,
+  outputPath: string
+): Promise<void> {
+  await invoke("export_compress_batch_manifest", { request: payload, outputPath });
+}
+
+export async function replayCompressFromManifest(
+  manifestPath: string
+): Promise<CompressExecuteResponse> {
+  return invoke<CompressExecuteResponse>("replay_compress_from_manifest", { manifestPath });
+}
+
 export async function previewExifOffset(
   payload: ExifOffsetPreviewRequest
 ): Promise<ExifOffsetPreviewResponse> {
@@ -110,6 +171,10 @@ export async function executeExifOffset(
   return invoke<ExifOffsetExecuteResponse>("execute_exif_offset", { request: payload });
 }
 
+export async function batchGetExif(paths: string[], tags: number[]): Promise<ExifReadResult[]> {
+  return invoke<ExifReadResult[]>("batch_get_exif", { paths, tags });
+}
+
 export async function previewMetadataStrip(
   payload: MetadataStripPreviewRequest
 ): Promise<MetadataStripPreviewResponse> {
@@ -122,6 +187,26 @@ export async function executeMetadataStrip(
   return invoke<MetadataStripExecuteResponse>("execute_metadata_strip", { request: payload });
 }
 
+export async function previewReorient(
+  payload: ReorientPreviewRequest
+): Promise<ReorientPreviewResponse> {
+  return invoke<ReorientPreviewResponse>("preview_reorient", { request: payload });
+}
+
+export async function executeReorient(
+  payload: ReorientPreviewRequest
+): Promise<ReorientExecuteResponse> {
+  return invoke<ReorientExecuteResponse>("execute_reorient", { request: payload });
+}
+
+export async function startWatchFolder(config: WatchFolderConfig): Promise<string> {
+  return invoke<string>("start_watch_folder", { config });
+}
+
+export async function stopWatchFolder(id: string): Promise<void> {
+  return invoke<void>("stop_watch_folder", { id });
+}
+
 export async function resizeCollectInfo(
   inputPaths: string[],
   includeSubfolders: boolean
@@ -145,10 +230,38 @@ export async function cancelOperation(): Promise<void> {
   await invoke("cancel_operation");
 }
 
+export async function loadOperationHistory(limit: number): Promise<HistoryEntry[]> {
+  return invoke<HistoryEntry[]>("load_operation_history", { limit });
+}
+
+export async function clearOperationHistory(): Promise<void> {
+  await invoke("clear_operation_history");
+}
+
 export async function loadSettings(): Promise<AppSettings> {
   return invoke<AppSettings>("load_settings");
 }
 
+export async function getDefaultOutputDir(operation: string): Promise<string | null> {
+  return invoke<string | null>("get_default_output_dir", { operation });
+}
+
+export async function getInvalidOutputDirectories(): Promise<string[]> {
+  return invoke<string[]>("get_invalid_output_directories");
+}
+
+export async function resetSettings(): Promise<AppSettings> {
+  return invoke<AppSettings>("reset_settings");
+}
+
+export async function resetSettingsSection(section: string): Promise<AppSettings> {
+  return invoke<AppSettings>("reset_settings_section", { section });
+}
+
+export async function listRenameTemplateGroups(): Promise<string[]> {
+  return invoke<string[]>("list_rename_template_groups");
+}
+
 export async function saveSettings(settings: AppSettings): Promise<void> {
   await invoke("save_settings", { settings });
 }
@@ -161,6 +274,32 @@ export async function exportSettings(outputPath: string): Promise<void> {
   await invoke("export_settings", { outputPath });
 }
 
+export async function exportOperationResultCsv(
+  details: OperationResultRow[],
+  outputPath: string
+): Promise<void> {
+  await invoke("export_operation_result_csv", { details, outputPath });
+}
+
+export async function exportOperationResultJson(
+  details: OperationResultRow[],
+  outputPath: string
+): Promise<void> {
+  await invoke("export_operation_result_json", { details, outputPath });
+}
+
+export async function exportPreviewManifest(
+  manifestType: string,
+  items: unknown[],
+  outputPath: string
+): Promise<void> {
+  await invoke("export_preview_manifest", { manifestType, items, outputPath });
+}
+
+export async function importPreviewManifest(inputPath: string): Promise<PreviewManifest> {
+  return invoke<PreviewManifest>("import_preview_manifest", { inputPath });
+}
+
 export async function importSettings(
   inputPath: string,
   mode: "overwrite" | "merge",
@@ -175,6 +314,10 @@ export async function previewImportConflicts(
   return invoke<ImportConflictPreview>("preview_import_conflicts", { inputPath });
 }
 
+export async function previewImportMerge(inputPath: string): Promise<ImportMergePreview> {
+  return invoke<ImportMergePreview>("preview_import_merge", { inputPath });
+}
+
 export async function openSettingsFolder(): Promise<void> {
   await invoke("open_settings_folder");
 }
@@ -187,6 +330,61 @@ export async function listRenameTemplateTags(): Promise<RenameTemplateTag[]> {
   return invoke<RenameTemplateTag[]>("list_rename_template_tags");
 }
 
+export async function listTemplateWarnings(template: string): Promise<TemplateWarning[]> {
+  return invoke<TemplateWarning[]>("list_template_warnings", { template });
+}
+
+export async function sortPreviewItems<T>(
+  items: T[],
+  field: "sourcePath" | "destinationPath" | "status" | "reason" | "sourceSize",
+  ascending: boolean
+): Promise<T[]> {
+  return invoke<T[]>("sort_preview_items", { items, field, ascending });
+}
+
+export async function listMetadataStripPresets(): Promise<MetadataStripPresetInfo[]> {
+  return invoke<MetadataStripPresetInfo[]>("list_metadata_strip_presets");
+}
+
+export async function getFileInfo(path: string): Promise<FileInfo> {
+  return invoke<FileInfo>("get_file_info", { path });
+}
+
+export async function batchGetFileInfo(paths: string[]): Promise<FileInfoResult[]> {
+  return invoke<FileInfoResult[]>("batch_get_file_info", { paths });
+}
+
+export async function validatePaths(paths: string[]): Promise<PathValidation[]> {
+  return invoke<PathValidation[]>("validate_paths", { paths });
+}
+
+export async function listSupportedExtensions(): Promise<Record<string, string[] | null>> {
+  return invoke<Record<string, string[] | null>>("list_supported_extensions");
+}
+
+export async function getCommonParent(paths: string[]): Promise<string | null> {
+  return invoke<string | null>("get_common_parent", { paths });
+}
+
 export async function isDirectoryPath(path: string): Promise<boolean> {
   return invoke<boolean>("is_directory_path", { path });
 }
+
+export function parseCommandError(error: unknown): CommandError | null {
+  if (typeof error !== "string") {
+    return null;
+  }
+  try {
+    const parsed = JSON.parse(error) as Partial<CommandError>;
+    if (typeof parsed.code === "string" && typeof parsed.message === "string") {
+      return parsed as CommandError;
+    }
+  } catch {
+    // Plain string error from a command that does not use AppError.
+  }
+  return null;
+}
+
+export function describeError(error: unknown): string {
+  return parseCommandError(error)?.message ?? String(error);
+}
@@ -1,14 +1,26 @@
 export type RenameSource = "captureThenModified" | "modifiedOnly" | "currentTime";
 
+export type UnicodeNorm = "nfc" | "nfd" | "none";
+
+export type SequenceSortBy = "fileName" | "captureDateTime" | "fileModified";
+
 export interface RenamePreviewRequest {
   inputPaths: string[];
   includeSubfolders: boolean;
+  maxDepth?: number | null;
+  maxFiles?: number | null;
   template: string;
   source: RenameSource;
   outputDir?: string | null;
   duplicateOutput?: boolean | null;
-  conflictPolicy?: "overwrite" | "sequence" | "skip" | null;
+  conflictPolicy?: "overwrite" | "sequence" | "skip" | "prefixParent" | null;
   useFfprobe?: boolean | null;
+  unicodeNormalize?: UnicodeNorm | null;
+  dryRun?: boolean | null;
+  sequenceSortBy?: SequenceSortBy | null;
+  preserveExtensionCase?: boolean | null;
+  warnOnAnomalousDatetime?: boolean | null;
+  failOnPermissionDenied?: boolean | null;
 }
 
 export interface RenamePreviewItem {
@@ -23,6 +35,10 @@ export interface RenamePreviewResponse {
   total: number;
   ready: number;
   skipped: number;
+  anomalousDatetimeCount: number;
+  timestampSourceCounts: Record<string, number>;
+  sourceByExtension: ExtensionCount[];
+  warnings: string[];
 }
 
 export interface RenameExecuteDetail {
@@ -32,11 +48,19 @@ export interface RenameExecuteDetail {
   reason?: string | null;
 }
 
-export interface RenameExecuteResponse {
+export interface OperationSummary {
+  operation: string;
   processed: number;
   succeeded: number;
   failed: number;
   skipped: number;
+  elapsedMs?: number | null;
+  canceled: boolean;
+  warnings: string[];
+}
+
+export interface RenameExecuteResponse extends OperationSummary {
+  copyFallbackCount: number;
   details: RenameExecuteDetail[];
 }
 
@@ -46,9 +70,15 @@ export interface RenameTemplateTag {
   description: string;
 }
 
+export interface TemplateWarning {
+  code: "low_uniqueness" | "static_template";
+  message: string;
+}
+
 export interface RenameTemplate {
   name: string;
   template: string;
+  group?: string | null;
 }
 
 export interface DeletePattern {
@@ -61,10 +91,19 @@ export interface DeletePattern {
 export interface DeletePreviewRequest {
   inputPaths: string[];
   includeSubfolders: boolean;
+  maxDepth?: number | null;
+  maxFiles?: number | null;
   extensions: string[];
   mode: "direct" | "trash" | "retreat";
   retreatDir?: string | null;
-  conflictPolicy?: "overwrite" | "sequence" | "skip" | null;
+  conflictPolicy?: "overwrite" | "sequence" | "skip" | "prefixParent" | null;
+  dryRun?: boolean | null;
+  globPatterns?: string[] | null;
+  removeEmptyDirs?: boolean | null;
+  preserveDirectoryStructure?: boolean | null;
+  failOnPermissionDenied?: boolean | null;
+  modifiedBefore?: string | null;
+  modifiedAfter?: string | null;
 }
 
 export interface DeletePreviewItem {
@@ -80,6 +119,8 @@ export interface DeletePreviewResponse {
   total: number;
   ready: number;
   skipped: number;
+  sourceByExtension: ExtensionCount[];
+  warnings: string[];
 }
 
 export interface DeleteExecuteDetail {
@@ -90,25 +131,38 @@ export interface DeleteExecuteDetail {
   reason?: string | null;
 }
 
-export interface DeleteExecuteResponse {
-  processed: number;
-  succeeded: number;
-  failed: number;
-  skipped: number;
+export interface DeleteExecuteResponse extends OperationSummary {
+  copyFallbackCount: number;
   details: DeleteExecuteDetail[];
 }
 
 export interface FlattenPreviewRequest {
   inputDir: string;
+  inputDirs?: string[] | null;
   outputDir?: string | null;
-  conflictPolicy: "overwrite" | "sequence" | "skip";
-}
+  conflictPolicy: "overwrite" | "sequence" | "skip" | "prefixParent";
+  unicodeNormalize?: UnicodeNorm | null;
+  dryRun?: boolean | null;
+  undoManifestPath?: string | null;
+  preserveSidecarPairing?: boolean | null;
+  outputSuffix?: string | null;
+  includeHidden?: boolean | null;
+  includeSystemFiles?: boolean | null;
+  inPlace?: boolean | null;
+  maxFiles?: number | null;
+  groupByDate?: DateGroupBy | null;
+  dateGroupFormat?: string | null;
+}
+
+export type DateGroupBy = "none" | "byModified" | "byCaptureDate";
 
 export interface FlattenPreviewItem {
   sourcePath: string;
   destinationPath: string;
   status: "ready" | "skipped";
   reason?: string | null;
+  isSidecarOf?: string | null;
+  action: "copy" | "move";
 }
 
 export interface FlattenPreviewResponse {
@@ -118,6 +172,8 @@ export interface FlattenPreviewResponse {
   ready: number;
   skipped: number;
   collisions: number;
+  hiddenFilesSkipped: number;
+  warnings: string[];
 }
 
 export interface FlattenExecuteDetail {
@@ -127,48 +183,79 @@ export interface FlattenExecuteDetail {
   reason?: string | null;
 }
 
-export interface FlattenExecuteResponse {
+export interface FlattenExecuteResponse extends OperationSummary {
   outputDir: string;
-  processed: number;
-  succeeded: number;
-  failed: number;
-  skipped: number;
   details: FlattenExecuteDetail[];
 }
 
 export interface CompressCollectInfoResponse {
   fileCount: number;
   totalSize: number;
+  byExtension: ExtensionCount[];
+}
+
+export interface ExtensionCount {
+  extension: string;
+  count: number;
+  totalSize: number;
 }
 
 export interface CompressEstimateResponse {
   fileCount: number;
   totalSourceSize: number;
   estimatedTotalSize: number;
+  perFileEstimates: PerFileEstimate[] | null;
+  estimateConfidence?: number | null;
+  samplesUsed: number;
+  samplingStrategy: "uniform" | "stratified";
+}
+
+export interface PerFileEstimate {
+  path: string;
+  sourceSize: number;
+  estimatedSize: number | null;
 }
 
 export interface EstimateProgressEvent {
   current: number;
   total: number;
+  partialRatio?: number | null;
 }
 
 export interface CompressPreviewRequest {
   inputPaths: string[];
   includeSubfolders: boolean;
+  maxFiles?: number | null;
   resizePercent: number;
   quality: number;
   targetSizeKb?: number | null;
   tolerancePercent?: number | null;
   preserveExif: boolean;
   outputDir?: string | null;
-  conflictPolicy: "overwrite" | "sequence" | "skip";
+  conflictPolicy: "overwrite" | "sequence" | "skip" | "prefixParent";
+  dryRun?: boolean | null;
+  minSourceSizeKb?: number | null;
+  verifyOutput?: boolean | null;
+  itemTimeoutMs?: number | null;
+  useDirectWriteThresholdBytes?: number | null;
+  skipIfCompressed?: boolean | null;
+  outputSuffix?: string | null;
+  keepOriginal?: boolean | null;
+  aspectRatioFilter?: AspectRatioFilter | null;
 }
 
+export type AspectRatioFilter = "portrait" | "landscape" | "square" | "all";
+
 export interface CompressPreviewItem {
   sourcePath: string;
   destinationPath: string;
   sourceSize: number;
   estimatedSize: number;
+  sourceWidth?: number | null;
+  sourceHeight?: number | null;
+  outputWidth?: number | null;
+  outputHeight?: number | null;
+  perFileTargetKb?: number | null;
   status: "ready" | "skipped";
   reason?: string | null;
 }
@@ -183,7 +270,17 @@ export interface CompressPreviewResponse {
   total: number;
   ready: number;
   skipped: number;
-  warnings: number;
+  toleranceWarnings: number;
+  filteredBySize: number;
+  filteredByAspect: number;
+  suggestIterations?: SuggestIteration[] | null;
+  warnings: string[];
+}
+
+export interface SuggestIteration {
+  resizePercent: number;
+  estimatedRatio: number;
+  estimatedBytes: number;
 }
 
 export interface CompressExecuteDetail {
@@ -192,31 +289,35 @@ export interface CompressExecuteDetail {
   status: "succeeded" | "failed" | "skipped";
   outputSize?: number | null;
   reason?: string | null;
+  writeMode: "direct" | "atomic" | "";
 }
 
-export interface CompressExecuteResponse {
+export interface CompressExecuteResponse extends OperationSummary {
   outputDir: string;
   effectiveResizePercent: number;
   effectiveQuality: number;
-  processed: number;
-  succeeded: number;
-  failed: number;
-  skipped: number;
   details: CompressExecuteDetail[];
 }
 
 export interface ExifOffsetPreviewRequest {
   inputPaths: string[];
   includeSubfolders: boolean;
+  maxFiles?: number | null;
   offsetSeconds: number;
+  offsetSubseconds?: string | null;
+  dryRun?: boolean | null;
+  outputDir?: string | null;
+  conflictPolicy?: "overwrite" | "sequence" | "skip" | "prefixParent" | null;
 }
 
 export interface ExifOffsetPreviewItem {
   sourcePath: string;
   originalDatetime?: string | null;
+  sourceTag?: "DateTimeOriginal" | "DateTimeDigitized" | "DateTime" | null;
   correctedDatetime?: string | null;
   status: "ready" | "skipped";
   reason?: string | null;
+  suspectedAnomaly: boolean;
 }
 
 export interface ExifOffsetPreviewResponse {
@@ -224,24 +325,24 @@ export interface ExifOffsetPreviewResponse {
   total: number;
   ready: number;
   skipped: number;
+  sortedByGap?: ExifOffsetPreviewItem[] | null;
+  warnings: string[];
 }
 
 export interface ExifOffsetExecuteDetail {
   sourcePath: string;
+  destinationPath?: string | null;
   status: "succeeded" | "failed" | "skipped";
   reason?: string | null;
 }
 
-export interface ExifOffsetExecuteResponse {
-  processed: number;
-  succeeded: number;
-  failed: number;
-  skipped: number;
+export interface ExifOffsetExecuteResponse extends OperationSummary {
   details: ExifOffsetExecuteDetail[];
 }
 
 export interface MetadataStripCategories {
   gps: boolean;
+  gpsBlur?: boolean;
   cameraLens: boolean;
   software: boolean;
   authorCopyright: boolean;
@@ -249,17 +350,32 @@ export interface MetadataStripCategories {
   thumbnail: boolean;
   iptc: boolean;
   xmp: boolean;
+  iccProfile?: boolean;
   shootingSettings: boolean;
   captureDateTime: boolean;
 }
 
-export type MetadataStripPreset = "snsPublish" | "delivery" | "fullClean" | "custom";
+export type MetadataStripPreset =
+  | "snsPublish"
+  | "delivery"
+  | "privacyMinimal"
+  | "fullClean"
+  | "custom";
+
+export interface MetadataStripPresetInfo {
+  id: MetadataStripPreset;
+  label: string;
+  description: string;
+  defaultCategories: MetadataStripCategories;
+}
 
 export interface MetadataStripPreviewRequest {
   inputPaths: string[];
   includeSubfolders: boolean;
+  maxFiles?: number | null;
   preset: MetadataStripPreset;
   categories: MetadataStripCategories;
+  dryRun?: boolean | null;
 }
 
 export interface MetadataStripPreviewItem {
@@ -268,6 +384,8 @@ export interface MetadataStripPreviewItem {
   tagsToStrip: number;
   hasIptc: boolean;
   hasXmp: boolean;
+  hasIccProfile: boolean;
+  hasMpf: boolean;
   status: "ready" | "skipped";
   reason?: string | null;
 }
@@ -277,6 +395,7 @@ export interface MetadataStripPreviewResponse {
   total: number;
   ready: number;
   skipped: number;
+  warnings: string[];
 }
 
 export interface MetadataStripExecuteDetail {
@@ -284,21 +403,19 @@ export interface MetadataStripExecuteDetail {
   strippedTags: number;
   strippedIptc: boolean;
   strippedXmp: boolean;
+  strippedIccProfile: boolean;
   status: "succeeded" | "failed" | "skipped";
   reason?: string | null;
 }
 
-export interface MetadataStripExecuteResponse {
-  processed: number;
-  succeeded: number;
-  failed: number;
-  skipped: number;
+export interface MetadataStripExecuteResponse extends OperationSummary {
   details: MetadataStripExecuteDetail[];
 }
 
 export interface ResizePreviewRequest {
   inputPaths: string[]
   includeSubfolders: boolean
+  maxFiles?: number | null
   sizePx: number
   mode: "long_side" | "short_side"
   smallImagePolicy: "skip" | "copy" | "upscale"
@@ -307,7 +424,7 @@ export interface ResizePreviewRequest {
   quality: number
   preserveExif: boolean
   outputDir?: string | null
-  conflictPolicy: "overwrite" | "sequence" | "skip"
+  conflictPolicy: "overwrite" | "sequence" | "skip" | "prefixParent"
 }
 
 export interface ResizePreviewItem {
@@ -327,6 +444,7 @@ export interface ResizePreviewResponse {
   total: number
   ready: number
   skipped: number
+  warnings: string[]
 }
 
 export interface ResizeCollectInfoResponse {
@@ -334,15 +452,22 @@ export interface ResizeCollectInfoResponse {
   totalSize: number
 }
 
-export interface ResizeExecuteResponse {
+export interface ResizeExecuteResponse extends OperationSummary {
   outputDir: string
-  succeeded: number
-  failed: number
-  skipped: number
 }
 
 export interface OperationProgressEvent {
-  operation: "rename" | "delete" | "flatten" | "compress" | "exifOffset" | "metadataStrip" | "resize";
+  operation:
+    | "rename"
+    | "delete"
+    | "duplicateDetect"
+    | "flatten"
+    | "organizeByDate"
+    | "compress"
+    | "exifOffset"
+    | "metadataStrip"
+    | "reorient"
+    | "resize";
   processed: number;
   total: number;
   succeeded: number;
@@ -351,13 +476,223 @@ export interface OperationProgressEvent {
   currentPath?: string | null;
   done: boolean;
   canceled: boolean;
+  etaSeconds?: number | null;
+  bytesProcessed: number;
+  totalBytes: number;
+}
+
+export type HashAlgorithm = "sha256" | "md5";
+
+export type DuplicateAction = "deleteKeepFirst" | "retreat" | "reportOnly";
+
+export interface DuplicateDetectRequest {
+  inputPaths: string[];
+  includeSubfolders: boolean;
+  maxFiles?: number | null;
+  hashAlgorithm: HashAlgorithm;
+  action: DuplicateAction;
+  retreatDir?: string | null;
+  conflictPolicy?: "overwrite" | "sequence" | "skip" | "prefixParent" | null;
+  dryRun?: boolean | null;
+}
+
+export interface DuplicateGroup {
+  hash: string;
+  fileSize: number;
+  files: string[];
+  wastedBytes: number;
+}
+
+export interface DuplicateDetectResponse {
+  duplicateGroups: DuplicateGroup[];
+  totalFiles: number;
+  duplicateFiles: number;
+  wastedBytes: number;
+  unreadable: number;
+  warnings: string[];
+}
+
+export interface OrganizeByDateRequest {
+  inputPaths: string[];
+  includeSubfolders: boolean;
+  maxFiles?: number | null;
+  outputRoot: string;
+  dateFormat: string;
+  source: RenameSource;
+  conflictPolicy: "overwrite" | "sequence" | "skip" | "prefixParent";
+  useFfprobe?: boolean | null;
+  dryRun?: boolean | null;
+}
+
+export interface OrganizeByDatePreviewItem {
+  sourcePath: string;
+  destinationPath?: string | null;
+  resolvedDate?: string | null;
+  status: "ready" | "skipped";
+  reason?: string | null;
+}
+
+export interface OrganizeByDatePreviewResponse {
+  outputRoot: string;
+  items: OrganizeByDatePreviewItem[];
+  total: number;
+  ready: number;
+  skipped: number;
+  warnings: string[];
+}
+
+export interface OrganizeByDateExecuteDetail {
+  sourcePath: string;
+  destinationPath?: string | null;
+  status: "succeeded" | "failed" | "skipped";
+  reason?: string | null;
+}
+
+export interface OrganizeByDateExecuteResponse extends OperationSummary {
+  outputRoot: string;
+  details: OrganizeByDateExecuteDetail[];
+}
+
+export interface ReorientPreviewRequest {
+  inputPaths: string[];
+  includeSubfolders: boolean;
+  maxFiles?: number | null;
+  conflictPolicy: "overwrite" | "sequence" | "skip" | "prefixParent";
+  outputDir?: string | null;
+  quality?: number | null;
+  losslessIfPossible?: boolean | null;
+}
+
+export interface ReorientPreviewItem {
+  sourcePath: string;
+  destinationPath: string;
+  orientation?: number | null;
+  status: "ready" | "skipped";
+  reason?: string | null;
+}
+
+export interface ReorientPreviewResponse {
+  items: ReorientPreviewItem[];
+  total: number;
+  ready: number;
+  skipped: number;
+  warnings: string[];
+}
+
+export interface ReorientExecuteDetail {
+  sourcePath: string;
+  destinationPath: string;
+  originalOrientation: number;
+  appliedTransform: string;
+  status: "succeeded" | "failed" | "skipped";
+  reason?: string | null;
+  lossless: boolean;
+  pixelsTrimmed?: number | null;
+}
+
+export interface ReorientExecuteResponse extends OperationSummary {
+  details: ReorientExecuteDetail[];
+}
+
+export type WatchOperation = "rename" | "compress" | "metadataStrip";
+
+export interface WatchFolderConfig {
+  folderPath: string;
+  includeSubfolders: boolean;
+  extensions: string[];
+  operation: WatchOperation;
+  renameRequest?: RenamePreviewRequest | null;
+  compressRequest?: CompressPreviewRequest | null;
+  metadataStripRequest?: MetadataStripPreviewRequest | null;
+}
+
+export interface WatchFolderEvent {
+  watchId: string;
+  filePath: string;
+  operation: WatchOperation;
+  status: "succeeded" | "failed" | "skipped";
+  reason?: string | null;
+}
+
+export interface OperationResultRow {
+  sourcePath: string;
+  destinationPath?: string | null;
+  status: "succeeded" | "failed" | "skipped";
+  reason?: string | null;
+}
+
+export interface PreviewManifest {
+  manifestVersion: number;
+  manifestType: string;
+  generatedAt: string;
+  items: unknown[];
+}
+
+export interface CompressBatchManifest {
+  cfmVersion: string;
+  generatedAt: string;
+  request: CompressPreviewRequest;
+}
+
+export interface HistoryEntry {
+  id: string;
+  operation: string;
+  timestamp: string;
+  processed: number;
+  succeeded: number;
+  failed: number;
+  skipped: number;
+  canceled: boolean;
+  cancelReason?: string | null;
+  elapsedMs?: number | null;
+  parameters: unknown;
+}
+
+export interface ExifReadResult {
+  path: string;
+  fields: Record<number, string>;
+  error?: string | null;
+}
+
+export interface FileInfo {
+  path: string;
+  sizeBytes: number;
+  modifiedAt: string;
+  createdAt?: string | null;
+  imageWidth?: number | null;
+  imageHeight?: number | null;
+  isDirectory: boolean;
+}
+
+export interface FileInfoResult {
+  path: string;
+  info?: FileInfo | null;
+  error?: string | null;
+}
+
+export interface PathValidation {
+  path: string;
+  exists: boolean;
+  isFile: boolean;
+  isDirectory: boolean;
+  isReadable: boolean;
+  isWritable: boolean;
+  sizeBytes?: number | null;
+}
+
+export interface CommandError {
+  code: "invalidRequest" | "io" | "settings" | "cancelled" | "permissionDenied" | "workerCrashed";
+  message: string;
 }
 
 export interface AppSettings {
   deletePatterns: DeletePattern[];
   renameTemplates: RenameTemplate[];
   outputDirectories: Record<string, string>;
+  outputSuffixes: Record<string, string>;
   theme: "system" | "light" | "dark";
+  compressMaxConcurrentDecodes?: number | null;
+  metadataScanThreads?: number | null;
 }
 
 export interface ImportConflictPreview {
@@ -366,3 +701,31 @@ export interface ImportConflictPreview {
   outputDirectoryKeys: string[];
   themeConflict: boolean;
 }
+
+export interface PatternDiff {
+  name: string;
+  existing: DeletePattern;
+  imported: DeletePattern;
+  extensionsChanged: boolean;
+  modeChanged: boolean;
+}
+
+export interface TemplateDiff {
+  name: string;
+  existing: RenameTemplate;
+  imported: RenameTemplate;
+  templateChanged: boolean;
+  groupChanged: boolean;
+}
+
+export interface OutputDirDiff {
+  key: string;
+  existingValue: string;
+  importedValue: string;
+}
+
+export interface ImportMergePreview {
+  patternDiffs: PatternDiff[];
+  templateDiffs: TemplateDiff[];
+  outputDirDiffs: OutputDirDiff[];
+}